@@ -0,0 +1,40 @@
+// Débit de GET /chain, requêtes servies par le routeur sans passer par le réseau
+//
+// Lancer avec `cargo bench --bench api`.
+
+mod support;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mini_blockchain::api::{self, AppState};
+use mini_blockchain::config::NodeConfig;
+use tower::ServiceExt;
+
+fn bench_get_chain(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("get_chain");
+    group.throughput(Throughput::Elements(1));
+    for len in [100, 1_000, 10_000] {
+        let app = runtime.block_on(async {
+            let state = AppState::new(support::build_chain(len, 1));
+            api::router(state, &NodeConfig::default())
+        });
+
+        group.bench_with_input(BenchmarkId::from_parameter(len), &app, |b, app| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let request = Request::get("/chain").body(Body::empty()).unwrap();
+                    let response = app.clone().oneshot(request).await.unwrap();
+                    assert_eq!(response.status(), StatusCode::OK);
+                    to_bytes(response.into_body(), usize::MAX).await.unwrap()
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_chain);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+// Calcul du hash d'un bloc selon la taille de ses données : fonction de
+// référence et état SHA-256 précalculé utilisé par le mining. Coût d'un essai
+// Argon2id, indépendant de la taille des données.
+//
+// Lancer avec `cargo bench --bench hashing`.
+
+mod support;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mini_blockchain::proof_of_work::{Argon2Hasher, ARGON2_MEMORY_KIB};
+use support::SeededRng;
+
+fn bench_calculate_hash(c: &mut Criterion) {
+    let mut rng = SeededRng::new(support::SEED);
+    let mut group = c.benchmark_group("calculate_hash");
+    for len in [64, 1024, 16 * 1024] {
+        let mut block = support::block(1, &"0".repeat(64), &mut rng);
+        block.data = rng.payload(len);
+
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("reference", len), &block, |b, block| {
+            b.iter(|| block.calculate_hash())
+        });
+
+        // Chemin du mining : en-tête haché une fois, seul le nonce change
+        let midstate = block.midstate();
+        group.bench_with_input(
+            BenchmarkId::new("midstate", len),
+            &midstate,
+            |b, midstate| b.iter(|| midstate.digest(block.nonce)),
+        );
+    }
+    group.finish();
+}
+
+fn bench_argon2id(c: &mut Criterion) {
+    let mut hasher = Argon2Hasher::new();
+    let header_hash = [0x5au8; 32];
+
+    let mut group = c.benchmark_group("argon2id");
+    group.sample_size(20);
+    group.throughput(Throughput::Bytes(u64::from(ARGON2_MEMORY_KIB) * 1024));
+    group.bench_function("digest", |b| b.iter(|| hasher.digest(&header_hash)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_calculate_hash, bench_argon2id);
+criterion_main!(benches);
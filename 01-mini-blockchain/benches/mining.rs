@@ -0,0 +1,43 @@
+// Mining d'un même bloc aux difficultés 1 à 4 (SHA-256) et 1 à 2 (Argon2id)
+//
+// Le bloc est fixe (horloge et données seedées) : le nonce trouvé, donc le
+// nombre de hashes par itération, est identique d'une exécution à l'autre.
+// Lancer avec `cargo bench --bench mining`.
+
+mod support;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use mini_blockchain::proof_of_work::{mine_block, PowAlgorithm};
+use support::SeededRng;
+
+fn bench_mine_block(c: &mut Criterion) {
+    let mut rng = SeededRng::new(support::SEED);
+    let block = support::block(1, &"0".repeat(64), &mut rng);
+
+    let mut group = c.benchmark_group("mine_block");
+    group.sample_size(10);
+    for (algorithm, max_difficulty) in [(PowAlgorithm::Sha256, 4), (PowAlgorithm::Argon2id, 2)] {
+        let mut block = block.clone();
+        block.algorithm = algorithm;
+        for difficulty in 1..=max_difficulty {
+            group.bench_with_input(
+                BenchmarkId::new(algorithm.to_string(), difficulty),
+                &difficulty,
+                |b, &difficulty| {
+                    b.iter_batched(
+                        || block.clone(),
+                        |mut block| {
+                            mine_block(&mut block, difficulty);
+                            block
+                        },
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mine_block);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+// Données reproductibles des benchmarks : horloge fixe et générateur seedé
+//
+// Deux exécutions construisent exactement les mêmes blocs (mêmes horodatages,
+// mêmes données, donc mêmes nonces) : les mesures restent comparables d'une
+// version à l'autre.
+
+// Chaque benchmark n'utilise qu'une partie de ces aides
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use mini_blockchain::checkpoints::Checkpoints;
+use mini_blockchain::consensus;
+use mini_blockchain::proof_of_work::mine_block;
+use mini_blockchain::{Block, Blockchain};
+
+/// Graine du générateur de données
+pub const SEED: u64 = 0x5eed_b10c;
+
+/// Horodatage du bloc genesis (horloge fixe)
+pub const EPOCH: i64 = 1_700_000_000;
+
+/// Écart entre deux blocs consécutifs, en secondes
+pub const BLOCK_INTERVAL: i64 = 10;
+
+/// Taille des données de chaque bloc généré
+pub const PAYLOAD_LEN: usize = 64;
+
+/// Générateur pseudo-aléatoire déterministe (SplitMix64)
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Chaîne alphanumérique de `len` caractères
+    pub fn payload(&mut self, len: usize) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+        (0..len)
+            .map(|_| ALPHABET[(self.next_u64() % ALPHABET.len() as u64) as usize] as char)
+            .collect()
+    }
+}
+
+/// Horodatage du bloc `index` selon l'horloge fixe
+pub fn timestamp(index: u64) -> DateTime<Utc> {
+    DateTime::from_timestamp(EPOCH + index as i64 * BLOCK_INTERVAL, 0).unwrap()
+}
+
+/// Bloc non miné à la hauteur `index`, données tirées de `rng`
+pub fn block(index: u64, previous_hash: &str, rng: &mut SeededRng) -> Block {
+    Block::with_timestamp(
+        index,
+        rng.payload(PAYLOAD_LEN),
+        previous_hash.to_string(),
+        timestamp(index),
+    )
+}
+
+/// Chaîne valide de `len` blocs (genesis compris) minée en `difficulty`
+pub fn build_chain(len: usize, difficulty: usize) -> Blockchain {
+    let mut rng = SeededRng::new(SEED);
+    let mut genesis = Block::with_timestamp(
+        0,
+        "Genesis Block".to_string(),
+        "0".to_string(),
+        timestamp(0),
+    );
+    genesis.hash = genesis.calculate_hash();
+
+    let mut blocks = vec![genesis];
+    for index in 1..len as u64 {
+        let mut next = block(index, &blocks[blocks.len() - 1].hash, &mut rng);
+        mine_block(&mut next, difficulty);
+        blocks.push(next);
+    }
+
+    Blockchain {
+        chain: blocks.into(),
+        difficulty,
+        checkpoints: Checkpoints::default(),
+        consensus: consensus::default_engine(),
+    }
+}
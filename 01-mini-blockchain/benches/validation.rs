@@ -0,0 +1,29 @@
+// Validation complète, séquentielle et parallèle, de chaînes de 1k à 100k blocs
+//
+// Lancer avec `cargo bench --bench validation`.
+
+mod support;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn bench_validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate_detailed");
+    group.sample_size(10);
+    for len in [1_000, 10_000, 100_000] {
+        let blockchain = support::build_chain(len, 1);
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("serial", len), &blockchain, |b, chain| {
+            b.iter(|| chain.validate_detailed())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("parallel", len),
+            &blockchain,
+            |b, chain| b.iter(|| chain.validate_detailed_parallel()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_validation);
+criterion_main!(benches);
@@ -0,0 +1,590 @@
+use crate::api::error::ApiError;
+use crate::config::NodeConfig;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// En-tête alternatif à `Authorization: Bearer` pour transmettre la clé
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Nombre de seaux au-delà duquel les seaux pleins sont purgés
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Droits associés à une clé (`Write` inclut `Read`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    Read,
+    Write,
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Scope::Read),
+            "write" => Ok(Scope::Write),
+            other => Err(format!("Unknown scope '{}', expected read or write", other)),
+        }
+    }
+}
+
+/// Clé d'API configurée
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    /// Nom lisible (utilisé dans les logs et pour la limitation de débit)
+    pub name: String,
+    pub token: String,
+    pub scope: Scope,
+}
+
+/// Seau de jetons d'un client
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Limiteur de débit à seau de jetons, un seau par client
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Autoriser `requests` requêtes par minute (rafale maximale = `requests`)
+    pub fn per_minute(requests: u32) -> Self {
+        Self {
+            capacity: requests as f64,
+            refill_per_sec: requests as f64 / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Consommer un jeton pour `client`
+    ///
+    /// # Returns
+    /// Ok si la requête est autorisée, sinon le délai avant le prochain jeton
+    pub fn check(&self, client: &str) -> Result<(), Duration> {
+        self.check_n(client, 1)
+    }
+
+    /// Consommer `tokens` jetons d'un coup pour `client` (rien n'est consommé
+    /// en cas de refus)
+    ///
+    /// Une demande plus grande que la rafale maximale est toujours refusée.
+    pub fn check_n(&self, client: &str, tokens: u32) -> Result<(), Duration> {
+        self.check_at(client, tokens, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, tokens: u32, now: Instant) -> Result<(), Duration> {
+        if self.capacity == 0.0 || tokens == 0 {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            let (capacity, rate) = (self.capacity, self.refill_per_sec);
+            buckets.retain(|_, b| {
+                b.tokens + now.duration_since(b.updated).as_secs_f64() * rate < capacity
+            });
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+
+        // Remplir le seau selon le temps écoulé
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated = now;
+
+        let tokens = f64::from(tokens);
+        if bucket.tokens >= tokens {
+            bucket.tokens -= tokens;
+            Ok(())
+        } else {
+            let missing = tokens.min(self.capacity) - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / self.refill_per_sec))
+        }
+    }
+}
+
+/// Politique d'accès de l'API : clés, scopes et limites de débit
+#[derive(Debug)]
+pub struct ApiSecurity {
+    /// Clés indexées par le SHA-256 de leur token : la recherche compare des
+    /// empreintes, pas le token lui-même, et ne révèle rien de son préfixe
+    keys: HashMap<[u8; 32], ApiKey>,
+    require_read_auth: bool,
+    /// Accepter les écritures sans clé (refusées par défaut)
+    allow_anonymous_writes: bool,
+    key_limiter: RateLimiter,
+    ip_limiter: RateLimiter,
+    peer_limiter: RateLimiter,
+}
+
+impl ApiSecurity {
+    /// Créer une politique à partir des clés et des limites (requêtes/minute)
+    pub fn new(
+        keys: Vec<ApiKey>,
+        require_read_auth: bool,
+        key_rate_limit: u32,
+        ip_rate_limit: u32,
+    ) -> Self {
+        Self {
+            keys: keys
+                .into_iter()
+                .map(|k| (token_digest(&k.token), k))
+                .collect(),
+            require_read_auth,
+            allow_anonymous_writes: false,
+            key_limiter: RateLimiter::per_minute(key_rate_limit),
+            ip_limiter: RateLimiter::per_minute(ip_rate_limit),
+            peer_limiter: RateLimiter::per_minute(0),
+        }
+    }
+
+    /// Limiter les messages entre nœuds (`requests` par minute et par IP)
+    pub fn with_peer_rate_limit(mut self, requests: u32) -> Self {
+        self.peer_limiter = RateLimiter::per_minute(requests);
+        self
+    }
+
+    /// Accepter (ou non) les écritures des requêtes sans clé
+    pub fn with_anonymous_writes(mut self, allow: bool) -> Self {
+        self.allow_anonymous_writes = allow;
+        self
+    }
+
+    /// Politique sans authentification ni limitation (développement, tests)
+    pub fn disabled() -> Self {
+        Self::new(Vec::new(), false, 0, 0).with_anonymous_writes(true)
+    }
+
+    /// Construire la politique depuis la configuration du nœud
+    pub fn from_config(config: &NodeConfig) -> Self {
+        Self::new(
+            config.api_keys.clone(),
+            config.require_read_auth,
+            config.key_rate_limit,
+            config.ip_rate_limit,
+        )
+        .with_peer_rate_limit(config.peer_rate_limit)
+        .with_anonymous_writes(config.allow_anonymous_writes)
+    }
+
+    /// Au moins une clé est-elle configurée ?
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Les requêtes sans clé peuvent-elles écrire ?
+    pub fn allows_anonymous_writes(&self) -> bool {
+        self.allow_anonymous_writes
+    }
+
+    /// Autoriser une requête qui demande le scope `required`
+    ///
+    /// Le scope `write` exige une clé d'écriture, sauf si les écritures
+    /// anonymes sont autorisées (`allow_anonymous_writes`) ; sans clé
+    /// configurée et sans cette option, toute écriture est donc refusée. Le
+    /// scope `read` n'exige une clé que si `require_read_auth` est actif :
+    /// sinon une clé inconnue est ignorée, comme une requête anonyme. Le
+    /// scope `write` est limité par clé et par IP.
+    ///
+    /// # Arguments
+    /// * `headers` - En-têtes portant la clé (`Authorization` ou `X-API-Key`)
+    /// * `ip` - Adresse du client, si elle est connue
+    /// * `required` - Scope nécessaire à l'opération
+    pub fn check(
+        &self,
+        headers: &HeaderMap,
+        ip: Option<IpAddr>,
+        required: Scope,
+    ) -> Result<(), ApiError> {
+        let writes = u32::from(required == Scope::Write);
+        self.check_writes(headers, ip, required, writes)
+    }
+
+    /// Comme `check`, en consommant `writes` jetons des limites d'écriture
+    /// (un par appel d'écriture d'un lot JSON-RPC)
+    pub fn check_writes(
+        &self,
+        headers: &HeaderMap,
+        ip: Option<IpAddr>,
+        required: Scope,
+        writes: u32,
+    ) -> Result<(), ApiError> {
+        // 1. Identifier la clé (une route publique ignore une clé inconnue)
+        let needs_key = match required {
+            Scope::Read => self.require_read_auth,
+            Scope::Write => !self.allow_anonymous_writes,
+        };
+        let key = match extract_token(headers) {
+            Some(token) => match self.keys.get(&token_digest(token)) {
+                Some(key) => Some(key),
+                None if required == Scope::Write || needs_key => {
+                    return Err(ApiError::Unauthorized("Invalid API key".to_string()));
+                }
+                None => None,
+            },
+            None if needs_key => {
+                return Err(ApiError::Unauthorized("Missing API key".to_string()));
+            }
+            None => None,
+        };
+
+        // 2. Vérifier le scope
+        if let Some(key) = key {
+            if key.scope < required {
+                return Err(ApiError::Forbidden(format!(
+                    "API key '{}' lacks the write scope",
+                    key.name
+                )));
+            }
+        }
+
+        // 3. Limiter le débit des requêtes qui modifient l'état
+        if let Some(ip) = ip {
+            self.ip_limiter
+                .check_n(&ip.to_string(), writes)
+                .map_err(|retry_after| ApiError::RateLimited { retry_after })?;
+        }
+        if let Some(key) = key {
+            self.key_limiter
+                .check_n(&key.name, writes)
+                .map_err(|retry_after| ApiError::RateLimited { retry_after })?;
+        }
+        Ok(())
+    }
+}
+
+/// Empreinte d'un token, seule forme sous laquelle il est comparé
+fn token_digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// Middleware d'authentification et de limitation de débit
+///
+/// - GET/HEAD/OPTIONS demandent le scope `read` (seulement si configuré)
+/// - Les autres méthodes demandent le scope `write` et sont limitées par clé et par IP
+///
+/// `/rpc` n'est pas derrière ce middleware : le scope y dépend des méthodes
+/// appelées (voir `rpc_handler`).
+pub async fn authorize(
+    State(security): State<Arc<ApiSecurity>>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let required = match *request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => Scope::Read,
+        _ => Scope::Write,
+    };
+    let ip = remote.map(|ConnectInfo(addr)| addr.ip());
+    security.check(request.headers(), ip, required)?;
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware de limitation de débit des échanges entre nœuds
+///
+/// `/peers/addr` et `/peers/blocks` se passent de clé : chaque IP a son propre
+/// seau, distinct de celui des requêtes authentifiées.
+pub async fn limit_peer_messages(
+    State(security): State<Arc<ApiSecurity>>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(ConnectInfo(addr)) = remote {
+        security
+            .peer_limiter
+            .check(&addr.ip().to_string())
+            .map_err(|retry_after| ApiError::RateLimited { retry_after })?;
+    }
+    Ok(next.run(request).await)
+}
+
+/// Extraire le token de `Authorization: Bearer ...` ou de `X-API-Key`
+///
+/// Le nom du schéma est insensible à la casse (RFC 9110).
+fn extract_token(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split_once(' '))
+        .and_then(|(scheme, token)| scheme.eq_ignore_ascii_case("bearer").then_some(token));
+
+    bearer
+        .or_else(|| headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{router, AppState};
+    use crate::Blockchain;
+    use axum::body::Body;
+    use axum::extract::connect_info::MockConnectInfo;
+    use axum::http::StatusCode;
+    use tower::ServiceExt;
+
+    fn keys() -> Vec<ApiKey> {
+        vec![
+            ApiKey {
+                name: "writer".to_string(),
+                token: "w-token".to_string(),
+                scope: Scope::Write,
+            },
+            ApiKey {
+                name: "reader".to_string(),
+                token: "r-token".to_string(),
+                scope: Scope::Read,
+            },
+        ]
+    }
+
+    fn app(security: ApiSecurity) -> axum::Router {
+        let state = AppState::new(Blockchain::new(1)).with_security(security);
+        router(state, &NodeConfig::default())
+    }
+
+    fn post_block(token: Option<&str>) -> Request {
+        let mut builder = Request::post("/blocks").header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        builder.body(Body::from(r#"{"data":"tx"}"#)).unwrap()
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let limiter = RateLimiter::per_minute(2);
+        let start = Instant::now();
+
+        assert!(limiter.check_at("a", 1, start).is_ok());
+        assert!(limiter.check_at("a", 1, start).is_ok());
+        let retry = limiter.check_at("a", 1, start).unwrap_err();
+        assert!(retry <= Duration::from_secs(30));
+
+        // Un autre client a son propre seau
+        assert!(limiter.check_at("b", 1, start).is_ok());
+
+        // 30 secondes plus tard, un jeton est disponible
+        assert!(limiter
+            .check_at("a", 1, start + Duration::from_secs(30))
+            .is_ok());
+
+        // Plusieurs jetons sont pris d'un coup ou pas du tout
+        assert!(limiter.check_at("c", 3, start).is_err());
+        assert!(limiter.check_at("c", 2, start).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scopes_and_status_codes() {
+        let app = app(ApiSecurity::new(keys(), false, 30, 0));
+
+        let response = app.clone().oneshot(post_block(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.clone().oneshot(post_block(Some("bad"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(post_block(Some("r-token")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(post_block(Some("w-token")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Les lectures restent publiques par défaut
+        let request = Request::get("/chain").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_key_rate_limit() {
+        let app = app(ApiSecurity::new(keys(), false, 1, 0));
+
+        let response = app
+            .clone()
+            .oneshot(post_block(Some("w-token")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.oneshot(post_block(Some("w-token"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+    }
+
+    #[test]
+    fn test_bearer_scheme_is_case_insensitive() {
+        for value in ["Bearer w-token", "bearer w-token", "BEARER  w-token"] {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, value.parse().unwrap());
+            assert_eq!(extract_token(&headers), Some("w-token"), "{}", value);
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Basic w-token".parse().unwrap());
+        assert_eq!(extract_token(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_scope_follows_the_called_methods() {
+        let app = app(ApiSecurity::new(keys(), false, 1, 0));
+        let rpc = |token: Option<&str>, body: &str| {
+            let mut builder = Request::post("/rpc").header("content-type", "application/json");
+            if let Some(token) = token {
+                builder = builder.header("authorization", format!("Bearer {}", token));
+            }
+            builder.body(Body::from(body.to_string())).unwrap()
+        };
+        let count = r#"{"jsonrpc":"2.0","method":"getblockcount","id":1}"#;
+        let submit = r#"{"jsonrpc":"2.0","method":"generateblock","params":["tx"],"id":2}"#;
+
+        // Lectures : sans clé ou avec une clé de lecture, sans limite d'écriture
+        for token in [None, Some("r-token"), Some("w-token"), Some("w-token")] {
+            let response = app.clone().oneshot(rpc(token, count)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // Écritures : clé d'écriture exigée, y compris au sein d'un lot
+        let response = app.clone().oneshot(rpc(None, submit)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let batch = format!("[{},{}]", count, submit);
+        let response = app
+            .clone()
+            .oneshot(rpc(Some("r-token"), &batch))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = app
+            .clone()
+            .oneshot(rpc(Some("w-token"), submit))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(rpc(Some("w-token"), submit)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_batch_is_charged_per_write_call() {
+        let app = app(ApiSecurity::new(keys(), false, 3, 0));
+        let rpc = |calls: usize| {
+            let submit = r#"{"jsonrpc":"2.0","method":"generateblock","params":["tx"],"id":1}"#;
+            let batch = format!("[{}]", vec![submit; calls].join(","));
+            Request::post("/rpc")
+                .header("content-type", "application/json")
+                .header("authorization", "Bearer w-token")
+                .body(Body::from(batch))
+                .unwrap()
+        };
+
+        // Un lot plus grand que le seau est refusé avant tout traitement
+        let response = app.clone().oneshot(rpc(4)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+        // Un lot qui tient dans le seau le vide
+        let response = app.clone().oneshot(rpc(3)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(rpc(1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_writes_need_an_explicit_opt_in() {
+        // Sans clé configurée, les écritures sont refusées par défaut
+        let node = app(ApiSecurity::new(Vec::new(), false, 0, 0));
+        let response = node.clone().oneshot(post_block(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let request = Request::get("/chain").body(Body::empty()).unwrap();
+        let response = node.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let security = ApiSecurity::new(Vec::new(), false, 0, 0).with_anonymous_writes(true);
+        assert!(security
+            .check(&HeaderMap::new(), None, Scope::Write)
+            .is_ok());
+
+        // Avec des clés, l'option ouvre les écritures sans clé, pas celles
+        // d'une clé inconnue
+        let node = app(ApiSecurity::new(keys(), false, 0, 0).with_anonymous_writes(true));
+        let response = node.clone().oneshot(post_block(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = node.oneshot(post_block(Some("bad"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_public_reads_ignore_unknown_keys() {
+        let get_chain = || {
+            Request::get("/chain")
+                .header("authorization", "Bearer bad")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app(ApiSecurity::new(keys(), false, 0, 0))
+            .oneshot(get_chain())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Une lecture qui exige une clé la vérifie
+        let response = app(ApiSecurity::new(keys(), true, 0, 0))
+            .oneshot(get_chain())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_peer_messages_are_limited_per_ip() {
+        let app = |ip: [u8; 4]| {
+            app(ApiSecurity::new(keys(), false, 30, 30).with_peer_rate_limit(1))
+                .layer(MockConnectInfo(SocketAddr::from((ip, 50_000))))
+        };
+        let addr = || {
+            Request::post("/peers/addr")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"addresses":[]}"#))
+                .unwrap()
+        };
+
+        // Sans clé d'API, mais dans la limite de l'IP
+        let node = app([10, 0, 0, 1]);
+        let response = node.clone().oneshot(addr()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = node.oneshot(addr()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
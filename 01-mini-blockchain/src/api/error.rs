@@ -0,0 +1,306 @@
+use crate::api::models::ErrorResponse;
+use crate::blockchain::BlockchainError;
+use crate::chain_service::ServiceError;
+use crate::export::TransferError;
+use crate::peers::PeerError;
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Erreur unique renvoyée par tous les handlers de l'API
+///
+/// Chaque variante correspond à un code HTTP et à un code d'erreur stable
+/// (`ErrorResponse::code`) sur lequel les clients peuvent se baser.
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error(transparent)]
+    Chain(#[from] BlockchainError),
+
+    #[error("Block not found")]
+    BlockNotFound,
+
+    #[error("Transaction not found")]
+    TransactionNotFound,
+
+    #[error(
+        "Block {0} has no Bitcoin-style header, headers need BLOCKCHAIN_POW_ALGORITHM=sha256d"
+    )]
+    HeadersUnavailable(u64),
+
+    #[error(transparent)]
+    Peer(#[from] PeerError),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    InvalidRequest(String),
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("Rate limit exceeded")]
+    RateLimited { retry_after: Duration },
+
+    #[error("Blockchain state is unavailable")]
+    Unavailable,
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    /// Code HTTP associé à l'erreur
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Chain(BlockchainError::EmptyData)
+            | ApiError::Chain(BlockchainError::InvalidTransactions) => StatusCode::BAD_REQUEST,
+            ApiError::Chain(BlockchainError::InvalidGenesis)
+            | ApiError::Chain(BlockchainError::InvalidHash)
+            | ApiError::Chain(BlockchainError::InvalidPreviousHash)
+            | ApiError::Chain(BlockchainError::InvalidIndex)
+            | ApiError::Chain(BlockchainError::CheckpointMismatch)
+            | ApiError::Chain(BlockchainError::UnauthorizedSigner)
+            | ApiError::Chain(BlockchainError::FutureBlock) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Chain(BlockchainError::NotInTurn) => StatusCode::CONFLICT,
+            ApiError::Chain(BlockchainError::MiningFailed) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Chain(BlockchainError::MiningCancelled) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::BlockNotFound | ApiError::TransactionNotFound => StatusCode::NOT_FOUND,
+            ApiError::HeadersUnavailable(_) => StatusCode::CONFLICT,
+            ApiError::Peer(PeerError::InvalidUrl(_)) => StatusCode::BAD_REQUEST,
+            ApiError::Peer(PeerError::Banned(_)) => StatusCode::FORBIDDEN,
+            ApiError::Peer(PeerError::NotFound(_)) => StatusCode::NOT_FOUND,
+            ApiError::Peer(PeerError::InboundFull | PeerError::OutboundFull) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            ApiError::Peer(PeerError::Unreachable { .. }) => StatusCode::BAD_GATEWAY,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Code d'erreur stable, lisible par les machines
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Chain(BlockchainError::InvalidGenesis) => "invalid_genesis",
+            ApiError::Chain(BlockchainError::InvalidHash) => "invalid_hash",
+            ApiError::Chain(BlockchainError::InvalidPreviousHash) => "invalid_previous_hash",
+            ApiError::Chain(BlockchainError::InvalidIndex) => "invalid_index",
+            ApiError::Chain(BlockchainError::CheckpointMismatch) => "checkpoint_mismatch",
+            ApiError::Chain(BlockchainError::UnauthorizedSigner) => "unauthorized_signer",
+            ApiError::Chain(BlockchainError::FutureBlock) => "future_block",
+            ApiError::Chain(BlockchainError::NotInTurn) => "not_in_turn",
+            ApiError::Chain(BlockchainError::MiningFailed) => "mining_failed",
+            ApiError::Chain(BlockchainError::MiningCancelled) => "mining_cancelled",
+            ApiError::Chain(BlockchainError::EmptyData) => "empty_data",
+            ApiError::Chain(BlockchainError::InvalidTransactions) => "invalid_transactions",
+            ApiError::BlockNotFound => "block_not_found",
+            ApiError::TransactionNotFound => "transaction_not_found",
+            ApiError::HeadersUnavailable(_) => "headers_unavailable",
+            ApiError::Peer(PeerError::InvalidUrl(_)) => "invalid_peer_url",
+            ApiError::Peer(PeerError::Banned(_)) => "peer_banned",
+            ApiError::Peer(PeerError::NotFound(_)) => "peer_not_found",
+            ApiError::Peer(PeerError::InboundFull | PeerError::OutboundFull) => "peer_limit",
+            ApiError::Peer(PeerError::Unreachable { .. }) => "peer_unreachable",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::InvalidRequest(_) => "invalid_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::Unavailable => "unavailable",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.to_string(),
+            code: self.code().to_string(),
+        };
+        let mut response = (self.status(), Json(body)).into_response();
+
+        match self {
+            ApiError::Unauthorized(_) => {
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            ApiError::RateLimited { retry_after } => {
+                // Arrondi au supérieur : un client qui réessaie au bout de
+                // `Retry-After` secondes doit trouver un jeton disponible
+                let seconds =
+                    (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)).max(1);
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+            }
+            _ => {}
+        }
+        response
+    }
+}
+
+impl From<TransferError> for ApiError {
+    fn from(error: TransferError) -> Self {
+        match error {
+            TransferError::Invalid { error, .. } => ApiError::Chain(error),
+            TransferError::Conflict { .. } => ApiError::Conflict(error.to_string()),
+            TransferError::Decode { .. } | TransferError::Io(_) => {
+                ApiError::InvalidRequest(error.to_string())
+            }
+        }
+    }
+}
+
+impl From<ServiceError> for ApiError {
+    fn from(error: ServiceError) -> Self {
+        match error {
+            ServiceError::Stopped => ApiError::Unavailable,
+            ServiceError::Chain(error) => ApiError::Chain(error),
+            ServiceError::Transfer(error) => error.into(),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::InvalidRequest(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::InvalidRequest(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::InvalidRequest(rejection.body_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blockchain_error_mapping() {
+        let cases = [
+            (
+                BlockchainError::InvalidGenesis,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_genesis",
+            ),
+            (
+                BlockchainError::InvalidHash,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_hash",
+            ),
+            (
+                BlockchainError::InvalidPreviousHash,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_previous_hash",
+            ),
+            (
+                BlockchainError::InvalidIndex,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_index",
+            ),
+            (
+                BlockchainError::CheckpointMismatch,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "checkpoint_mismatch",
+            ),
+            (
+                BlockchainError::UnauthorizedSigner,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unauthorized_signer",
+            ),
+            (
+                BlockchainError::FutureBlock,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "future_block",
+            ),
+            (
+                BlockchainError::NotInTurn,
+                StatusCode::CONFLICT,
+                "not_in_turn",
+            ),
+            (
+                BlockchainError::MiningFailed,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "mining_failed",
+            ),
+            (
+                BlockchainError::MiningCancelled,
+                StatusCode::SERVICE_UNAVAILABLE,
+                "mining_cancelled",
+            ),
+            (
+                BlockchainError::EmptyData,
+                StatusCode::BAD_REQUEST,
+                "empty_data",
+            ),
+            (
+                BlockchainError::InvalidTransactions,
+                StatusCode::BAD_REQUEST,
+                "invalid_transactions",
+            ),
+        ];
+
+        for (error, status, code) in cases {
+            let error = ApiError::from(error);
+            assert_eq!(error.status(), status);
+            assert_eq!(error.code(), code);
+        }
+    }
+
+    #[test]
+    fn test_service_error_mapping() {
+        let stopped = ApiError::from(ServiceError::Stopped);
+        assert_eq!(stopped.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(stopped.code(), "unavailable");
+
+        let conflict = ApiError::from(ServiceError::Transfer(TransferError::Conflict {
+            record: 0,
+            index: 0,
+        }));
+        assert_eq!(conflict.status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_rate_limited_response_headers() {
+        let response = ApiError::RateLimited {
+            retry_after: Duration::from_millis(1500),
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+
+        for (retry_after, expected) in [
+            (Duration::from_secs(3), "3"),
+            (Duration::from_millis(1), "1"),
+            (Duration::ZERO, "1"),
+        ] {
+            let response = ApiError::RateLimited { retry_after }.into_response();
+            assert_eq!(response.headers()[header::RETRY_AFTER], expected);
+        }
+    }
+}
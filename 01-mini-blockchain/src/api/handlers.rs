@@ -1,90 +1,885 @@
+use crate::api::error::ApiError;
 use crate::api::models::*;
-use crate::blockchain::SharedBlockchain;
+use crate::bitcoin;
+use crate::blockchain::{Blockchain, ValidationMode};
+use crate::chain_service::ChainHandle;
+use crate::events::NodeEvent;
+use crate::export::{ChainFormat, TransferError};
+use crate::merkle;
+use crate::orphans::{BlockStatus, OrphanPool};
+use crate::peers::{AddrMessage, PeerInfo, PeerManager};
+use crate::proof_of_work::PowAlgorithm;
+use crate::storage::CHAIN_FILE_VERSION;
+use crate::sync::{SyncHeader, SyncStatus, SyncTracker};
 use crate::Block;
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    body::Bytes,
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        ConnectInfo, Path, Query, State,
+    },
+    http::header,
+    response::{IntoResponse, Response},
     Json,
 };
+use std::net::SocketAddr;
+
+// ---------------------------------------------------------------------------
+// Opérations partagées entre l'API REST et l'interface JSON-RPC
+// ---------------------------------------------------------------------------
+
+/// Construire la réponse contenant toute la chaîne
+///
+/// Les blocs viennent d'un snapshot : ils sont sérialisés sans être copiés.
+/// `is_valid` reprend l'état du préfixe validé, sans revérifier la chaîne
+/// (`validate` s'en charge sur un thread bloquant).
+pub fn chain_snapshot(chain: &ChainHandle) -> ChainResponse {
+    let bc = chain.snapshot();
+
+    ChainResponse {
+        version: CHAIN_FILE_VERSION,
+        chain: bc.chain.clone(),
+        length: bc.len(),
+        is_valid: bc.chain.validated_len() == bc.len(),
+    }
+}
+
+/// Miner et ajouter un bloc
+///
+/// Le service de chaîne publie `BlockAdded` et abandonne le mining (sans
+/// modifier la chaîne) si le nœud s'arrête.
+///
+/// # Returns
+/// Le bloc ajouté ou l'erreur de l'API
+pub async fn mine_and_append(chain: &ChainHandle, data: String) -> Result<Block, ApiError> {
+    Ok(chain.append(data).await?)
+}
+
+/// Ajouter au sommet un bloc déjà scellé, puis publier `BlockAdded`
+///
+/// Le bloc est validé comme un bloc reçu d'un pair (`append_block`) ; s'il
+/// est refusé, `ValidationFailed` est publié. Comme pour `import_blocks`, la
+/// validation s'exécute sur un thread bloquant, sur une copie du snapshot, et
+/// le service de chaîne n'échange les blocs que si le sommet n'a pas bougé.
+///
+/// # Returns
+/// Le bloc ajouté ou l'erreur de validation
+pub async fn submit_block(chain: &ChainHandle, block: Block) -> Result<Block, ApiError> {
+    let index = block.index;
+    for _ in 0..IMPORT_ATTEMPTS {
+        let base = chain.snapshot();
+        let (staged, result) = tokio::task::spawn_blocking({
+            let (base, block) = (base.clone(), block.clone());
+            move || {
+                let mut staged = Blockchain::clone(&base);
+                let result = staged.append_block(block).cloned();
+                (staged, result)
+            }
+        })
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        match result {
+            Ok(block) => {
+                if !chain.commit(&base, staged).await? {
+                    continue;
+                }
+                chain.events().publish(NodeEvent::BlockAdded {
+                    block: block.clone(),
+                });
+                return Ok(block);
+            }
+            // Le bloc a peut-être été validé contre un sommet déjà dépassé
+            Err(_) if chain.snapshot().latest_block().hash != base.latest_block().hash => {}
+            Err(error) => {
+                chain
+                    .events()
+                    .publish(NodeEvent::block_rejected(index, &error));
+                return Err(error.into());
+            }
+        }
+    }
+
+    Err(ApiError::Conflict(
+        "The chain kept changing while the block was validated, submit it again".to_string(),
+    ))
+}
+
+/// Rechercher un bloc par son index
+pub fn find_block_by_index(chain: &ChainHandle, index: u64) -> Result<Block, ApiError> {
+    chain
+        .snapshot()
+        .get_block(index)
+        .cloned()
+        .ok_or(ApiError::BlockNotFound)
+}
+
+/// Rechercher un bloc par son hash
+pub fn find_block_by_hash(chain: &ChainHandle, hash: &str) -> Result<Block, ApiError> {
+    chain
+        .snapshot()
+        .get_block_by_hash(hash)
+        .cloned()
+        .ok_or(ApiError::BlockNotFound)
+}
+
+/// En-têtes Bitcoin des blocs `from..from + limit` pour un client léger
+///
+/// Seuls les blocs minés avec `sha256d` ont un en-tête qui les représente
+/// sans perte : la plage s'arrête au sommet de la chaîne et échoue sur un
+/// bloc d'un autre algorithme.
+pub fn headers_range(
+    chain: &ChainHandle,
+    from: u64,
+    limit: usize,
+) -> Result<HeadersResponse, ApiError> {
+    let bc = chain.snapshot();
+    if from == 0 {
+        return Err(ApiError::HeadersUnavailable(0));
+    }
+
+    let mut headers = Vec::new();
+    for index in (from..).take(limit.min(MAX_HEADERS)) {
+        let Some(block) = bc.get_block(index) else {
+            break;
+        };
+        if block.algorithm != PowAlgorithm::Sha256d || !block.fits_bitcoin_header() {
+            return Err(ApiError::HeadersUnavailable(index));
+        }
+        headers.push(block.bitcoin_header());
+    }
+
+    Ok(HeadersResponse {
+        genesis_hash: bc.chain[0].hash.clone(),
+        difficulty: bc.difficulty,
+        from,
+        headers,
+    })
+}
+
+/// En-têtes des blocs `from..from + limit` pour un nœud qui se synchronise
+pub fn sync_headers(chain: &ChainHandle, from: u64, limit: usize) -> SyncHeadersResponse {
+    let bc = chain.snapshot();
+    let headers = (from..)
+        .take(limit.min(MAX_HEADERS))
+        .map_while(|index| bc.get_block(index))
+        .map(SyncHeader::from)
+        .collect();
+
+    SyncHeadersResponse {
+        genesis_hash: bc.chain[0].hash.clone(),
+        height: bc.latest_block().index,
+        from,
+        headers,
+    }
+}
+
+/// Preuve de Merkle de la transaction `position` du bloc `index`
+pub fn transaction_proof(
+    chain: &ChainHandle,
+    index: u64,
+    position: usize,
+) -> Result<MerkleProofResponse, ApiError> {
+    let block = find_block_by_index(chain, index)?;
+    let transaction = block
+        .transactions()
+        .nth(position)
+        .ok_or(ApiError::TransactionNotFound)?
+        .to_string();
+    let proof = block
+        .transaction_proof(position)
+        .ok_or(ApiError::TransactionNotFound)?;
+
+    Ok(MerkleProofResponse {
+        height: index,
+        position,
+        txid: bitcoin::to_display_hex(&merkle::txid(&transaction)),
+        transaction,
+        leaf_index: proof.leaf_index,
+        siblings: proof.siblings.iter().map(bitcoin::to_display_hex).collect(),
+        merkle_root: bitcoin::to_display_hex(&block.bitcoin_header().merkle_root),
+    })
+}
+
+/// Valider la chaîne et publier un événement en cas d'échec
+///
+/// En mode incrémental, seuls les blocs ajoutés depuis la dernière validation
+/// sont revérifiés. La validation s'exécute sur un thread bloquant : une
+/// validation complète ne monopolise pas le runtime.
+pub async fn validate(
+    chain: &ChainHandle,
+    mode: ValidationMode,
+) -> Result<ValidateResponse, ApiError> {
+    let bc = chain.snapshot();
+    let report = tokio::task::spawn_blocking({
+        let bc = bc.clone();
+        move || bc.validate(mode)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if let Some(fault) = report.faults.first() {
+        chain.events().publish(NodeEvent::ValidationFailed {
+            block_index: Some(fault.index),
+            reason: fault.to_string(),
+        });
+    }
+
+    Ok(ValidateResponse {
+        is_valid: report.is_valid(),
+        chain_length: bc.len(),
+        checked_blocks: report.checked,
+    })
+}
+
+/// Exporter toute la chaîne dans le format demandé, sur un thread bloquant
+pub async fn export_snapshot(
+    chain: &ChainHandle,
+    format: ChainFormat,
+) -> Result<Vec<u8>, ApiError> {
+    let bc = chain.snapshot();
+    tokio::task::spawn_blocking(move || {
+        let mut buffer = Vec::new();
+        bc.export(&mut buffer, format)
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        Ok(buffer)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(e.to_string()))?
+}
+
+/// Nombre de fois qu'un import (ou un bloc soumis) est rejoué si le sommet
+/// bouge pendant sa validation
+const IMPORT_ATTEMPTS: usize = 3;
+
+/// Importer des blocs puis notifier les abonnés de chaque bloc ajouté
+///
+/// Les blocs valides lus avant une erreur restent dans la chaîne : renvoyer
+/// le même fichier reprend l'import là où il s'est arrêté.
+///
+/// Le décodage et la validation s'exécutent sur un thread bloquant, sur une
+/// copie du snapshot ; le service de chaîne n'échange les blocs que si le
+/// sommet n'a pas bougé entre-temps, sinon l'import est rejoué sur le
+/// nouveau snapshot.
+pub async fn import_blocks(
+    chain: &ChainHandle,
+    body: Bytes,
+    format: ChainFormat,
+) -> Result<ImportResponse, ApiError> {
+    for _ in 0..IMPORT_ATTEMPTS {
+        let base = chain.snapshot();
+        let (staged, result) = tokio::task::spawn_blocking({
+            let (base, body) = (base.clone(), body.clone());
+            move || {
+                let mut staged = Blockchain::clone(&base);
+                let result = staged.import(body.as_ref(), format);
+                (staged, result)
+            }
+        })
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        let unchanged = staged.len() == base.len() && staged.chain[0].hash == base.chain[0].hash;
+        let added: Vec<Block> = staged.chain.iter_from(base.len()).cloned().collect();
+        let length = staged.len();
+        if !unchanged && !chain.commit(&base, staged).await? {
+            continue;
+        }
+
+        for block in added {
+            chain.events().publish(NodeEvent::BlockAdded { block });
+        }
+        if let Err(
+            error @ (TransferError::Invalid { index, .. } | TransferError::Conflict { index, .. }),
+        ) = &result
+        {
+            chain
+                .events()
+                .publish(NodeEvent::block_rejected(*index, error));
+        }
+
+        let report = result?;
+        return Ok(ImportResponse {
+            imported: report.imported,
+            skipped: report.skipped,
+            length,
+        });
+    }
+
+    Err(ApiError::Conflict(
+        "The chain kept changing during the import, send the file again to resume".to_string(),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Handlers REST
+// ---------------------------------------------------------------------------
+
+/// Handler pour GET /
+/// Retourne un message de bienvenue
+pub async fn root() -> &'static str {
+    "Mini Blockchain API - See /chain for the blockchain"
+}
 
 /// Handler pour GET /chain
 /// Retourne toute la blockchain
-pub async fn get_chain(
-    State(blockchain): State<SharedBlockchain>,
-) -> Result<Json<ChainResponse>, StatusCode> {
-    // Acquérir le verrou en lecture
-    let bc = blockchain.read().unwrap();
-
-    // Créer la réponse avec la chaîne complète
-    let response = ChainResponse {
-        chain: bc.chain.clone(),
-        length: bc.chain.len(),
-        is_valid: bc.is_valid(),
-    };
-    Ok(Json(response))
+#[utoipa::path(
+    get,
+    path = "/chain",
+    tag = "chain",
+    responses(
+        (status = 200, description = "Full chain", body = ChainResponse)
+    )
+)]
+pub async fn get_chain(State(chain): State<ChainHandle>) -> Json<ChainResponse> {
+    Json(chain_snapshot(&chain))
 }
 
 /// Handler pour POST /blocks
 /// Ajoute un nouveau bloc à la chaîne
+#[utoipa::path(
+    post,
+    path = "/blocks",
+    tag = "blocks",
+    request_body = AddBlockRequest,
+    responses(
+        (status = 200, description = "Block mined and added", body = AddBlockResponse),
+        (status = 400, description = "Empty data or malformed body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the write scope", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+        (status = 503, description = "Mining cancelled by a node shutdown", body = ErrorResponse)
+    ),
+    security((), ("bearer" = []), ("api_key" = []))
+)]
 pub async fn add_block(
-    State(blockchain): State<SharedBlockchain>,
-    Json(payload): Json<AddBlockRequest>,
-) -> Result<Json<AddBlockResponse>, (StatusCode, Json<ErrorResponse>)> {
-    // Vérifier que les données ne sont pas vides
-    if payload.data.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Data cannot be empty".to_string(),
-            }),
-        ));
-    }
+    State(chain): State<ChainHandle>,
+    payload: Result<Json<AddBlockRequest>, JsonRejection>,
+) -> Result<Json<AddBlockResponse>, ApiError> {
+    let Json(payload) = payload?;
+    let block = mine_and_append(&chain, payload.data).await?;
 
-    // Acquérir le verrou en écriture pour modifier la blockchain
-    let mut bc = blockchain.write().unwrap();
-
-    // Ajouter le bloc et gérer le résultat
-    match bc.add_block(payload.data) {
-        Ok(block) => Ok(Json(AddBlockResponse {
-            block: block.clone(),
-            message: "Block added successfully".to_string(),
-        })),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: e.to_string(),
-            }),
-        )),
-    }
+    Ok(Json(AddBlockResponse {
+        block,
+        message: "Block added successfully".to_string(),
+    }))
 }
 
 /// Handler pour GET /blocks/:index
 /// Retourne un bloc spécifique par son index
+#[utoipa::path(
+    get,
+    path = "/blocks/{index}",
+    tag = "blocks",
+    params(("index" = u64, Path, description = "Block height")),
+    responses(
+        (status = 200, description = "Block found", body = Block),
+        (status = 400, description = "Invalid index", body = ErrorResponse),
+        (status = 404, description = "Block not found", body = ErrorResponse)
+    )
+)]
 pub async fn get_block(
-    State(blockchain): State<SharedBlockchain>,
-    Path(index): Path<u64>,
-) -> Result<Json<Block>, StatusCode> {
-    // Acquérir le verrou en lecture
-    let bc = blockchain.read().unwrap();
+    State(chain): State<ChainHandle>,
+    index: Result<Path<u64>, PathRejection>,
+) -> Result<Json<Block>, ApiError> {
+    let Path(index) = index?;
 
     // Rechercher et retourner le bloc, ou 404 si non trouvé
-    bc.get_block(index)
-        .cloned()
-        .map(Json)
-        .ok_or(StatusCode::NOT_FOUND)
+    find_block_by_index(&chain, index).map(Json)
+}
+
+/// Handler pour GET /blocks/hash/:hash
+/// Retourne un bloc spécifique par son hash
+#[utoipa::path(
+    get,
+    path = "/blocks/hash/{hash}",
+    tag = "blocks",
+    params(("hash" = String, Path, description = "Block hash (hex)")),
+    responses(
+        (status = 200, description = "Block found", body = Block),
+        (status = 404, description = "Block not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_block_by_hash(
+    State(chain): State<ChainHandle>,
+    hash: Result<Path<String>, PathRejection>,
+) -> Result<Json<Block>, ApiError> {
+    let Path(hash) = hash?;
+    find_block_by_hash(&chain, &hash).map(Json)
+}
+
+/// Handler pour GET /blocks/:index/proof/:position
+/// Retourne la preuve de Merkle d'une transaction (une ligne de données du bloc)
+#[utoipa::path(
+    get,
+    path = "/blocks/{index}/proof/{position}",
+    tag = "light",
+    params(
+        ("index" = u64, Path, description = "Block height"),
+        ("position" = usize, Path, description = "Transaction position (line of the block data)")
+    ),
+    responses(
+        (status = 200, description = "Merkle inclusion proof", body = MerkleProofResponse),
+        (status = 400, description = "Invalid index or position", body = ErrorResponse),
+        (status = 404, description = "Block or transaction not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_transaction_proof(
+    State(chain): State<ChainHandle>,
+    path: Result<Path<(u64, usize)>, PathRejection>,
+) -> Result<Json<MerkleProofResponse>, ApiError> {
+    let Path((index, position)) = path?;
+    transaction_proof(&chain, index, position).map(Json)
+}
+
+/// Handler pour GET /headers
+/// Retourne des en-têtes de blocs de 80 octets pour la synchronisation d'un client léger
+#[utoipa::path(
+    get,
+    path = "/headers",
+    tag = "light",
+    params(HeadersQuery),
+    responses(
+        (status = 200, description = "Consecutive headers, up to 2000", body = HeadersResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
+        (status = 409, description = "Range contains blocks not mined with sha256d", body = ErrorResponse)
+    )
+)]
+pub async fn get_headers(
+    State(chain): State<ChainHandle>,
+    query: Result<Query<HeadersQuery>, QueryRejection>,
+) -> Result<Json<HeadersResponse>, ApiError> {
+    let Query(query) = query?;
+    let from = query.from.unwrap_or(1);
+    let limit = query.limit.unwrap_or(MAX_HEADERS);
+
+    headers_range(&chain, from, limit).map(Json)
+}
+
+/// Handler pour GET /sync/headers
+/// Retourne les en-têtes des blocs (sans leurs données) pour la synchronisation headers-first
+#[utoipa::path(
+    get,
+    path = "/sync/headers",
+    tag = "sync",
+    params(HeadersQuery),
+    responses(
+        (status = 200, description = "Consecutive block headers, up to 2000", body = SyncHeadersResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse)
+    )
+)]
+pub async fn get_sync_headers(
+    State(chain): State<ChainHandle>,
+    query: Result<Query<HeadersQuery>, QueryRejection>,
+) -> Result<Json<SyncHeadersResponse>, ApiError> {
+    let Query(query) = query?;
+    let from = query.from.unwrap_or(1);
+    let limit = query.limit.unwrap_or(MAX_HEADERS);
+
+    Ok(Json(sync_headers(&chain, from, limit)))
+}
+
+/// Handler pour GET /sync/status
+/// Retourne l'avancement du téléchargement initial de la chaîne
+#[utoipa::path(
+    get,
+    path = "/sync/status",
+    tag = "sync",
+    responses(
+        (status = 200, description = "Sync progress", body = SyncStatus)
+    )
+)]
+pub async fn get_sync_status(
+    State(chain): State<ChainHandle>,
+    State(sync): State<SyncTracker>,
+) -> Json<SyncStatus> {
+    let mut status = sync.status();
+    status.local_height = chain.snapshot().latest_block().index;
+    Json(status)
+}
+
+/// Handler pour GET /peers
+/// Liste les connexions, les pairs bannis et la taille du carnet d'adresses
+#[utoipa::path(
+    get,
+    path = "/peers",
+    tag = "peers",
+    responses(
+        (status = 200, description = "Connections and bans", body = PeersResponse)
+    )
+)]
+pub async fn get_peers(State(peers): State<PeerManager>) -> Json<PeersResponse> {
+    Json(PeersResponse {
+        connections: peers.connections(),
+        banned: peers.banned(),
+        known_addresses: peers.known_addresses(),
+    })
+}
+
+/// Handler pour POST /peers
+/// Se connecter à un pair, même banni ou au-delà de la limite de connexions sortantes
+#[utoipa::path(
+    post,
+    path = "/peers",
+    tag = "peers",
+    request_body = AddPeerRequest,
+    responses(
+        (status = 200, description = "Connected", body = PeerInfo),
+        (status = 400, description = "Invalid URL or malformed body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the write scope", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse),
+        (status = 502, description = "Peer did not answer the addr handshake", body = ErrorResponse)
+    ),
+    security((), ("bearer" = []), ("api_key" = []))
+)]
+pub async fn add_peer(
+    State(peers): State<PeerManager>,
+    payload: Result<Json<AddPeerRequest>, JsonRejection>,
+) -> Result<Json<PeerInfo>, ApiError> {
+    let Json(payload) = payload?;
+    Ok(Json(peers.add_manual(&payload.url).await?))
+}
+
+/// Handler pour DELETE /peers/:id
+/// Fermer une connexion, et bannir le pair avec `?ban=true`
+#[utoipa::path(
+    delete,
+    path = "/peers/{id}",
+    tag = "peers",
+    params(("id" = u64, Path, description = "Connection id"), DisconnectQuery),
+    responses(
+        (status = 200, description = "Connection closed", body = PeerInfo),
+        (status = 400, description = "Invalid id or query", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the write scope", body = ErrorResponse),
+        (status = 404, description = "No such connection", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded", body = ErrorResponse)
+    ),
+    security((), ("bearer" = []), ("api_key" = []))
+)]
+pub async fn remove_peer(
+    State(peers): State<PeerManager>,
+    id: Result<Path<u64>, PathRejection>,
+    query: Result<Query<DisconnectQuery>, QueryRejection>,
+) -> Result<Json<PeerInfo>, ApiError> {
+    let Path(id) = id?;
+    let Query(query) = query?;
+    Ok(Json(peers.disconnect(id, query.ban.unwrap_or(false))?))
+}
+
+/// Handler pour POST /peers/addr
+/// Échange de messages `addr` entre nœuds (poignée de main et gossip) ; seul
+/// un émetteur dont l'URL désigne l'adresse de la connexion devient pair entrant
+#[utoipa::path(
+    post,
+    path = "/peers/addr",
+    tag = "peers",
+    request_body = AddrMessage,
+    responses(
+        (status = 200, description = "Addresses known by this node", body = AddrMessage),
+        (status = 400, description = "Invalid sender URL or malformed body", body = ErrorResponse),
+        (status = 403, description = "Sender is banned", body = ErrorResponse),
+        (status = 429, description = "Too many peer messages from this IP", body = ErrorResponse),
+        (status = 503, description = "No inbound slot left", body = ErrorResponse)
+    )
+)]
+pub async fn receive_addr(
+    State(peers): State<PeerManager>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    payload: Result<Json<AddrMessage>, JsonRejection>,
+) -> Result<Json<AddrMessage>, ApiError> {
+    let Json(message) = payload?;
+    let sender = peers
+        .verify_sender(
+            message.from.as_deref(),
+            remote.map(|ConnectInfo(addr)| addr),
+        )
+        .await?;
+    Ok(Json(peers.receive_addr(&message, sender.as_deref())?))
+}
+
+/// Handler pour POST /peers/blocks
+/// Reçoit un bloc miné relayé par un pair ; un bloc dont le parent est inconnu
+/// est gardé comme orphelin et son parent est demandé à l'émetteur, s'il est
+/// vérifié (`PeerManager::verify_sender`)
+#[utoipa::path(
+    post,
+    path = "/peers/blocks",
+    tag = "peers",
+    request_body = BlockMessage,
+    responses(
+        (status = 200, description = "Block connected, orphaned or already known", body = BlockStatus),
+        (status = 400, description = "Invalid sender URL or malformed body", body = ErrorResponse),
+        (status = 403, description = "Sender is banned", body = ErrorResponse),
+        (status = 422, description = "Block rejected by validation", body = ErrorResponse),
+        (status = 429, description = "Too many peer messages from this IP", body = ErrorResponse)
+    )
+)]
+pub async fn receive_block(
+    State(chain): State<ChainHandle>,
+    State(peers): State<PeerManager>,
+    State(orphans): State<OrphanPool>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    payload: Result<Json<BlockMessage>, JsonRejection>,
+) -> Result<Json<BlockStatus>, ApiError> {
+    let Json(message) = payload?;
+    let source = peers
+        .verify_sender(
+            message.from.as_deref(),
+            remote.map(|ConnectInfo(addr)| addr),
+        )
+        .await?;
+
+    let status = orphans
+        .receive(&chain, &peers, message.block, source)
+        .await?;
+    Ok(Json(status))
+}
+
+/// Handler pour GET /orphans
+/// Liste les blocs reçus dont le parent est encore inconnu
+#[utoipa::path(
+    get,
+    path = "/orphans",
+    tag = "peers",
+    responses(
+        (status = 200, description = "Orphan blocks, by height", body = OrphansResponse)
+    )
+)]
+pub async fn get_orphans(State(orphans): State<OrphanPool>) -> Json<OrphansResponse> {
+    let list = orphans.list();
+    Json(OrphansResponse {
+        count: list.len(),
+        max_orphans: orphans.max_orphans(),
+        orphans: list,
+    })
 }
 
 /// Handler pour GET /validate
 /// Valide l'intégrité de la chaîne
-pub async fn validate_chain(State(blockchain): State<SharedBlockchain>) -> Json<serde_json::Value> {
-    // Acquérir le verrou en lecture
-    let bc = blockchain.read().unwrap();
+#[utoipa::path(
+    get,
+    path = "/validate",
+    tag = "chain",
+    params(ValidateQuery),
+    responses(
+        (status = 200, description = "Validation result", body = ValidateResponse),
+        (status = 400, description = "Invalid query", body = ErrorResponse)
+    )
+)]
+pub async fn validate_chain(
+    State(chain): State<ChainHandle>,
+    query: Result<Query<ValidateQuery>, QueryRejection>,
+) -> Result<Json<ValidateResponse>, ApiError> {
+    let Query(query) = query?;
+    let mode = match query.full {
+        Some(true) => ValidationMode::Full,
+        _ => ValidationMode::Incremental,
+    };
+
+    Ok(Json(validate(&chain, mode).await?))
+}
 
-    // Créer la réponse avec le statut de validation et la longueur
-    let response = serde_json::json!({
-        "is_valid": bc.is_valid(),
-        "chain_length": bc.len()
-    });
+/// Handler pour GET /export
+/// Exporte la chaîne, un bloc par enregistrement (JSON Lines ou binaire)
+#[utoipa::path(
+    get,
+    path = "/export",
+    tag = "chain",
+    params(TransferQuery),
+    responses(
+        (status = 200, description = "One block per JSON line", body = String, content_type = "application/x-ndjson"),
+        (status = 200, description = "MBC1 header then length-prefixed bincode blocks", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 400, description = "Unknown format", body = ErrorResponse)
+    )
+)]
+pub async fn export_chain(
+    State(chain): State<ChainHandle>,
+    query: Result<Query<TransferQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query) = query?;
+    let format = query.format.unwrap_or_default();
+    let body = export_snapshot(&chain, format).await?;
 
-    Json(response)
+    Ok(([(header::CONTENT_TYPE, format.content_type())], body).into_response())
+}
+
+/// Handler pour POST /import
+/// Importe des blocs exportés par GET /export, en validant chacun
+#[utoipa::path(
+    post,
+    path = "/import",
+    tag = "chain",
+    params(TransferQuery),
+    request_body(content = String, description = "Export produced by GET /export", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Blocks imported", body = ImportResponse),
+        (status = 400, description = "Undecodable record", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the write scope", body = ErrorResponse),
+        (status = 409, description = "Record conflicts with the local chain", body = ErrorResponse),
+        (status = 422, description = "Record rejected by validation", body = ErrorResponse)
+    ),
+    security((), ("bearer" = []), ("api_key" = []))
+)]
+pub async fn import_chain(
+    State(chain): State<ChainHandle>,
+    query: Result<Query<TransferQuery>, QueryRejection>,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let Query(query) = query?;
+    let format = query.format.unwrap_or_default();
+
+    import_blocks(&chain, body, format).await.map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{router, AppState};
+    use crate::config::NodeConfig;
+    use crate::Blockchain;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_export_then_import_into_new_node() {
+        let mut source = Blockchain::new(1);
+        for i in 1..=3 {
+            source.add_block(format!("Block {}", i)).unwrap();
+        }
+        let source_app = router(AppState::new(source.clone()), &NodeConfig::default());
+
+        let request = Request::get("/export?format=binary")
+            .body(Body::empty())
+            .unwrap();
+        let response = source_app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/octet-stream"
+        );
+        let export = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        let state = AppState::new(Blockchain::new(1));
+        let mut events = state.events.subscribe();
+        let target_app = router(state.clone(), &NodeConfig::default());
+
+        let request = Request::post("/import?format=binary")
+            .body(Body::from(export))
+            .unwrap();
+        let response = target_app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.chain.snapshot().chain, source.chain);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(NodeEvent::BlockAdded { .. })
+        ));
+
+        // Un enregistrement illisible est refusé
+        let request = Request::post("/import?format=jsonl")
+            .body(Body::from("not json\n"))
+            .unwrap();
+        let response = target_app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Un bloc qui contredit la chaîne locale est signalé aux abonnés
+        let mut events = state.events.subscribe();
+        let mut conflicting = source.chain[2].clone();
+        conflicting.data = "Tampered".to_string();
+        conflicting.hash = conflicting.calculate_hash();
+        let request = Request::post("/import?format=jsonl")
+            .body(Body::from(serde_json::to_string(&conflicting).unwrap()))
+            .unwrap();
+        let response = target_app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(NodeEvent::ValidationFailed {
+                block_index: Some(2),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_light_client_syncs_and_verifies_over_http() {
+        use crate::consensus::ProofOfWork;
+        use crate::light_client::LightClient;
+        use std::sync::Arc;
+
+        let engine = Arc::new(ProofOfWork::new(PowAlgorithm::Sha256d));
+        let mut blockchain = Blockchain::new(1).with_consensus(engine);
+        blockchain.add_block("Coffee".to_string()).unwrap();
+        blockchain
+            .add_block("Alice pays Bob 5\nBob pays Carol 2".to_string())
+            .unwrap();
+        let app = router(AppState::new(blockchain), &NodeConfig::default());
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::get(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, body)
+            }
+        };
+
+        let (status, body) = get("/headers?from=1&limit=10").await;
+        assert_eq!(status, StatusCode::OK);
+        let page: HeadersResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(page.headers.len(), 2);
+
+        let mut client = LightClient::new(page.genesis_hash, page.difficulty);
+        assert_eq!(client.extend(page.from, &page.headers).unwrap(), 2);
+
+        let (status, body) = get("/blocks/2/proof/1").await;
+        assert_eq!(status, StatusCode::OK);
+        let proof: MerkleProofResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(proof.transaction, "Bob pays Carol 2");
+        client
+            .verify_transaction(proof.height, &proof.transaction, &proof.to_proof().unwrap())
+            .unwrap();
+
+        let (status, _) = get("/blocks/2/proof/2").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Le genesis n'a pas d'en-tête Bitcoin
+        let (status, body) = get("/headers?from=0").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code, "headers_unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_validate_incremental_and_full() {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..=3 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        let app = router(AppState::new(blockchain), &NodeConfig::default());
+
+        let validate = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::get(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<ValidateResponse>(&body).unwrap()
+            }
+        };
+
+        let incremental = validate("/validate").await;
+        assert!(incremental.is_valid);
+        assert_eq!(incremental.checked_blocks, 0);
+
+        let full = validate("/validate?full=true").await;
+        assert!(full.is_valid);
+        assert_eq!(full.checked_blocks, 4);
+    }
 }
@@ -0,0 +1,66 @@
+use crate::metrics::metrics;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Instant;
+
+/// Handler pour GET /metrics
+/// Exporte les métriques du nœud au format texte de Prometheus
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics().encode(),
+    )
+}
+
+/// Middleware mesurant la latence de chaque requête par route et par statut
+pub async fn track_latency(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    // Utiliser le motif de la route (/blocks/:index) pour limiter la cardinalité
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let response = next.run(request).await;
+
+    metrics()
+        .http_request_duration_seconds
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .observe(started.elapsed().as_secs_f64());
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{router, AppState};
+    use crate::config::NodeConfig;
+    use crate::Blockchain;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_reports_request_latency() {
+        let app = router(AppState::new(Blockchain::new(1)), &NodeConfig::default());
+
+        let request = Request::get("/blocks/0").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("blockchain_http_request_duration_seconds"));
+        assert!(text.contains(r#"route="/blocks/:index""#));
+    }
+}
@@ -1,5 +1,144 @@
+pub mod auth;
+pub mod error;
 pub mod handlers;
+pub mod metrics;
 pub mod models;
+pub mod openapi;
+pub mod rpc;
+pub mod state;
+pub mod stream;
 
+pub use error::ApiError;
 pub use handlers::*;
 pub use models::*;
+pub use rpc::rpc_handler;
+pub use state::AppState;
+pub use stream::{events_sse, ws_handler};
+
+use crate::config::NodeConfig;
+use axum::{
+    extract::DefaultBodyLimit,
+    http::{header, HeaderName, HeaderValue},
+    middleware,
+    routing::{delete, get, post},
+    Router,
+};
+use std::future::IntoFuture;
+use std::io;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Construire le routeur de l'API avec toutes les routes et les couches
+/// d'authentification, de limite de taille et de CORS
+pub fn router(state: AppState, config: &NodeConfig) -> Router {
+    let api = Router::new()
+        .route("/chain", get(get_chain))
+        .route("/blocks", post(add_block))
+        .route("/blocks/:index", get(get_block))
+        .route("/blocks/hash/:hash", get(get_block_by_hash))
+        .route("/blocks/:index/proof/:position", get(get_transaction_proof))
+        .route("/headers", get(get_headers))
+        .route("/sync/headers", get(get_sync_headers))
+        .route("/sync/status", get(get_sync_status))
+        .route("/peers", get(get_peers).post(add_peer))
+        .route("/peers/:id", delete(remove_peer))
+        .route("/orphans", get(get_orphans))
+        .route("/validate", get(validate_chain))
+        .route("/export", get(export_chain))
+        .route(
+            "/import",
+            post(import_chain).layer(DefaultBodyLimit::max(config.max_import_bytes)),
+        )
+        .route("/events", get(events_sse))
+        .route("/ws", get(ws_handler))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::authorize,
+        ))
+        .route_layer(middleware::from_fn(metrics::track_latency));
+
+    // JSON-RPC : le scope dépend des méthodes appelées, `rpc_handler`
+    // authentifie lui-même la requête
+    let rpc = Router::new()
+        .route("/rpc", post(rpc_handler))
+        .route_layer(middleware::from_fn(metrics::track_latency));
+
+    // Échange entre nœuds, sans clé d'API : limité par IP, l'émetteur
+    // n'étant cru que si son URL désigne l'adresse de la connexion
+    let peer_exchange = Router::new()
+        .route("/peers/addr", post(receive_addr))
+        .route("/peers/blocks", post(receive_block))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::limit_peer_messages,
+        ));
+
+    Router::new()
+        .route("/", get(root))
+        .route("/openapi.json", get(openapi::openapi_json))
+        .route("/swagger-ui", get(openapi::swagger_ui))
+        .route("/metrics", get(metrics::metrics_handler))
+        .merge(peer_exchange)
+        .merge(rpc)
+        .merge(api)
+        .with_state(state)
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .layer(cors_layer(&config.cors_origins))
+}
+
+/// Servir l'API jusqu'au déclenchement de `state.shutdown`
+///
+/// Dès l'arrêt, le serveur n'accepte plus de connexions, le mining en cours
+/// est annulé et les flux d'événements sont fermés ; les requêtes restantes
+/// ont `config.shutdown_timeout` pour se terminer.
+pub async fn serve(listener: TcpListener, state: AppState, config: &NodeConfig) -> io::Result<()> {
+    let shutdown = state.shutdown.clone();
+    let app = router(state, config);
+
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown({
+        let shutdown = shutdown.clone();
+        async move { shutdown.wait().await }
+    });
+
+    let deadline = async {
+        shutdown.wait().await;
+        tokio::time::sleep(config.shutdown_timeout).await;
+    };
+
+    tokio::select! {
+        result = server.into_future() => result,
+        _ = deadline => {
+            tracing::warn!(
+                "Requests still running after {:?}, closing them",
+                config.shutdown_timeout
+            );
+            Ok(())
+        }
+    }
+}
+
+/// CORS permissif si aucune origine n'est configurée, sinon liste blanche
+fn cors_layer(origins: &[String]) -> CorsLayer {
+    if origins.is_empty() || origins.iter().any(|o| o == "*") {
+        return CorsLayer::permissive();
+    }
+
+    let origins: Vec<HeaderValue> = origins
+        .iter()
+        .filter_map(|origin| HeaderValue::from_str(origin).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods(Any)
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            HeaderName::from_static(auth::API_KEY_HEADER),
+        ])
+}
@@ -1,29 +1,191 @@
+use crate::bitcoin::{self, BlockHeader};
 use crate::block::Block;
+use crate::block_store::BlockStore;
+use crate::export::ChainFormat;
+use crate::merkle::MerkleProof;
+use crate::orphans::OrphanInfo;
+use crate::peers::{BannedPeer, PeerInfo};
+use crate::sync::SyncHeader;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 /// Requête pour ajouter un nouveau bloc
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AddBlockRequest {
     pub data: String,
 }
 
 /// Réponse après l'ajout d'un bloc
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AddBlockResponse {
     pub block: Block,
     pub message: String,
 }
 
 /// Réponse pour obtenir toute la chaîne
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChainResponse {
-    pub chain: Vec<Block>,
+    /// Version du format de la chaîne (`storage::CHAIN_FILE_VERSION`) : une
+    /// réponse enregistrée se relit avec `bc-tool`
+    pub version: u32,
+    #[schema(value_type = Vec<Block>)]
+    pub chain: BlockStore,
     pub length: usize,
+    /// true si toute la chaîne a déjà été validée (voir GET /validate)
     pub is_valid: bool,
 }
 
+/// Réponse de validation de la chaîne
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ValidateResponse {
+    pub is_valid: bool,
+    pub chain_length: usize,
+    /// Blocs revérifiés (0 si la chaîne n'a pas changé depuis la dernière validation)
+    pub checked_blocks: usize,
+}
+
+/// Paramètres de GET /validate
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct ValidateQuery {
+    /// Revérifier toute la chaîne depuis le genesis, sans cache ni points de contrôle
+    pub full: Option<bool>,
+}
+
+/// Paramètres de GET /export et POST /import
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct TransferQuery {
+    /// `jsonl` (par défaut) ou `binary`
+    #[param(inline)]
+    pub format: Option<ChainFormat>,
+}
+
+/// Réponse après un import
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportResponse {
+    /// Blocs ajoutés à la chaîne
+    pub imported: usize,
+    /// Blocs déjà présents, ignorés
+    pub skipped: usize,
+    /// Taille de la chaîne après l'import
+    pub length: usize,
+}
+
 /// Réponse en cas d'erreur
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
+    /// Message lisible
     pub error: String,
+    /// Code d'erreur stable (voir `ApiError::code`)
+    pub code: String,
+}
+
+/// Nombre maximal d'en-têtes renvoyés par GET /headers
+pub const MAX_HEADERS: usize = 2000;
+
+/// Paramètres de GET /headers
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct HeadersQuery {
+    /// Hauteur du premier en-tête (1 par défaut : le genesis n'a pas d'en-tête)
+    pub from: Option<u64>,
+    /// Nombre d'en-têtes (2000 au plus, par défaut)
+    pub limit: Option<usize>,
+}
+
+/// Réponse de GET /headers : en-têtes Bitcoin de 80 octets, en hexadécimal
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeadersResponse {
+    /// Hash du genesis, ancre de confiance du client léger
+    pub genesis_hash: String,
+    /// Difficulté de la chaîne
+    pub difficulty: usize,
+    /// Hauteur du premier en-tête renvoyé
+    pub from: u64,
+    /// En-têtes consécutifs, 160 caractères hexadécimaux chacun
+    #[schema(value_type = Vec<String>)]
+    pub headers: Vec<BlockHeader>,
+}
+
+/// Preuve de Merkle d'une transaction, hashes affichés (octets inversés)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MerkleProofResponse {
+    /// Hauteur du bloc
+    pub height: u64,
+    /// Position de la transaction dans le bloc (ligne de données)
+    pub position: usize,
+    pub transaction: String,
+    pub txid: String,
+    /// Position de la feuille dans l'arbre (la feuille 0 engage la hauteur)
+    pub leaf_index: usize,
+    /// Voisins du bas vers le haut
+    pub siblings: Vec<String>,
+    pub merkle_root: String,
+}
+
+impl MerkleProofResponse {
+    /// Relire la preuve pour la vérifier
+    ///
+    /// # Returns
+    /// None si un voisin n'est pas un hash valide
+    pub fn to_proof(&self) -> Option<MerkleProof> {
+        Some(MerkleProof {
+            leaf_index: self.leaf_index,
+            siblings: self
+                .siblings
+                .iter()
+                .map(|sibling| bitcoin::from_display_hex(sibling))
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// Réponse de GET /sync/headers : en-têtes complets pour la synchronisation
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SyncHeadersResponse {
+    /// Hash du genesis : un pair d'une autre chaîne est ignoré
+    pub genesis_hash: String,
+    /// Hauteur du sommet du nœud
+    pub height: u64,
+    /// Hauteur du premier en-tête renvoyé
+    pub from: u64,
+    pub headers: Vec<SyncHeader>,
+}
+
+/// Corps de POST /peers
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AddPeerRequest {
+    /// API REST du pair (`http://hôte:port`)
+    pub url: String,
+}
+
+/// Paramètres de DELETE /peers/:id
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct DisconnectQuery {
+    /// Bannir aussi le pair (false par défaut)
+    pub ban: Option<bool>,
+}
+
+/// Réponse de GET /peers
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PeersResponse {
+    pub connections: Vec<PeerInfo>,
+    pub banned: Vec<BannedPeer>,
+    /// Nombre d'adresses du carnet
+    pub known_addresses: usize,
+}
+
+/// Corps de POST /peers/blocks : bloc miné relayé par un pair
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BlockMessage {
+    /// URL publique de l'émetteur, à qui les parents manquants sont demandés
+    pub from: Option<String>,
+    pub block: Block,
+}
+
+/// Réponse de GET /orphans
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrphansResponse {
+    pub count: usize,
+    /// Taille maximale de la réserve
+    pub max_orphans: usize,
+    pub orphans: Vec<OrphanInfo>,
 }
@@ -0,0 +1,130 @@
+use crate::api::{handlers, models, rpc, stream};
+use crate::events::{EventTopic, NodeEvent};
+use crate::export::ChainFormat;
+use crate::orphans::{BlockStatus, OrphanInfo};
+use crate::peers::{AddrMessage, BannedPeer, Direction, PeerInfo};
+use crate::proof_of_work::PowAlgorithm;
+use crate::sync::{SyncHeader, SyncPhase, SyncStatus};
+use crate::Block;
+use axum::{response::Html, Json};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// Spécification OpenAPI 3 générée à partir des handlers et des modèles
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Mini Blockchain API",
+        description = "REST, JSON-RPC and event streaming API of the mini-blockchain node"
+    ),
+    paths(
+        handlers::get_chain,
+        handlers::add_block,
+        handlers::get_block,
+        handlers::get_block_by_hash,
+        handlers::get_transaction_proof,
+        handlers::get_headers,
+        handlers::get_sync_headers,
+        handlers::get_sync_status,
+        handlers::get_peers,
+        handlers::add_peer,
+        handlers::remove_peer,
+        handlers::receive_addr,
+        handlers::receive_block,
+        handlers::get_orphans,
+        handlers::validate_chain,
+        handlers::export_chain,
+        handlers::import_chain,
+        rpc::rpc_handler,
+        stream::events_sse,
+        stream::ws_handler,
+    ),
+    components(schemas(
+        Block,
+        models::AddBlockRequest,
+        models::AddBlockResponse,
+        models::ChainResponse,
+        models::ValidateResponse,
+        models::ImportResponse,
+        models::HeadersResponse,
+        models::MerkleProofResponse,
+        models::SyncHeadersResponse,
+        SyncHeader,
+        SyncPhase,
+        SyncStatus,
+        models::AddPeerRequest,
+        models::PeersResponse,
+        PeerInfo,
+        BannedPeer,
+        Direction,
+        AddrMessage,
+        models::BlockMessage,
+        models::OrphansResponse,
+        OrphanInfo,
+        BlockStatus,
+        ChainFormat,
+        PowAlgorithm,
+        models::ErrorResponse,
+        NodeEvent,
+        EventTopic,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "chain", description = "Whole-chain queries"),
+        (name = "blocks", description = "Block queries and mining"),
+        (name = "light", description = "Header sync and Merkle proofs for light clients"),
+        (name = "sync", description = "Headers-first initial block download"),
+        (name = "peers", description = "Peer connections, address gossip, bans and block relay"),
+        (name = "rpc", description = "JSON-RPC 2.0 interface"),
+        (name = "events", description = "Real-time event streams")
+    )
+)]
+pub struct ApiDoc;
+
+/// Déclarer les schémas d'authentification (Bearer et en-tête X-API-Key)
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+    }
+}
+
+/// Handler pour GET /openapi.json
+/// Retourne la spécification OpenAPI
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Handler pour GET /swagger-ui
+/// Page Swagger UI (chargée depuis un CDN) pointant sur /openapi.json
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_HTML)
+}
+
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Mini Blockchain API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
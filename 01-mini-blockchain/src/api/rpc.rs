@@ -0,0 +1,507 @@
+use crate::api::auth::{ApiSecurity, Scope};
+use crate::api::error::ApiError;
+use crate::api::handlers;
+use crate::blockchain::{BlockchainError, ValidationMode};
+use crate::chain_service::ChainHandle;
+use crate::Block;
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Codes d'erreur standard JSON-RPC 2.0
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Codes applicatifs (mêmes valeurs que bitcoind)
+pub const BLOCK_NOT_FOUND: i64 = -5;
+pub const BLOCK_REJECTED: i64 = -25;
+
+/// Objet d'erreur JSON-RPC
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    /// Code d'erreur stable de l'API REST (`{"error_code": "..."}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<ApiError> for RpcError {
+    fn from(error: ApiError) -> Self {
+        let code = match &error {
+            ApiError::BlockNotFound => BLOCK_NOT_FOUND,
+            // Données refusées avant tout mining : paramètre invalide
+            ApiError::Chain(BlockchainError::EmptyData)
+            | ApiError::Chain(BlockchainError::InvalidTransactions) => INVALID_PARAMS,
+            ApiError::Chain(BlockchainError::MiningFailed)
+            | ApiError::Chain(BlockchainError::MiningCancelled) => INTERNAL_ERROR,
+            ApiError::Chain(_) => BLOCK_REJECTED,
+            ApiError::InvalidRequest(_) => INVALID_PARAMS,
+            _ => INTERNAL_ERROR,
+        };
+        Self {
+            code,
+            message: error.to_string(),
+            data: Some(serde_json::json!({ "error_code": error.code() })),
+        }
+    }
+}
+
+/// Réponse JSON-RPC (soit `result`, soit `error`)
+#[derive(Debug, Serialize)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Value,
+}
+
+impl RpcResponse {
+    fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    fn failure(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(error),
+            id,
+        }
+    }
+}
+
+/// Handler pour POST /rpc
+/// Traite un appel JSON-RPC 2.0 simple ou un lot d'appels
+#[utoipa::path(
+    post,
+    path = "/rpc",
+    tag = "rpc",
+    request_body(content = Object, description = "JSON-RPC 2.0 request or batch"),
+    responses(
+        (status = 200, description = "JSON-RPC response or batch of responses", body = Object),
+        (status = 204, description = "Only notifications were sent"),
+        (status = 401, description = "Missing or invalid API key", body = ErrorResponse),
+        (status = 403, description = "A write method was called with a read-only API key", body = ErrorResponse),
+        (status = 429, description = "Rate limit exceeded by a write method", body = ErrorResponse)
+    ),
+    security((), ("bearer" = []), ("api_key" = []))
+)]
+pub async fn rpc_handler(
+    State(chain): State<ChainHandle>,
+    State(security): State<Arc<ApiSecurity>>,
+    remote: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    // Le scope est celui de la méthode la plus exigeante de la requête, et
+    // chaque appel d'écriture d'un lot consomme un jeton des limites de débit
+    let payload = serde_json::from_slice::<Value>(&body);
+    let writes = payload.as_ref().map_or(0, write_calls);
+    let required = if writes > 0 {
+        Scope::Write
+    } else {
+        Scope::Read
+    };
+    let ip = remote.map(|ConnectInfo(addr)| addr.ip());
+    security.check_writes(&headers, ip, required, writes)?;
+
+    Ok(match process_payload(&chain, payload).await {
+        Some(response) => Json(response).into_response(),
+        // Uniquement des notifications : aucune réponse à renvoyer
+        None => StatusCode::NO_CONTENT.into_response(),
+    })
+}
+
+/// Scope nécessaire pour appeler `method`
+fn method_scope(method: &str) -> Scope {
+    match method {
+        "submitblock" | "generateblock" => Scope::Write,
+        _ => Scope::Read,
+    }
+}
+
+/// Nombre d'appels d'écriture d'un appel ou d'un lot d'appels
+fn write_calls(payload: &Value) -> u32 {
+    let is_write = |call: &Value| {
+        call.get("method")
+            .and_then(Value::as_str)
+            .is_some_and(|method| method_scope(method) == Scope::Write)
+    };
+    match payload {
+        Value::Array(calls) => {
+            let writes = calls.iter().filter(|call| is_write(call)).count();
+            u32::try_from(writes).unwrap_or(u32::MAX)
+        }
+        call => u32::from(is_write(call)),
+    }
+}
+
+/// Traiter le corps brut d'une requête JSON-RPC
+///
+/// # Returns
+/// La réponse à renvoyer, ou None si la requête ne contenait que des notifications
+pub async fn process(chain: &ChainHandle, body: &[u8]) -> Option<Value> {
+    process_payload(chain, serde_json::from_slice(body)).await
+}
+
+/// Traiter une requête JSON-RPC déjà décodée (ou son erreur de décodage)
+async fn process_payload(
+    chain: &ChainHandle,
+    payload: Result<Value, serde_json::Error>,
+) -> Option<Value> {
+    let payload = match payload {
+        Ok(payload) => payload,
+        Err(e) => {
+            let error = RpcError::new(PARSE_ERROR, format!("Parse error: {}", e));
+            return Some(to_value(RpcResponse::failure(Value::Null, error)));
+        }
+    };
+
+    match payload {
+        Value::Array(calls) if calls.is_empty() => {
+            let error = RpcError::new(INVALID_REQUEST, "Empty batch");
+            Some(to_value(RpcResponse::failure(Value::Null, error)))
+        }
+        Value::Array(calls) => {
+            // Les appels d'un lot sont exécutés dans l'ordre
+            let mut responses = Vec::new();
+            for call in calls {
+                if let Some(response) = process_call(chain, call).await {
+                    responses.push(to_value(response));
+                }
+            }
+            if responses.is_empty() {
+                None
+            } else {
+                Some(Value::Array(responses))
+            }
+        }
+        call => process_call(chain, call).await.map(to_value),
+    }
+}
+
+/// Traiter un appel unique
+///
+/// # Returns
+/// La réponse, ou None s'il s'agit d'une notification (pas de champ `id`)
+async fn process_call(chain: &ChainHandle, call: Value) -> Option<RpcResponse> {
+    let Value::Object(call) = call else {
+        let error = RpcError::new(INVALID_REQUEST, "Request must be an object");
+        return Some(RpcResponse::failure(Value::Null, error));
+    };
+
+    let is_notification = !call.contains_key("id");
+    let id = call.get("id").cloned().unwrap_or(Value::Null);
+
+    let result = match parse_call(&call) {
+        Ok((method, params)) => dispatch(chain, method, params).await,
+        Err(error) => Err(error),
+    };
+
+    if is_notification {
+        return None;
+    }
+    Some(match result {
+        Ok(value) => RpcResponse::success(id, value),
+        Err(error) => RpcResponse::failure(id, error),
+    })
+}
+
+/// Vérifier l'enveloppe JSON-RPC et extraire la méthode et les paramètres
+fn parse_call(call: &Map<String, Value>) -> Result<(&str, &Value), RpcError> {
+    if call.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+    if let Some(id) = call.get("id") {
+        if !(id.is_string() || id.is_number() || id.is_null()) {
+            return Err(RpcError::new(
+                INVALID_REQUEST,
+                "id must be a string, number or null",
+            ));
+        }
+    }
+    let method = call
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_REQUEST, "method must be a string"))?;
+
+    let params = call.get("params").unwrap_or(&Value::Null);
+    if !(params.is_array() || params.is_object() || params.is_null()) {
+        return Err(RpcError::new(
+            INVALID_REQUEST,
+            "params must be an array or an object",
+        ));
+    }
+    Ok((method, params))
+}
+
+/// Appeler l'opération correspondant à la méthode
+async fn dispatch(chain: &ChainHandle, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "getblockcount" => Ok(Value::from(chain.snapshot().latest_block().index)),
+        "getdifficulty" => Ok(Value::from(chain.snapshot().difficulty)),
+        "getblockhash" => {
+            let height = param(params, 0, "height")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected block height"))?;
+            let block = handlers::find_block_by_index(chain, height)?;
+            Ok(Value::from(block.hash))
+        }
+        "getblock" => {
+            // Accepte un hash (comme bitcoind) ou un index
+            let block = match param(params, 0, "hash").or_else(|| param(params, 0, "height")) {
+                Some(Value::String(hash)) => handlers::find_block_by_hash(chain, hash)?,
+                Some(Value::Number(height)) => match height.as_u64() {
+                    Some(height) => handlers::find_block_by_index(chain, height)?,
+                    None => return Err(ApiError::BlockNotFound.into()),
+                },
+                _ => {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        "Expected block hash or height",
+                    ))
+                }
+            };
+            Ok(to_value(block))
+        }
+        "submitblock" => {
+            // Bloc déjà scellé (miné ou signé ailleurs), comme chez bitcoind
+            let block = param(params, 0, "block")
+                .and_then(|block| Block::deserialize(block).ok())
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected a block object"))?;
+            let block = handlers::submit_block(chain, block).await?;
+            Ok(to_value(block))
+        }
+        "generateblock" => {
+            let data = param(params, 0, "data")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected block data string"))?;
+            let block = handlers::mine_and_append(chain, data.to_string()).await?;
+            Ok(to_value(block))
+        }
+        "validatechain" => {
+            // Paramètre optionnel `full` (booléen), comme GET /validate?full=true
+            let mode = match param(params, 0, "full").and_then(Value::as_bool) {
+                Some(true) => ValidationMode::Full,
+                _ => ValidationMode::Incremental,
+            };
+            Ok(to_value(handlers::validate(chain, mode).await?))
+        }
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", other),
+        )),
+    }
+}
+
+/// Lire un paramètre positionnel (tableau) ou nommé (objet)
+fn param<'a>(params: &'a Value, position: usize, name: &str) -> Option<&'a Value> {
+    match params {
+        Value::Array(values) => values.get(position),
+        Value::Object(values) => values.get(name),
+        _ => None,
+    }
+}
+
+/// Sérialiser une valeur en JSON (les types de l'API sont toujours sérialisables)
+fn to_value<T: Serialize>(value: T) -> Value {
+    serde_json::to_value(value).expect("API types serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventBus, NodeEvent};
+    use crate::shutdown::Shutdown;
+    use crate::Blockchain;
+    use serde_json::json;
+
+    fn setup() -> ChainHandle {
+        ChainHandle::spawn(Blockchain::new(1), EventBus::default(), Shutdown::new())
+    }
+
+    async fn call(chain: &ChainHandle, request: Value) -> Value {
+        process(chain, request.to_string().as_bytes())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_single_calls() {
+        let chain = setup();
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "getblockcount", "id": 1}),
+        )
+        .await;
+        assert_eq!(response, json!({"jsonrpc": "2.0", "result": 0, "id": 1}));
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "generateblock", "params": ["tx"], "id": "a"}),
+        )
+        .await;
+        let hash = response["result"]["hash"].as_str().unwrap().to_string();
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "getblockhash", "params": [1], "id": 2}),
+        )
+        .await;
+        assert_eq!(response["result"], json!(hash));
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "getblock", "params": {"hash": hash}, "id": 3}),
+        )
+        .await;
+        assert_eq!(response["result"]["data"], "tx");
+    }
+
+    #[tokio::test]
+    async fn test_submitblock_appends_a_sealed_block() {
+        let chain = setup();
+        let mut events = chain.subscribe();
+        let mut miner = Blockchain::clone(&chain.snapshot());
+        let block = miner
+            .add_block("Mined elsewhere".to_string())
+            .unwrap()
+            .clone();
+
+        let submit = |block: &Block| json!({"jsonrpc": "2.0", "method": "submitblock", "params": [block], "id": 1});
+        let response = call(&chain, submit(&block)).await;
+        assert_eq!(response["result"]["hash"], json!(block.hash));
+        assert_eq!(chain.snapshot().latest_block(), &block);
+        assert!(matches!(
+            events.recv().await,
+            Ok(NodeEvent::BlockAdded { block: added }) if added == block
+        ));
+
+        // Le bloc n'est ni reminé ni accepté deux fois
+        let response = call(&chain, submit(&block)).await;
+        assert_eq!(response["error"]["code"], BLOCK_REJECTED);
+        let mut tampered = miner.add_block("Block 2".to_string()).unwrap().clone();
+        tampered.data = "Tampered".to_string();
+        let response = call(&chain, submit(&tampered)).await;
+        assert_eq!(response["error"]["data"]["error_code"], "invalid_hash");
+        assert_eq!(chain.snapshot().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_with_notification() {
+        let chain = setup();
+        let batch = json!([
+            {"jsonrpc": "2.0", "method": "getdifficulty", "id": 1},
+            {"jsonrpc": "2.0", "method": "validatechain"},
+            {"jsonrpc": "2.0", "method": "unknown", "id": 2}
+        ]);
+
+        let response = call(&chain, batch).await;
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["result"], 1);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+
+        // Un lot composé uniquement de notifications ne produit aucune réponse
+        let only_notifications = json!([{"jsonrpc": "2.0", "method": "getblockcount"}]);
+        assert!(process(&chain, only_notifications.to_string().as_bytes())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_error_codes() {
+        let chain = setup();
+
+        let response = process(&chain, b"{not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response = call(&chain, json!([])).await;
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "1.0", "method": "getblockcount", "id": 1}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "getblockhash", "params": ["x"], "id": 1}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "getblock", "params": [42], "id": 1}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], BLOCK_NOT_FOUND);
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "generateblock", "params": [""], "id": 1}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert_eq!(response["error"]["data"]["error_code"], "empty_data");
+
+        // Données ambiguës pour la racine de Merkle d'un en-tête Bitcoin
+        let bitcoin = ChainHandle::spawn(
+            Blockchain::new(1).with_consensus(Arc::new(crate::consensus::ProofOfWork::new(
+                crate::proof_of_work::PowAlgorithm::Sha256d,
+            ))),
+            EventBus::default(),
+            Shutdown::new(),
+        );
+        let response = call(
+            &bitcoin,
+            json!({"jsonrpc": "2.0", "method": "generateblock", "params": ["tx\ntx"], "id": 1}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert_eq!(
+            response["error"]["data"]["error_code"],
+            "invalid_transactions"
+        );
+
+        let response = call(
+            &chain,
+            json!({"jsonrpc": "2.0", "method": "submitblock", "params": ["tx"], "id": 1}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+}
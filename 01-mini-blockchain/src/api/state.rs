@@ -0,0 +1,111 @@
+use crate::api::auth::ApiSecurity;
+use crate::blockchain::Blockchain;
+use crate::chain_service::ChainHandle;
+use crate::events::EventBus;
+use crate::orphans::OrphanPool;
+use crate::peers::{PeerConfig, PeerManager};
+use crate::shutdown::Shutdown;
+use crate::sync::SyncTracker;
+use axum::extract::FromRef;
+use std::sync::Arc;
+
+/// État partagé par tous les handlers de l'API
+#[derive(Clone)]
+pub struct AppState {
+    pub chain: ChainHandle,
+    pub events: EventBus,
+    pub security: Arc<ApiSecurity>,
+    pub shutdown: Shutdown,
+    /// Avancement de la synchronisation initiale
+    pub sync: SyncTracker,
+    /// Connexions et carnet d'adresses des pairs
+    pub peers: PeerManager,
+    /// Blocs relayés en attente de leur parent
+    pub orphans: OrphanPool,
+}
+
+impl AppState {
+    /// Créer l'état de l'application à partir d'une blockchain
+    ///
+    /// Démarre le service de chaîne : doit être appelé dans un runtime tokio.
+    pub fn new(blockchain: Blockchain) -> Self {
+        let events = EventBus::default();
+        let shutdown = Shutdown::new();
+        Self {
+            chain: ChainHandle::spawn(blockchain, events.clone(), shutdown.clone()),
+            events,
+            security: Arc::new(ApiSecurity::disabled()),
+            shutdown,
+            sync: SyncTracker::default(),
+            peers: PeerManager::new(PeerConfig::default()),
+            orphans: OrphanPool::default(),
+        }
+    }
+
+    /// Remplacer la politique d'accès (désactivée par défaut)
+    pub fn with_security(mut self, security: ApiSecurity) -> Self {
+        self.security = Arc::new(security);
+        self
+    }
+
+    /// Remplacer le gestionnaire de pairs (sans seed ni persistance par défaut)
+    pub fn with_peers(mut self, peers: PeerManager) -> Self {
+        self.peers = peers;
+        self
+    }
+
+    /// Remplacer la réserve d'orphelins (100 blocs, 20 minutes par défaut)
+    pub fn with_orphans(mut self, orphans: OrphanPool) -> Self {
+        self.orphans = orphans;
+        self
+    }
+}
+
+// Permet aux handlers d'extraire directement `State<ChainHandle>`
+impl FromRef<AppState> for ChainHandle {
+    fn from_ref(state: &AppState) -> Self {
+        state.chain.clone()
+    }
+}
+
+// Permet aux handlers d'extraire directement `State<EventBus>`
+impl FromRef<AppState> for EventBus {
+    fn from_ref(state: &AppState) -> Self {
+        state.events.clone()
+    }
+}
+
+// Permet au middleware d'authentification d'extraire `State<Arc<ApiSecurity>>`
+impl FromRef<AppState> for Arc<ApiSecurity> {
+    fn from_ref(state: &AppState) -> Self {
+        state.security.clone()
+    }
+}
+
+// Permet aux handlers d'extraire directement `State<Shutdown>`
+impl FromRef<AppState> for Shutdown {
+    fn from_ref(state: &AppState) -> Self {
+        state.shutdown.clone()
+    }
+}
+
+// Permet aux handlers d'extraire directement `State<SyncTracker>`
+impl FromRef<AppState> for SyncTracker {
+    fn from_ref(state: &AppState) -> Self {
+        state.sync.clone()
+    }
+}
+
+// Permet aux handlers d'extraire directement `State<PeerManager>`
+impl FromRef<AppState> for PeerManager {
+    fn from_ref(state: &AppState) -> Self {
+        state.peers.clone()
+    }
+}
+
+// Permet aux handlers d'extraire directement `State<OrphanPool>`
+impl FromRef<AppState> for OrphanPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.orphans.clone()
+    }
+}
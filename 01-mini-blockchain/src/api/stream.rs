@@ -0,0 +1,215 @@
+use crate::api::error::ApiError;
+use crate::events::{EventBus, EventTopic, NodeEvent};
+use crate::shutdown::Shutdown;
+use axum::{
+    extract::{
+        rejection::QueryRejection,
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, WatchStream},
+    Stream, StreamExt,
+};
+use utoipa::IntoParams;
+
+/// Paramètres de requête communs à /events et /ws
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TopicsQuery {
+    /// Liste de sujets séparés par des virgules (tous par défaut)
+    pub topics: Option<String>,
+}
+
+/// Commande envoyée par un client WebSocket
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum WsCommand {
+    Subscribe { topics: Vec<EventTopic> },
+    Unsubscribe { topics: Vec<EventTopic> },
+}
+
+/// Parser le filtre de sujets, ou retourner une erreur 400
+fn parse_topics(topics: Option<&str>) -> Result<Vec<EventTopic>, ApiError> {
+    match topics {
+        None => Ok(EventTopic::ALL.to_vec()),
+        Some(list) => EventTopic::parse_list(list).map_err(ApiError::InvalidRequest),
+    }
+}
+
+/// Handler pour GET /events
+/// Diffuse les événements du nœud en Server-Sent Events
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "events",
+    params(TopicsQuery),
+    responses(
+        (status = 200, description = "Server-Sent Events stream", body = NodeEvent, content_type = "text/event-stream"),
+        (status = 400, description = "Unknown topic", body = ErrorResponse)
+    )
+)]
+pub async fn events_sse(
+    State(events): State<EventBus>,
+    State(shutdown): State<Shutdown>,
+    query: Result<Query<TopicsQuery>, QueryRejection>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let Query(query) = query?;
+    let topics = parse_topics(query.topics.as_deref())?;
+
+    // Ne garder que les événements des sujets demandés
+    let stream = BroadcastStream::new(events.subscribe()).filter_map(move |message| match message {
+        Ok(event) if topics.contains(&event.topic()) => {
+            Some(Event::default().event(event.name()).json_data(&event))
+        }
+        Ok(_) => None,
+        // Abonné trop lent : signaler le nombre d'événements perdus
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            Some(Ok(Event::default().event("lagged").data(skipped.to_string())))
+        }
+    });
+
+    // Terminer le flux à l'arrêt du nœud pour ne pas bloquer l'arrêt gracieux
+    let stop = WatchStream::new(shutdown.subscribe())
+        .filter(|triggered| *triggered)
+        .map(|_| None);
+    let stream = stream
+        .map(Some)
+        .merge(stop)
+        .take_while(|item| item.is_some())
+        .filter_map(|item| item);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Handler pour GET /ws
+/// Ouvre une connexion WebSocket avec abonnement par sujets
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "events",
+    params(TopicsQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade, events are sent as JSON text frames"),
+        (status = 400, description = "Unknown topic", body = ErrorResponse)
+    )
+)]
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(events): State<EventBus>,
+    State(shutdown): State<Shutdown>,
+    query: Result<Query<TopicsQuery>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query) = query?;
+    let topics = parse_topics(query.topics.as_deref())?;
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, events, shutdown, topics)))
+}
+
+/// Boucle d'une connexion WebSocket : commandes du client et événements du bus
+async fn handle_socket(
+    mut socket: WebSocket,
+    events: EventBus,
+    shutdown: Shutdown,
+    mut topics: Vec<EventTopic>,
+) {
+    let mut receiver = events.subscribe();
+
+    loop {
+        tokio::select! {
+            // Fermer proprement la connexion à l'arrêt du nœud
+            _ = shutdown.wait() => {
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: close_code::AWAY,
+                        reason: "node shutting down".into(),
+                    })))
+                    .await;
+                break;
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = match serde_json::from_str::<WsCommand>(&text) {
+                        Ok(command) => {
+                            apply_command(&mut topics, command);
+                            serde_json::json!({ "subscribed": topics })
+                        }
+                        Err(e) => serde_json::json!({ "error": format!("Invalid command: {}", e) }),
+                    };
+                    if socket.send(Message::Text(reply.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Ping/pong et messages binaires ignorés
+                Some(Ok(_)) => {}
+            },
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if !topics.contains(&event.topic()) {
+                        continue;
+                    }
+                    if send_event(&mut socket, &event).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+}
+
+/// Appliquer une commande d'abonnement à la liste de sujets
+fn apply_command(topics: &mut Vec<EventTopic>, command: WsCommand) {
+    match command {
+        WsCommand::Subscribe { topics: added } => {
+            for topic in added {
+                if !topics.contains(&topic) {
+                    topics.push(topic);
+                }
+            }
+        }
+        WsCommand::Unsubscribe { topics: removed } => {
+            topics.retain(|topic| !removed.contains(topic));
+        }
+    }
+}
+
+/// Envoyer un événement sérialisé en JSON sur la WebSocket
+async fn send_event(socket: &mut WebSocket, event: &NodeEvent) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_command() {
+        let mut topics = vec![EventTopic::Blocks];
+
+        let command: WsCommand =
+            serde_json::from_str(r#"{"action":"subscribe","topics":["reorgs","blocks"]}"#).unwrap();
+        apply_command(&mut topics, command);
+        assert_eq!(topics, vec![EventTopic::Blocks, EventTopic::Reorgs]);
+
+        let command: WsCommand =
+            serde_json::from_str(r#"{"action":"unsubscribe","topics":["blocks"]}"#).unwrap();
+        apply_command(&mut topics, command);
+        assert_eq!(topics, vec![EventTopic::Reorgs]);
+    }
+
+    #[test]
+    fn test_parse_topics_default_and_invalid() {
+        assert_eq!(parse_topics(None).unwrap(), EventTopic::ALL.to_vec());
+        let error = parse_topics(Some("nope")).unwrap_err();
+        assert_eq!(error.code(), "invalid_request");
+    }
+}
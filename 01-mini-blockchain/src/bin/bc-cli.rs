@@ -0,0 +1,669 @@
+//! Client en ligne de commande pour interagir avec un nœud mini-blockchain
+//!
+//! Les réponses sont désérialisées avec les mêmes types (`api::models`) que
+//! ceux utilisés par le serveur, ce qui évite toute divergence.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use mini_blockchain::api::models::{
+    AddBlockRequest, AddBlockResponse, ChainResponse, ErrorResponse, HeadersResponse,
+    ImportResponse, MerkleProofResponse, ValidateResponse,
+};
+use mini_blockchain::consensus::{DoubleSignEvidence, StakeAction, StakeChange, ValidatorVote};
+use mini_blockchain::export::ChainFormat;
+use mini_blockchain::light_client::LightClient;
+use mini_blockchain::wallet::{SignedTransfer, Wallet};
+use mini_blockchain::{Block, NodeEvent};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Client en ligne de commande du nœud mini-blockchain
+#[derive(Parser, Debug)]
+#[command(name = "bc-cli", version, about)]
+struct Cli {
+    /// URL du nœud
+    #[arg(long, env = "BC_NODE", default_value = "http://localhost:8090")]
+    node: String,
+
+    /// Clé d'API (envoyée en `Authorization: Bearer`)
+    #[arg(long, env = "BC_API_KEY")]
+    api_key: Option<String>,
+
+    /// Format de sortie
+    #[arg(long, short, global = true, value_enum, default_value_t = Output::Table)]
+    output: Output,
+
+    /// Fichier du wallet utilisé par `wallet` et `send`
+    #[arg(long, env = "BC_WALLET", default_value = "wallet.json")]
+    wallet: PathBuf,
+
+    /// Fichier des en-têtes vérifiés par `light`
+    #[arg(long, env = "BC_LIGHT_STATE", default_value = "light-client.json")]
+    light_state: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Format de sortie des commandes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Afficher toute la chaîne
+    Chain,
+
+    /// Afficher un bloc par son index ou son hash
+    Block {
+        /// Index (hauteur) ou hash du bloc
+        id: String,
+    },
+
+    /// Miner et ajouter un bloc contenant `data`
+    Add { data: String },
+
+    /// Voter pour ajouter (ou retirer) un validateur (Proof of Authority)
+    ///
+    /// Le vote est un bloc signé par le nœud, qui doit être le validateur
+    /// dont c'est le tour.
+    Vote {
+        /// Clé publique du validateur
+        validator: String,
+
+        /// Voter pour le retirer plutôt que pour l'ajouter
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Dénoncer un validateur qui a signé deux blocs à la même hauteur (Proof of Stake)
+    ///
+    /// Les deux blocs sont lus depuis des fichiers JSON (`block <id> -o json`).
+    Slash {
+        /// Clé publique du validateur fautif
+        validator: String,
+
+        /// Premier bloc signé
+        first: PathBuf,
+
+        /// Second bloc signé à la même hauteur
+        second: PathBuf,
+    },
+
+    /// Déléguer ou retirer une partie de la mise du wallet (Proof of Stake)
+    ///
+    /// La mise déléguée ne compte pour le tirage qu'après quelques blocs.
+    Stake {
+        /// Montant de la mise
+        amount: u64,
+
+        /// Clé publique du validateur qui reçoit la mise (sinon, retrait)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Numéro de l'opération du wallet : 0 pour la première, puis +1
+        #[arg(long, default_value_t = 0)]
+        nonce: u64,
+    },
+
+    /// Valider la chaîne du nœud
+    Validate {
+        /// Revérifier toute la chaîne depuis le genesis
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Afficher les derniers blocs, et les suivants avec --follow
+    Tail {
+        /// Nombre de blocs à afficher
+        #[arg(short = 'n', long, default_value_t = 10)]
+        lines: usize,
+
+        /// Continuer à afficher les nouveaux blocs (flux /events)
+        #[arg(short, long)]
+        follow: bool,
+    },
+
+    /// Télécharger un export de la chaîne (GET /export)
+    Export {
+        #[arg(long, short, default_value = "jsonl")]
+        format: ChainFormat,
+
+        /// Fichier de sortie (sortie standard par défaut)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Envoyer un export au nœud (POST /import)
+    Import {
+        file: PathBuf,
+
+        #[arg(long, short, default_value = "jsonl")]
+        format: ChainFormat,
+    },
+
+    /// Gérer le wallet local
+    #[command(subcommand)]
+    Wallet(WalletCommand),
+
+    /// Signer un transfert avec le wallet et l'ajouter à la chaîne
+    Send {
+        /// Adresse du destinataire
+        to: String,
+        amount: u64,
+    },
+
+    /// Client léger : en-têtes seulement, preuves de Merkle (chaîne `sha256d`)
+    #[command(subcommand)]
+    Light(LightCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum LightCommand {
+    /// Télécharger et vérifier les nouveaux en-têtes (GET /headers)
+    ///
+    /// Au premier lancement, le genesis et la difficulté annoncés par le nœud
+    /// deviennent l'ancre de confiance.
+    Sync,
+
+    /// Vérifier qu'une transaction est incluse dans un bloc déjà synchronisé
+    Verify {
+        /// Hauteur du bloc
+        height: u64,
+
+        /// Position de la transaction dans le bloc (ligne de données)
+        position: usize,
+
+        /// Transaction attendue (par défaut, celle renvoyée par le nœud)
+        #[arg(long)]
+        tx: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WalletCommand {
+    /// Générer un nouveau wallet
+    New {
+        /// Écraser le fichier s'il existe déjà
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Afficher l'adresse et la clé publique du wallet
+    Show,
+}
+
+/// Client HTTP du nœud
+struct NodeClient {
+    http: reqwest::Client,
+    base: String,
+    api_key: Option<String>,
+}
+
+impl NodeClient {
+    fn new(base: &str, api_key: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base: base.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self.http.request(method, format!("{}{}", self.base, path));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self.request(reqwest::Method::GET, path).send().await;
+        parse_response(response, &self.base).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        let response = self
+            .request(reqwest::Method::POST, path)
+            .json(body)
+            .send()
+            .await;
+        parse_response(response, &self.base).await
+    }
+
+    /// Télécharger l'export brut de la chaîne
+    async fn export(&self, format: ChainFormat) -> Result<Vec<u8>> {
+        let response = self
+            .request(reqwest::Method::GET, &format!("/export?format={}", format))
+            .send()
+            .await
+            .with_context(|| format!("Cannot reach node at {}", self.base))?;
+        if !response.status().is_success() {
+            bail!("Export failed with HTTP {}", response.status());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Rechercher un bloc par index si `id` est numérique, sinon par hash
+    async fn block(&self, id: &str) -> Result<Block> {
+        match id.parse::<u64>() {
+            Ok(index) => self.get(&format!("/blocks/{}", index)).await,
+            Err(_) => self.get(&format!("/blocks/hash/{}", id)).await,
+        }
+    }
+}
+
+/// Convertir une réponse HTTP en type attendu, ou en erreur lisible
+async fn parse_response<T: DeserializeOwned>(
+    response: reqwest::Result<reqwest::Response>,
+    node: &str,
+) -> Result<T> {
+    let response = response.with_context(|| format!("Cannot reach node at {}", node))?;
+    let status = response.status();
+
+    if status.is_success() {
+        return response.json().await.context("Unexpected response body");
+    }
+
+    match response.json::<ErrorResponse>().await {
+        Ok(error) => bail!("{} ({}, HTTP {})", error.error, error.code, status.as_u16()),
+        Err(_) => bail!("Request failed with HTTP {}", status),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli).await {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let client = NodeClient::new(&cli.node, cli.api_key.clone());
+
+    match cli.command {
+        Command::Chain => {
+            let chain: ChainResponse = client.get("/chain").await?;
+            match cli.output {
+                Output::Json => print_json(&chain)?,
+                Output::Table => {
+                    print_blocks(&chain.chain.to_vec());
+                    println!(
+                        "\n{} blocks, chain is {}",
+                        chain.length,
+                        if chain.is_valid { "valid" } else { "INVALID" }
+                    );
+                }
+            }
+        }
+        Command::Block { id } => {
+            let block = client.block(&id).await?;
+            match cli.output {
+                Output::Json => print_json(&block)?,
+                Output::Table => print_block_details(&block),
+            }
+        }
+        Command::Add { data } => {
+            let response: AddBlockResponse =
+                client.post("/blocks", &AddBlockRequest { data }).await?;
+            print_added_block(&response, cli.output)?;
+        }
+        Command::Vote { validator, remove } => {
+            let vote = ValidatorVote {
+                validator,
+                authorize: !remove,
+            };
+            let data = vote.to_data();
+            let response: AddBlockResponse =
+                client.post("/blocks", &AddBlockRequest { data }).await?;
+            print_added_block(&response, cli.output)?;
+        }
+        Command::Slash {
+            validator,
+            first,
+            second,
+        } => {
+            let evidence = DoubleSignEvidence {
+                validator,
+                first: load_block(&first)?,
+                second: load_block(&second)?,
+            };
+            if !evidence.verify() {
+                bail!("The two blocks are not conflicting blocks signed by this validator");
+            }
+            let data = evidence.to_data();
+            let response: AddBlockResponse =
+                client.post("/blocks", &AddBlockRequest { data }).await?;
+            print_added_block(&response, cli.output)?;
+        }
+        Command::Stake { amount, to, nonce } => {
+            let wallet = load_wallet(&cli.wallet)?;
+            let action = match to {
+                Some(to) => StakeAction::Delegate { to, amount },
+                None => StakeAction::Unstake { amount },
+            };
+            let data = StakeChange::new(&wallet, action, nonce).to_data();
+            let response: AddBlockResponse =
+                client.post("/blocks", &AddBlockRequest { data }).await?;
+            print_added_block(&response, cli.output)?;
+        }
+        Command::Validate { full } => {
+            let path = if full {
+                "/validate?full=true"
+            } else {
+                "/validate"
+            };
+            let response: ValidateResponse = client.get(path).await?;
+            match cli.output {
+                Output::Json => print_json(&response)?,
+                Output::Table => println!(
+                    "Chain of {} blocks is {} ({} blocks checked)",
+                    response.chain_length,
+                    if response.is_valid {
+                        "valid"
+                    } else {
+                        "INVALID"
+                    },
+                    response.checked_blocks
+                ),
+            }
+            if !response.is_valid {
+                std::process::exit(2);
+            }
+        }
+        Command::Tail { lines, follow } => {
+            let chain: ChainResponse = client.get("/chain").await?;
+            let start = chain.chain.len().saturating_sub(lines);
+            let recent: Vec<Block> = chain.chain.iter_from(start).cloned().collect();
+            match cli.output {
+                Output::Json => recent.iter().try_for_each(print_json_line)?,
+                Output::Table => print_blocks(&recent),
+            }
+
+            if follow {
+                follow_blocks(&client, cli.output).await?;
+            }
+        }
+        Command::Export { format, out } => {
+            let bytes = client.export(format).await?;
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, &bytes)
+                        .with_context(|| format!("Cannot write {}", path.display()))?;
+                    eprintln!("Wrote {} bytes to {}", bytes.len(), path.display());
+                }
+                None => std::io::stdout().write_all(&bytes)?,
+            }
+        }
+        Command::Import { file, format } => {
+            let bytes =
+                std::fs::read(&file).with_context(|| format!("Cannot read {}", file.display()))?;
+            let response: ImportResponse = parse_response(
+                client
+                    .request(reqwest::Method::POST, &format!("/import?format={}", format))
+                    .body(bytes)
+                    .send()
+                    .await,
+                &client.base,
+            )
+            .await?;
+            match cli.output {
+                Output::Json => print_json(&response)?,
+                Output::Table => println!(
+                    "Imported {} blocks ({} already present), chain length {}",
+                    response.imported, response.skipped, response.length
+                ),
+            }
+        }
+        Command::Wallet(WalletCommand::New { force }) => {
+            if cli.wallet.exists() && !force {
+                bail!(
+                    "{} already exists (use --force to overwrite)",
+                    cli.wallet.display()
+                );
+            }
+            let wallet = Wallet::generate();
+            wallet.save(&cli.wallet)?;
+            print_wallet(&wallet, &cli.wallet, cli.output)?;
+        }
+        Command::Wallet(WalletCommand::Show) => {
+            let wallet = load_wallet(&cli.wallet)?;
+            print_wallet(&wallet, &cli.wallet, cli.output)?;
+        }
+        Command::Send { to, amount } => {
+            let wallet = load_wallet(&cli.wallet)?;
+            let transfer = SignedTransfer::new(&wallet, to, amount);
+            let data = serde_json::to_string(&transfer)?;
+
+            let response: AddBlockResponse =
+                client.post("/blocks", &AddBlockRequest { data }).await?;
+            match cli.output {
+                Output::Json => print_json(&response)?,
+                Output::Table => println!(
+                    "Sent {} to {} in block #{} ({})",
+                    transfer.amount, transfer.to, response.block.index, response.block.hash
+                ),
+            }
+        }
+        Command::Light(LightCommand::Sync) => {
+            let mut light = match load_light_client(&cli.light_state)? {
+                Some(light) => light,
+                None => {
+                    let page: HeadersResponse = client.get("/headers?limit=0").await?;
+                    LightClient::new(page.genesis_hash, page.difficulty)
+                }
+            };
+            let start = light.height();
+
+            loop {
+                let from = light.height() + 1;
+                let page: HeadersResponse = client.get(&format!("/headers?from={}", from)).await?;
+                if page.genesis_hash != light.genesis_hash() {
+                    bail!("Node follows another chain (genesis {})", page.genesis_hash);
+                }
+                if page.headers.is_empty() {
+                    break;
+                }
+                light.extend(page.from, &page.headers)?;
+            }
+            save_light_client(&light, &cli.light_state)?;
+
+            match cli.output {
+                Output::Json => print_json(&serde_json::json!({
+                    "height": light.height(),
+                    "tip_hash": light.tip_hash(),
+                    "new_headers": light.height() - start,
+                })),
+                Output::Table => {
+                    println!(
+                        "Synced {} new headers, tip #{} ({})",
+                        light.height() - start,
+                        light.height(),
+                        light.tip_hash()
+                    );
+                    Ok(())
+                }
+            }?;
+        }
+        Command::Light(LightCommand::Verify {
+            height,
+            position,
+            tx,
+        }) => {
+            let light = load_light_client(&cli.light_state)?
+                .ok_or_else(|| anyhow!("No light client state, run `bc-cli light sync` first"))?;
+            let proof: MerkleProofResponse = client
+                .get(&format!("/blocks/{}/proof/{}", height, position))
+                .await?;
+            let transaction = tx.unwrap_or(proof.transaction.clone());
+            let merkle = proof
+                .to_proof()
+                .ok_or_else(|| anyhow!("Node returned a malformed proof"))?;
+            light.verify_transaction(height, &transaction, &merkle)?;
+
+            match cli.output {
+                Output::Json => print_json(&proof)?,
+                Output::Table => println!(
+                    "Transaction {} is included in block #{} (txid {})",
+                    transaction, height, proof.txid
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Suivre le flux SSE /events et afficher chaque nouveau bloc
+async fn follow_blocks(client: &NodeClient, output: Output) -> Result<()> {
+    let mut response = client
+        .request(reqwest::Method::GET, "/events?topics=blocks")
+        .send()
+        .await
+        .with_context(|| format!("Cannot reach node at {}", client.base))?;
+
+    if !response.status().is_success() {
+        bail!("Cannot subscribe to events: HTTP {}", response.status());
+    }
+
+    // Les événements SSE sont séparés par une ligne vide ; un même événement
+    // peut arriver sur plusieurs morceaux
+    let mut buffer = String::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(end) = buffer.find("\n\n") {
+            let frame: String = buffer.drain(..end + 2).collect();
+            let data: String = frame
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect();
+
+            if let Ok(NodeEvent::BlockAdded { block }) = serde_json::from_str(&data) {
+                match output {
+                    Output::Json => print_json_line(&block)?,
+                    Output::Table => print_block_row(&block),
+                }
+            }
+        }
+    }
+
+    Err(anyhow!("Event stream closed by the node"))
+}
+
+fn load_wallet(path: &Path) -> Result<Wallet> {
+    Wallet::load(path).with_context(|| {
+        format!(
+            "Cannot load wallet from {} (create one with `bc-cli wallet new`)",
+            path.display()
+        )
+    })
+}
+
+/// Relire l'état du client léger, None s'il n'a jamais été synchronisé
+fn load_light_client(path: &Path) -> Result<Option<LightClient>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json =
+        std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    let light = serde_json::from_str(&json)
+        .with_context(|| format!("Invalid light client state in {}", path.display()))?;
+    Ok(Some(light))
+}
+
+fn save_light_client(light: &LightClient, path: &Path) -> Result<()> {
+    std::fs::write(path, serde_json::to_string(light)?)
+        .with_context(|| format!("Cannot write {}", path.display()))
+}
+
+fn load_block(path: &Path) -> Result<Block> {
+    let json =
+        std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Invalid block in {}", path.display()))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_json_line<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+fn print_wallet(wallet: &Wallet, path: &Path, output: Output) -> Result<()> {
+    match output {
+        Output::Json => print_json(&serde_json::json!({
+            "address": wallet.address(),
+            "public_key": wallet.public_key_hex(),
+            "file": path,
+        })),
+        Output::Table => {
+            println!("Address:    {}", wallet.address());
+            println!("Public key: {}", wallet.public_key_hex());
+            println!("File:       {}", path.display());
+            Ok(())
+        }
+    }
+}
+
+fn print_added_block(response: &AddBlockResponse, output: Output) -> Result<()> {
+    match output {
+        Output::Json => print_json(response)?,
+        Output::Table => {
+            println!("{}", response.message);
+            print_blocks(std::slice::from_ref(&response.block));
+        }
+    }
+    Ok(())
+}
+
+fn print_blocks(blocks: &[Block]) {
+    println!(
+        "{:>6}  {:<20}  {:<16}  {:>8}  DATA",
+        "INDEX", "TIMESTAMP", "HASH", "NONCE"
+    );
+    blocks.iter().for_each(print_block_row);
+}
+
+fn print_block_row(block: &Block) {
+    println!(
+        "{:>6}  {:<20}  {:<16}  {:>8}  {}",
+        block.index,
+        block.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        truncate(&block.hash, 16),
+        block.nonce,
+        truncate(&block.data, 48)
+    );
+}
+
+fn print_block_details(block: &Block) {
+    println!("Index:         {}", block.index);
+    println!("Timestamp:     {}", block.timestamp.to_rfc3339());
+    println!("Hash:          {}", block.hash);
+    println!("Previous hash: {}", block.previous_hash);
+    println!("Nonce:         {}", block.nonce);
+    println!("Data:          {}", block.data);
+}
+
+/// Raccourcir une chaîne pour l'affichage en tableau
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut short: String = s.chars().take(max - 1).collect();
+        short.push('…');
+        short
+    }
+}
@@ -0,0 +1,263 @@
+//! Outil hors ligne d'inspection et de réparation d'une chaîne
+//!
+//! Travaille directement sur un répertoire de données (`BLOCKCHAIN_DATA_DIR`)
+//! ou sur un fichier JSON exporté, sans démarrer le serveur.
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use mini_blockchain::config;
+use mini_blockchain::consensus::{ConsensusKind, ConsensusParams};
+use mini_blockchain::export::ChainFormat;
+use mini_blockchain::proof_of_work::PowAlgorithm;
+use mini_blockchain::storage::{self, DataDir};
+use mini_blockchain::{Block, Blockchain};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Inspection et réparation hors ligne d'une chaîne mini-blockchain
+#[derive(Parser, Debug)]
+#[command(name = "bc-tool", version, about)]
+struct Cli {
+    /// Répertoire de données du nœud ou fichier JSON exporté
+    #[arg(long, short, env = "BLOCKCHAIN_DATA_DIR")]
+    data: PathBuf,
+
+    /// Difficulté à utiliser si le fichier n'en contient pas
+    #[arg(long, default_value_t = 2)]
+    difficulty: usize,
+
+    /// Moteur de consensus de la chaîne
+    #[arg(long, env = "BLOCKCHAIN_CONSENSUS", default_value_t = ConsensusKind::Pow)]
+    consensus: ConsensusKind,
+
+    /// Algorithme de Proof of Work de la chaîne
+    #[arg(long, env = "BLOCKCHAIN_POW_ALGORITHM", default_value_t = PowAlgorithm::Sha256)]
+    pow_algorithm: PowAlgorithm,
+
+    /// Validateurs initiaux (clés publiques), en Proof of Authority
+    #[arg(long, env = "BLOCKCHAIN_VALIDATORS", value_delimiter = ',')]
+    validators: Vec<String>,
+
+    /// Mises initiales (`clé_publique:montant`), en Proof of Stake
+    #[arg(long, env = "BLOCKCHAIN_STAKES", value_delimiter = ',', value_parser = config::parse_stake)]
+    stakes: Vec<(String, u64)>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Valider la chaîne et lister tous les blocs défectueux
+    Verify,
+
+    /// Afficher une plage de blocs
+    Dump {
+        /// Première hauteur (incluse)
+        #[arg(long, default_value_t = 0)]
+        from: u64,
+
+        /// Dernière hauteur (incluse), le sommet par défaut
+        #[arg(long)]
+        to: Option<u64>,
+
+        #[arg(long, short, value_enum, default_value_t = Output::Table)]
+        output: Output,
+    },
+
+    /// Supprimer tous les blocs au-dessus de `--to`
+    Truncate {
+        /// Hauteur du nouveau sommet
+        #[arg(long)]
+        to: u64,
+    },
+
+    /// Reconstruire les index dérivés du répertoire de données
+    Reindex,
+
+    /// Exporter la chaîne (un bloc par enregistrement)
+    Export {
+        #[arg(long, short, default_value = "jsonl")]
+        format: ChainFormat,
+
+        /// Fichier de sortie (sortie standard par défaut)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Importer un export à la suite de la chaîne (reprend un import interrompu)
+    Import {
+        /// Fichier produit par `export` ou par GET /export
+        file: PathBuf,
+
+        #[arg(long, short, default_value = "jsonl")]
+        format: ChainFormat,
+    },
+}
+
+/// Format de sortie de `dump`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+}
+
+/// Emplacement de la chaîne : répertoire de données ou fichier isolé
+enum Source {
+    DataDir(DataDir),
+    File(PathBuf),
+}
+
+impl Source {
+    fn open(path: PathBuf) -> Self {
+        if path.is_dir() {
+            Source::DataDir(DataDir::new(path))
+        } else {
+            Source::File(path)
+        }
+    }
+
+    fn load(&self, difficulty: usize) -> Result<Blockchain> {
+        let blockchain = match self {
+            Source::DataDir(dir) => dir.load(),
+            Source::File(path) => storage::read_chain_file(path, difficulty),
+        };
+        blockchain.context("Cannot read chain")
+    }
+
+    /// Charger la chaîne, ou en créer une nouvelle si le répertoire est vide
+    fn load_or_create(&self, difficulty: usize) -> Result<Blockchain> {
+        match self {
+            Source::DataDir(dir) => dir.load_or_create(difficulty).context("Cannot read chain"),
+            Source::File(path) if !path.exists() => Ok(Blockchain::new(difficulty)),
+            Source::File(_) => self.load(difficulty),
+        }
+    }
+
+    fn save(&self, blockchain: &Blockchain) -> Result<()> {
+        match self {
+            Source::DataDir(dir) => dir.save(blockchain),
+            Source::File(path) => storage::write_chain_file(path, blockchain),
+        }
+        .context("Cannot write chain")
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exécuter la commande
+///
+/// # Returns
+/// Le code de sortie du processus (2 si la chaîne est invalide)
+fn run(cli: Cli) -> Result<i32> {
+    let source = Source::open(cli.data);
+    let mut blockchain = match cli.command {
+        Command::Import { .. } => source.load_or_create(cli.difficulty)?,
+        _ => source.load(cli.difficulty)?,
+    }
+    .with_consensus(
+        cli.consensus
+            .engine(&ConsensusParams {
+                pow_algorithm: cli.pow_algorithm,
+                validators: cli.validators.clone(),
+                stakes: cli.stakes.clone(),
+                validator_key: None,
+            })
+            .map_err(anyhow::Error::msg)?,
+    );
+
+    match cli.command {
+        Command::Verify => {
+            let faults = blockchain.validate_detailed_parallel();
+            for fault in &faults {
+                println!("position {:>6}: {}", fault.position, fault);
+            }
+            if faults.is_empty() {
+                println!("OK: {} blocks, chain is valid", blockchain.len());
+            } else {
+                println!(
+                    "FAILED: {} faults in {} blocks",
+                    faults.len(),
+                    blockchain.len()
+                );
+                return Ok(2);
+            }
+        }
+        Command::Dump { from, to, output } => {
+            let to = to.unwrap_or(blockchain.latest_block().index);
+            let blocks: Vec<&Block> = blockchain
+                .chain
+                .iter()
+                .filter(|block| (from..=to).contains(&block.index))
+                .collect();
+            match output {
+                Output::Json => println!("{}", serde_json::to_string_pretty(&blocks)?),
+                Output::Table => blocks.iter().for_each(|block| {
+                    println!(
+                        "{:>6}  {}  {}  prev={}  nonce={}  {}",
+                        block.index,
+                        block.timestamp.to_rfc3339(),
+                        block.hash,
+                        block.previous_hash,
+                        block.nonce,
+                        block.data
+                    )
+                }),
+            }
+        }
+        Command::Truncate { to } => {
+            if to >= blockchain.latest_block().index {
+                bail!(
+                    "Chain tip is already at height {}",
+                    blockchain.latest_block().index
+                );
+            }
+            let removed = blockchain.truncate(to);
+            source.save(&blockchain)?;
+            println!("Removed {} blocks, new tip at height {}", removed, to);
+        }
+        Command::Reindex => {
+            let Source::DataDir(dir) = &source else {
+                bail!("reindex needs a data directory, not a single file");
+            };
+            let entries = dir.reindex(&blockchain).context("Cannot write index")?;
+            println!("Rebuilt {} ({} entries)", storage::HASH_INDEX_FILE, entries);
+        }
+        Command::Export { format, out } => match out {
+            Some(path) => {
+                let file = File::create(&path)
+                    .with_context(|| format!("Cannot create {}", path.display()))?;
+                blockchain.export(file, format)?;
+                eprintln!("Exported {} blocks to {}", blockchain.len(), path.display());
+            }
+            None => blockchain.export(std::io::stdout().lock(), format)?,
+        },
+        Command::Import { file, format } => {
+            let reader =
+                File::open(&file).with_context(|| format!("Cannot open {}", file.display()))?;
+            let result = blockchain.import(reader, format);
+
+            // Conserver les blocs importés avant une éventuelle erreur
+            source.save(&blockchain)?;
+            let report = result?;
+            println!(
+                "Imported {} blocks ({} already present), tip at height {}",
+                report.imported,
+                report.skipped,
+                blockchain.latest_block().index
+            );
+        }
+    }
+
+    Ok(0)
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+/// Taille d'un en-tête de bloc Bitcoin sérialisé
+pub const HEADER_LEN: usize = 80;
+
+/// Cible minimale de regtest (`0x207fffff`) : presque tout hash la respecte
+///
+/// Les blocs de la chaîne ne portent pas leur difficulté, qui est un paramètre
+/// de la chaîne vérifié à part : leur en-tête annonce donc cette cible.
+pub const REGTEST_BITS: u32 = 0x207f_ffff;
+
+/// En-tête de bloc au format Bitcoin (80 octets)
+///
+/// Les hashes sont gardés dans l'ordre interne (celui de la sérialisation) ;
+/// l'affichage habituel les inverse (`to_display_hex`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub version: i32,
+    /// Hash du bloc précédent, ordre interne
+    pub prev_blockhash: [u8; 32],
+    /// Racine de Merkle des transactions, ordre interne
+    pub merkle_root: [u8; 32],
+    /// Horodatage Unix, en secondes
+    pub time: u32,
+    /// Cible de difficulté au format compact
+    pub bits: u32,
+    pub nonce: u32,
+}
+
+impl BlockHeader {
+    /// Sérialiser l'en-tête : champs en little-endian, hashes dans l'ordre interne
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[0..4].copy_from_slice(&self.version.to_le_bytes());
+        bytes[4..36].copy_from_slice(&self.prev_blockhash);
+        bytes[36..68].copy_from_slice(&self.merkle_root);
+        bytes[68..72].copy_from_slice(&self.time.to_le_bytes());
+        bytes[72..76].copy_from_slice(&self.bits.to_le_bytes());
+        bytes[76..80].copy_from_slice(&self.nonce.to_le_bytes());
+        bytes
+    }
+
+    /// Lire un en-tête sérialisé
+    pub fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Self {
+        let word = |at: usize| -> [u8; 4] { bytes[at..at + 4].try_into().expect("4 bytes") };
+        Self {
+            version: i32::from_le_bytes(word(0)),
+            prev_blockhash: bytes[4..36].try_into().expect("32 bytes"),
+            merkle_root: bytes[36..68].try_into().expect("32 bytes"),
+            time: u32::from_le_bytes(word(68)),
+            bits: u32::from_le_bytes(word(72)),
+            nonce: u32::from_le_bytes(word(76)),
+        }
+    }
+
+    /// Lire un en-tête sérialisé en hexadécimal (160 caractères)
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let mut bytes = [0u8; HEADER_LEN];
+        hex::decode_to_slice(hex.trim(), &mut bytes)
+            .map_err(|_| format!("Invalid {}-byte header hex", HEADER_LEN))?;
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    /// En-tête sérialisé en hexadécimal
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    /// Double SHA-256 de l'en-tête sérialisé, ordre interne
+    pub fn hash(&self) -> [u8; 32] {
+        sha256d(&self.to_bytes())
+    }
+
+    /// Hash du bloc tel qu'affiché par les explorateurs (octets inversés)
+    pub fn block_hash(&self) -> String {
+        to_display_hex(&self.hash())
+    }
+
+    /// Le hash, lu comme un entier little-endian, est-il sous la cible `bits` ?
+    pub fn meets_target(&self) -> bool {
+        let mut hash = self.hash();
+        hash.reverse();
+        hash <= target_from_bits(self.bits)
+    }
+}
+
+// Sérialisé comme les 80 octets de l'en-tête, en hexadécimal
+impl Serialize for BlockHeader {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockHeader {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        BlockHeader::from_hex(&hex).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Double SHA-256
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+/// Afficher un hash Bitcoin : octets inversés puis hexadécimal
+pub fn to_display_hex(hash: &[u8; 32]) -> String {
+    let mut reversed = *hash;
+    reversed.reverse();
+    hex::encode(reversed)
+}
+
+/// Relire un hash affiché (64 caractères hexadécimaux) dans l'ordre interne
+pub fn from_display_hex(hash: &str) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(hash, &mut bytes).ok()?;
+    bytes.reverse();
+    Some(bytes)
+}
+
+/// Cible (entier 256 bits big-endian) décrite par le format compact `bits`
+///
+/// `bits` = exposant (octet de poids fort) et mantisse sur 3 octets :
+/// cible = mantisse × 256^(exposant − 3). Une mantisse négative donne 0.
+pub fn target_from_bits(bits: u32) -> [u8; 32] {
+    let exponent = (bits >> 24) as usize;
+    let mantissa = bits & 0x007f_ffff;
+    let mut target = [0u8; 32];
+    if bits & 0x0080_0000 != 0 {
+        return target;
+    }
+    for (i, byte) in mantissa.to_be_bytes()[1..].iter().enumerate() {
+        // Octet de poids 256^weight ; ceux qui sortent des 256 bits sont ignorés
+        let Some(weight) = exponent.checked_sub(1 + i) else {
+            continue;
+        };
+        if weight < 32 {
+            target[31 - weight] = *byte;
+        }
+    }
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// En-tête du bloc genesis de Bitcoin
+    const GENESIS_HEADER: &str = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c";
+    const GENESIS_HASH: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+    /// En-tête du bloc 1 de Bitcoin
+    const BLOCK_1_HEADER: &str = "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299";
+    const BLOCK_1_HASH: &str = "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048";
+
+    #[test]
+    fn test_bitcoin_genesis_header() {
+        let header = BlockHeader::from_hex(GENESIS_HEADER).unwrap();
+        assert_eq!(header.version, 1);
+        assert_eq!(header.prev_blockhash, [0u8; 32]);
+        assert_eq!(
+            to_display_hex(&header.merkle_root),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+        assert_eq!(header.time, 1_231_006_505);
+        assert_eq!(header.bits, 0x1d00_ffff);
+        assert_eq!(header.nonce, 2_083_236_893);
+
+        assert_eq!(header.block_hash(), GENESIS_HASH);
+        assert_eq!(hex::encode(header.to_bytes()), GENESIS_HEADER);
+        assert!(header.meets_target());
+    }
+
+    #[test]
+    fn test_bitcoin_block_1_links_to_genesis() {
+        let header = BlockHeader::from_hex(BLOCK_1_HEADER).unwrap();
+        assert_eq!(header.block_hash(), BLOCK_1_HASH);
+        assert_eq!(to_display_hex(&header.prev_blockhash), GENESIS_HASH);
+        assert_eq!(from_display_hex(GENESIS_HASH), Some(header.prev_blockhash));
+        assert!(header.meets_target());
+
+        // Un autre nonce ne respecte plus la cible
+        let tampered = BlockHeader {
+            nonce: header.nonce + 1,
+            ..header
+        };
+        assert!(!tampered.meets_target());
+        assert!(BlockHeader::from_hex("0100").is_err());
+    }
+
+    #[test]
+    fn test_compact_targets() {
+        // Cible du genesis : 0x00000000ffff0000...
+        let target = target_from_bits(0x1d00_ffff);
+        assert_eq!(target[..6], [0, 0, 0, 0, 0xff, 0xff]);
+        assert!(target[6..].iter().all(|byte| *byte == 0));
+
+        assert_eq!(target_from_bits(0x1f0f_ffff)[..3], [0, 0x0f, 0xff]);
+
+        assert_eq!(target_from_bits(0x0180_0000), [0u8; 32]);
+        assert_eq!(target_from_bits(REGTEST_BITS)[0], 0x7f);
+    }
+}
@@ -1,9 +1,13 @@
-use chrono::{DateTime, Utc};
+use crate::bitcoin::{self, BlockHeader, REGTEST_BITS};
+use crate::merkle::{self, MerkleProof};
+use crate::proof_of_work::PowAlgorithm;
+use chrono::{DateTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
 
 /// Structure représentant un bloc dans la blockchain
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct Block {
     pub index: u64,
     pub timestamp: DateTime<Utc>,
@@ -11,6 +15,12 @@ pub struct Block {
     pub previous_hash: String,
     pub hash: String,
     pub nonce: u64,
+    /// Signature du validateur (Proof of Authority), absente en Proof of Work
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Algorithme de Proof of Work, haché dans l'en-tête
+    #[serde(default)]
+    pub algorithm: PowAlgorithm,
 }
 
 impl Block {
@@ -21,26 +31,196 @@ impl Block {
     /// * `data` - Données/transactions du bloc
     /// * `previous_hash` - Hash du bloc précédent
     pub fn new(index: u64, data: String, previous_hash: String) -> Self {
+        Self::with_timestamp(index, data, previous_hash, Utc::now())
+    }
+
+    /// Créer un nouveau bloc avec un horodatage imposé (horloge fixe des
+    /// benchmarks, reconstruction de blocs connus)
+    ///
+    /// # Arguments
+    /// * `index` - Position du bloc dans la chaîne
+    /// * `data` - Données/transactions du bloc
+    /// * `previous_hash` - Hash du bloc précédent
+    /// * `timestamp` - Horodatage du bloc
+    pub fn with_timestamp(
+        index: u64,
+        data: String,
+        previous_hash: String,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
         Self {
             index,
-            timestamp: Utc::now(),
+            timestamp,
             data,
             previous_hash,
             hash: String::new(),
             nonce: 0,
+            signature: None,
+            algorithm: PowAlgorithm::default(),
         }
     }
 
-    /// Calculer le hash SHA-256 du bloc
+    /// Calculer le hash du bloc
+    ///
+    /// Avec l'algorithme `Sha256d`, c'est le hash Bitcoin de `bitcoin_header`
+    /// (double SHA-256, affiché octets inversés). Sinon, c'est un SHA-256 simple :
+    /// l'en-tête haché est `index`, `timestamp` (RFC 3339), le nombre de
+    /// transactions, la racine de Merkle des transactions (`merkle_root`, en
+    /// hexadécimal), `previous_hash`, `signature` (si présente), l'algorithme
+    /// de Proof of Work (`PowAlgorithm::header_tag`, vide pour SHA-256) puis
+    /// `nonce`, en décimal et sans séparateur. Le nonce vient en dernier : le
+    /// mining peut réutiliser l'état SHA-256 du reste de l'en-tête (voir
+    /// `midstate`).
     ///
     /// # Returns
     /// String hexadécimal de 64 caractères représentant le hash
     pub fn calculate_hash(&self) -> String {
-        let input = format!("{}{}{}{}{}", self.index, self.timestamp.to_rfc3339(), self.data, self.previous_hash, self.nonce);
+        self.hash_with_root(&self.merkle_root(), self.transaction_count())
+    }
+
+    /// Hash du bloc dont les données ont pour racine de Merkle `root` et
+    /// comptent `transactions` transactions
+    ///
+    /// Les données n'entrent dans le hash que par ces deux valeurs : un
+    /// en-tête qui les porte suffit à recalculer le hash.
+    pub fn hash_with_root(&self, root: &[u8; 32], transactions: usize) -> String {
+        if self.algorithm == PowAlgorithm::Sha256d {
+            return self.bitcoin_header_with_root(root).block_hash();
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(self.payload_with_root(root, transactions).as_bytes());
+        hasher.update(self.signature.as_deref().unwrap_or_default().as_bytes());
+        hasher.update(self.algorithm.header_tag().as_bytes());
+        hasher.update(self.nonce.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// État SHA-256 après la partie constante de l'en-tête (tout sauf le nonce)
+    ///
+    /// # Returns
+    /// Un état qui donne, pour chaque nonce, le même hash que `calculate_hash`
+    /// (sauf avec `Sha256d`, dont l'en-tête est binaire)
+    pub fn midstate(&self) -> HeaderMidstate {
         let mut hasher = Sha256::new();
-        hasher.update(input.as_bytes());
-        let hash = hex::encode(hasher.finalize());
-        return hash;
+        hasher.update(self.signing_payload().as_bytes());
+        hasher.update(self.signature.as_deref().unwrap_or_default().as_bytes());
+        hasher.update(self.algorithm.header_tag().as_bytes());
+        HeaderMidstate(hasher)
+    }
+
+    /// En-tête Bitcoin de 80 octets du bloc (algorithme `Sha256d`)
+    ///
+    /// Version 1, hash précédent relu dans l'ordre interne, racine de Merkle
+    /// des transactions (`merkle_leaves`), horodatage en secondes, cible
+    /// `REGTEST_BITS` (la difficulté est vérifiée à part) et nonce sur 32 bits.
+    pub fn bitcoin_header(&self) -> BlockHeader {
+        self.bitcoin_header_with_root(&self.merkle_root())
+    }
+
+    fn bitcoin_header_with_root(&self, root: &[u8; 32]) -> BlockHeader {
+        BlockHeader {
+            version: 1,
+            prev_blockhash: bitcoin::from_display_hex(&self.previous_hash).unwrap_or_default(),
+            merkle_root: *root,
+            time: u32::try_from(self.timestamp.timestamp()).unwrap_or_default(),
+            bits: REGTEST_BITS,
+            nonce: self.nonce as u32,
+        }
+    }
+
+    /// Transactions du bloc : une par ligne de `data`
+    ///
+    /// Découpage sur `\n` sans perte : une fin de ligne finale donne une
+    /// transaction vide au lieu de disparaître de la racine de Merkle.
+    pub fn transactions(&self) -> impl Iterator<Item = &str> {
+        self.data.split('\n')
+    }
+
+    /// Nombre de transactions du bloc, haché avec leur racine de Merkle
+    ///
+    /// La racine duplique le dernier nœud d'un niveau impair : `tx1\ntx2` et
+    /// `tx1\ntx2\ntx2` ont la même racine (CVE-2012-2459), pas le même
+    /// nombre de transactions.
+    pub fn transaction_count(&self) -> usize {
+        self.transactions().count()
+    }
+
+    /// Les transactions ont-elles une seule écriture possible ?
+    ///
+    /// Ni `\r`, ni transaction vide, ni txid en double : sans ces règles, la
+    /// racine de Merkle seule ne distingue pas `tx1\ntx2` de `tx1\ntx2\ntx2`.
+    /// Exigé des blocs `Sha256d` uniquement, dont l'en-tête Bitcoin ne porte
+    /// pas le nombre de transactions.
+    pub fn has_canonical_transactions(&self) -> bool {
+        let mut seen = std::collections::HashSet::new();
+        !self.data.contains('\r')
+            && self
+                .transactions()
+                .all(|tx| !tx.is_empty() && seen.insert(merkle::txid(tx)))
+    }
+
+    /// Feuilles de l'arbre de Merkle du bloc
+    ///
+    /// La première engage la hauteur (`sha256d` de l'index en little-endian),
+    /// comme la coinbase de BIP 34 ; suivent les txids des transactions.
+    pub fn merkle_leaves(&self) -> Vec<[u8; 32]> {
+        std::iter::once(bitcoin::sha256d(&self.index.to_le_bytes()))
+            .chain(self.transactions().map(merkle::txid))
+            .collect()
+    }
+
+    /// Racine de Merkle des transactions, qui engage les données dans le hash
+    pub fn merkle_root(&self) -> [u8; 32] {
+        merkle::merkle_root(&self.merkle_leaves())
+    }
+
+    /// Preuve d'inclusion de la transaction en position `position`
+    ///
+    /// # Returns
+    /// None si le bloc a moins de `position + 1` transactions
+    pub fn transaction_proof(&self, position: usize) -> Option<MerkleProof> {
+        MerkleProof::build(&self.merkle_leaves(), position.checked_add(1)?)
+    }
+
+    /// `bitcoin_header` représente-t-il le bloc sans perte ?
+    ///
+    /// Transactions canoniques et champs d'en-tête représentables
+    /// (`header_fits_bitcoin`) : sinon deux blocs différents auraient le même
+    /// hash.
+    pub fn fits_bitcoin_header(&self) -> bool {
+        self.has_canonical_transactions() && self.header_fits_bitcoin()
+    }
+
+    /// Les champs de l'en-tête tiennent-ils dans `bitcoin_header` ?
+    ///
+    /// Horodatage à la seconde tenant sur 32 bits, nonce sur 32 bits, hash
+    /// précédent hexadécimal et pas de signature. Ne lit pas les données :
+    /// se vérifie aussi sur un en-tête seul.
+    pub fn header_fits_bitcoin(&self) -> bool {
+        u32::try_from(self.timestamp.timestamp()).is_ok()
+            && self.timestamp.nanosecond() == 0
+            && u32::try_from(self.nonce).is_ok()
+            && bitcoin::from_display_hex(&self.previous_hash).is_some()
+            && self.signature.is_none()
+    }
+
+    /// Contenu signé par un validateur : l'en-tête sans signature ni nonce
+    ///
+    /// Les données y figurent par leur nombre et leur racine de Merkle, comme
+    /// dans le hash.
+    pub fn signing_payload(&self) -> String {
+        self.payload_with_root(&self.merkle_root(), self.transaction_count())
+    }
+
+    fn payload_with_root(&self, root: &[u8; 32], transactions: usize) -> String {
+        format!(
+            "{}{}{}{}{}",
+            self.index,
+            self.timestamp.to_rfc3339(),
+            transactions,
+            hex::encode(root),
+            self.previous_hash
+        )
     }
 
     /// Créer le bloc genesis (premier bloc de la chaîne)
@@ -48,9 +228,38 @@ impl Block {
     /// # Returns
     /// Le bloc genesis avec index 0 et previous_hash "0"
     pub fn genesis() -> Self {
-         let mut genesis = Block::new(0, "Genesis Block".to_string(), "0".to_string());
-         genesis.hash = genesis.calculate_hash();
-         genesis
+        let mut genesis = Block::new(0, "Genesis Block".to_string(), "0".to_string());
+        genesis.hash = genesis.calculate_hash();
+        genesis
+    }
+}
+
+/// État SHA-256 précalculé sur l'en-tête d'un bloc, sans le nonce
+///
+/// Chaque essai de mining ne hache plus que les chiffres du nonce, sans
+/// allocation.
+#[derive(Clone)]
+pub struct HeaderMidstate(Sha256);
+
+impl HeaderMidstate {
+    /// Hash brut (32 octets) de l'en-tête complété par `nonce`
+    pub fn digest(&self, nonce: u64) -> [u8; 32] {
+        // Écriture décimale du nonce, comme `format!("{}", nonce)`
+        let mut buffer = [0u8; 20];
+        let mut start = buffer.len();
+        let mut rest = nonce;
+        loop {
+            start -= 1;
+            buffer[start] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+
+        let mut hasher = self.0.clone();
+        hasher.update(&buffer[start..]);
+        hasher.finalize().into()
     }
 }
 
@@ -85,6 +294,138 @@ mod tests {
         // Vérifier que le hash est déterministe (même bloc = même hash)
         let hash2 = block.calculate_hash();
         assert_eq!(hash, hash2);
+
+        // Les données n'entrent dans le hash que par leur nombre et leur
+        // racine de Merkle
+        let root = block.merkle_root();
+        assert_eq!(block.hash_with_root(&root, 1), hash);
+        assert_ne!(block.hash_with_root(&root, 2), hash);
+        assert_ne!(block.hash_with_root(&[0; 32], 1), hash);
+    }
+
+    #[test]
+    fn test_fixed_timestamp_gives_reproducible_hash() {
+        let timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let block = |data: &str| {
+            Block::with_timestamp(1, data.to_string(), "prev_hash".to_string(), timestamp)
+        };
+
+        assert_eq!(block("Test data").timestamp, timestamp);
+        assert_eq!(
+            block("Test data").calculate_hash(),
+            block("Test data").calculate_hash()
+        );
+        assert_ne!(
+            block("Test data").calculate_hash(),
+            block("Other data").calculate_hash()
+        );
+    }
+
+    #[test]
+    fn test_midstate_matches_reference_hash() {
+        let mut block = Block::new(7, "Midstate".to_string(), "prev_hash".to_string());
+        let midstate = block.midstate();
+
+        for nonce in [0, 9, 10, 12345, u64::MAX] {
+            block.nonce = nonce;
+            assert_eq!(hex::encode(midstate.digest(nonce)), block.calculate_hash());
+        }
+
+        // La signature fait partie de l'en-tête haché
+        let unsigned = block.calculate_hash();
+        block.signature = Some("ab".repeat(64));
+        assert_ne!(block.calculate_hash(), unsigned);
+        assert_eq!(
+            hex::encode(block.midstate().digest(block.nonce)),
+            block.calculate_hash()
+        );
+
+        // Comme l'algorithme de Proof of Work
+        let sha256 = block.calculate_hash();
+        block.algorithm = PowAlgorithm::Argon2id;
+        assert_ne!(block.calculate_hash(), sha256);
+        assert_eq!(
+            hex::encode(block.midstate().digest(block.nonce)),
+            block.calculate_hash()
+        );
+    }
+
+    #[test]
+    fn test_bitcoin_header_hash() {
+        let mut block = Block::with_timestamp(
+            1,
+            "Bitcoin style".to_string(),
+            Block::genesis().hash,
+            DateTime::from_timestamp(1_231_006_505, 0).unwrap(),
+        );
+        block.algorithm = PowAlgorithm::Sha256d;
+        block.nonce = 42;
+        assert!(block.fits_bitcoin_header());
+
+        let header = block.bitcoin_header();
+        assert_eq!(header.time, 1_231_006_505);
+        assert_eq!(header.nonce, 42);
+        assert_eq!(
+            bitcoin::to_display_hex(&header.prev_blockhash),
+            block.previous_hash
+        );
+        assert_eq!(block.calculate_hash(), header.block_hash());
+
+        // La hauteur et les données sont engagées par la racine de Merkle
+        let mut moved = block.clone();
+        moved.index = 2;
+        assert_ne!(moved.calculate_hash(), block.calculate_hash());
+
+        // Chaque ligne de données est une transaction prouvable
+        block.data = "Alice pays Bob\nBob pays Carol".to_string();
+        let proof = block.transaction_proof(1).unwrap();
+        assert_eq!(
+            proof.root(merkle::txid("Bob pays Carol")),
+            block.bitcoin_header().merkle_root
+        );
+        assert!(block.transaction_proof(2).is_none());
+
+        // Ce que l'en-tête ne peut pas représenter est refusé
+        block.nonce = u64::from(u32::MAX) + 1;
+        assert!(!block.fits_bitcoin_header());
+        block.nonce = 42;
+        block.timestamp += chrono::Duration::milliseconds(5);
+        assert!(!block.fits_bitcoin_header());
+    }
+
+    #[test]
+    fn test_merkle_root_is_not_malleable() {
+        let mut block = Block::with_timestamp(
+            1,
+            "tx1\ntx2".to_string(),
+            Block::genesis().hash,
+            DateTime::from_timestamp(1_231_006_505, 0).unwrap(),
+        );
+        block.algorithm = PowAlgorithm::Sha256d;
+        assert!(block.fits_bitcoin_header());
+        let root = block.bitcoin_header().merkle_root;
+
+        // Une fin de ligne ajoutée change la racine et rend le bloc invalide
+        for data in ["tx1\ntx2\n", "tx1\r\ntx2", "tx1\n\ntx2"] {
+            let mut tampered = block.clone();
+            tampered.data = data.to_string();
+            assert_ne!(tampered.bitcoin_header().merkle_root, root, "{:?}", data);
+            assert!(!tampered.fits_bitcoin_header(), "{:?}", data);
+        }
+
+        // Dupliquer la dernière transaction garde la racine (CVE-2012-2459) :
+        // le bloc est refusé
+        let mut duplicated = block.clone();
+        duplicated.data = "tx1\ntx2\ntx2".to_string();
+        assert_eq!(duplicated.bitcoin_header().merkle_root, root);
+        assert!(!duplicated.fits_bitcoin_header());
+
+        // Hors Sha256d, le nombre de transactions est haché : toutes ces
+        // données donnent un hash différent
+        block.algorithm = PowAlgorithm::Sha256;
+        duplicated.algorithm = PowAlgorithm::Sha256;
+        assert_eq!(duplicated.merkle_root(), block.merkle_root());
+        assert_ne!(duplicated.calculate_hash(), block.calculate_hash());
     }
 
     #[test]
@@ -0,0 +1,314 @@
+use crate::block::Block;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Nombre de blocs par segment scellé
+pub const SEGMENT_LEN: usize = 256;
+
+/// Liste de blocs en copie sur écriture, clonable en O(1)
+///
+/// Les blocs sont rangés dans des segments pleins et immuables
+/// (`Arc<[Block]>`) suivis d'une queue partagée. Cloner la liste ne copie que
+/// deux pointeurs : un snapshot reste cohérent pendant que la chaîne
+/// d'origine continue de grandir. Un ajout après un clone copie au plus la
+/// queue (`SEGMENT_LEN` blocs), jamais toute la chaîne.
+///
+/// La liste retient aussi la longueur du préfixe déjà validé : toute
+/// modification d'un bloc ou troncature la fait redescendre, un ajout ne
+/// l'étend que via `push_validated`.
+#[derive(Default)]
+pub struct BlockStore {
+    /// Segments pleins de `SEGMENT_LEN` blocs
+    sealed: Arc<Vec<Arc<[Block]>>>,
+    /// Blocs qui suivent le dernier segment scellé
+    tail: Arc<Vec<Block>>,
+    /// Nombre de blocs en tête de liste dont la validité est connue
+    validated: AtomicUsize,
+}
+
+impl BlockStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Nombre de blocs
+    pub fn len(&self) -> usize {
+        self.sealed_len() + self.tail.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bloc à la position `position`
+    pub fn get(&self, position: usize) -> Option<&Block> {
+        match self.sealed.get(position / SEGMENT_LEN) {
+            Some(segment) => segment.get(position % SEGMENT_LEN),
+            None => self.tail.get(position - self.sealed_len()),
+        }
+    }
+
+    /// Bloc modifiable à la position `position` (copie le segment s'il est partagé)
+    ///
+    /// Le bloc et ceux qui le suivent devront être validés à nouveau.
+    pub fn get_mut(&mut self, position: usize) -> Option<&mut Block> {
+        let validated = self.validated.get_mut();
+        *validated = (*validated).min(position);
+
+        let sealed_len = self.sealed_len();
+        if position < sealed_len {
+            let segment = &mut Arc::make_mut(&mut self.sealed)[position / SEGMENT_LEN];
+            Arc::make_mut(segment).get_mut(position % SEGMENT_LEN)
+        } else {
+            Arc::make_mut(&mut self.tail).get_mut(position - sealed_len)
+        }
+    }
+
+    pub fn first(&self) -> Option<&Block> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&Block> {
+        self.tail.last().or_else(|| self.sealed.last()?.last())
+    }
+
+    /// Parcourir tous les blocs dans l'ordre
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Block> + '_ {
+        self.sealed
+            .iter()
+            .flat_map(|segment| segment.iter())
+            .chain(self.tail.iter())
+    }
+
+    /// Parcourir les blocs à partir de la position `start`
+    pub fn iter_from(&self, start: usize) -> impl Iterator<Item = &Block> + '_ {
+        let first_segment = (start / SEGMENT_LEN).min(self.sealed.len());
+        let skip = start - first_segment * SEGMENT_LEN;
+        self.sealed[first_segment..]
+            .iter()
+            .flat_map(|segment| segment.iter())
+            .chain(self.tail.iter())
+            .skip(skip)
+    }
+
+    /// Ajouter un bloc à la fin, en scellant la queue quand elle est pleine
+    pub fn push(&mut self, block: Block) {
+        let tail = Arc::make_mut(&mut self.tail);
+        tail.push(block);
+        if tail.len() == SEGMENT_LEN {
+            let segment: Arc<[Block]> = std::mem::take(tail).into();
+            Arc::make_mut(&mut self.sealed).push(segment);
+        }
+    }
+
+    /// Ajouter un bloc déjà validé contre le sommet actuel
+    ///
+    /// Le préfixe validé s'étend au nouveau bloc s'il couvrait toute la liste.
+    pub fn push_validated(&mut self, block: Block) {
+        let extends = *self.validated.get_mut() == self.len();
+        self.push(block);
+        if extends {
+            *self.validated.get_mut() = self.len();
+        }
+    }
+
+    /// Longueur du préfixe dont la validité est connue
+    pub fn validated_len(&self) -> usize {
+        self.validated.load(Ordering::Relaxed)
+    }
+
+    /// Retenir que les `len` premiers blocs sont valides
+    ///
+    /// Ne fait jamais reculer le préfixe : d'autres lecteurs du même snapshot
+    /// peuvent valider en parallèle.
+    pub fn mark_validated(&self, len: usize) {
+        self.validated
+            .fetch_max(len.min(self.len()), Ordering::Relaxed);
+    }
+
+    /// Ne garder que les `len` premiers blocs
+    pub fn truncate(&mut self, len: usize) {
+        let validated = self.validated.get_mut();
+        *validated = (*validated).min(len);
+        if len >= self.len() {
+            return;
+        }
+        if len >= self.sealed_len() {
+            let keep = len - self.sealed_len();
+            Arc::make_mut(&mut self.tail).truncate(keep);
+            return;
+        }
+
+        // Le segment coupé redevient la queue
+        let sealed = Arc::make_mut(&mut self.sealed);
+        let segment = len / SEGMENT_LEN;
+        let tail = sealed[segment][..len % SEGMENT_LEN].to_vec();
+        sealed.truncate(segment);
+        self.tail = Arc::new(tail);
+    }
+
+    /// Copier les blocs dans un vecteur
+    pub fn to_vec(&self) -> Vec<Block> {
+        self.iter().cloned().collect()
+    }
+
+    fn sealed_len(&self) -> usize {
+        self.sealed.len() * SEGMENT_LEN
+    }
+}
+
+impl Clone for BlockStore {
+    fn clone(&self) -> Self {
+        Self {
+            sealed: self.sealed.clone(),
+            tail: self.tail.clone(),
+            validated: AtomicUsize::new(self.validated_len()),
+        }
+    }
+}
+
+impl From<Vec<Block>> for BlockStore {
+    fn from(blocks: Vec<Block>) -> Self {
+        blocks.into_iter().collect()
+    }
+}
+
+impl FromIterator<Block> for BlockStore {
+    fn from_iter<I: IntoIterator<Item = Block>>(iter: I) -> Self {
+        let mut store = BlockStore::new();
+        for block in iter {
+            store.push(block);
+        }
+        store
+    }
+}
+
+impl<'a> IntoIterator for &'a BlockStore {
+    type Item = &'a Block;
+    type IntoIter = Box<dyn Iterator<Item = &'a Block> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl Index<usize> for BlockStore {
+    type Output = Block;
+
+    fn index(&self, position: usize) -> &Block {
+        self.get(position).expect("block position out of range")
+    }
+}
+
+impl IndexMut<usize> for BlockStore {
+    fn index_mut(&mut self, position: usize) -> &mut Block {
+        self.get_mut(position).expect("block position out of range")
+    }
+}
+
+impl PartialEq for BlockStore {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl fmt::Debug for BlockStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Sérialisé comme un simple tableau de blocs : les formats existants
+// (chain.json, GET /chain) ne changent pas
+impl Serialize for BlockStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for block in self.iter() {
+            seq.serialize_element(block)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Block>::deserialize(deserializer).map(BlockStore::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(count: usize) -> Vec<Block> {
+        (0..count)
+            .map(|i| Block::new(i as u64, format!("Block {}", i), "0".to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_push_get_and_iter_across_segments() {
+        let source = blocks(SEGMENT_LEN * 2 + 10);
+        let store = BlockStore::from(source.clone());
+
+        assert_eq!(store.len(), source.len());
+        assert_eq!(store[SEGMENT_LEN].index, SEGMENT_LEN as u64);
+        assert_eq!(store.last(), source.last());
+        assert!(store.get(source.len()).is_none());
+        assert_eq!(store.to_vec(), source);
+        assert!(store
+            .iter_from(SEGMENT_LEN + 3)
+            .eq(source[SEGMENT_LEN + 3..].iter()));
+    }
+
+    #[test]
+    fn test_snapshot_is_not_affected_by_later_writes() {
+        let source = blocks(SEGMENT_LEN + 1);
+        let mut store = BlockStore::from(source.clone());
+        let snapshot = store.clone();
+
+        store[0].data = "Tampered".to_string();
+        store.push(Block::new(99, "Next".to_string(), "0".to_string()));
+        store.truncate(SEGMENT_LEN / 2);
+        assert_eq!(store.len(), SEGMENT_LEN / 2);
+        assert_eq!(store[0].data, "Tampered");
+
+        assert_eq!(snapshot.len(), SEGMENT_LEN + 1);
+        assert_eq!(snapshot[0].data, "Block 0");
+        assert_eq!(snapshot.to_vec(), source);
+    }
+
+    #[test]
+    fn test_validated_prefix_follows_mutations() {
+        let mut store = BlockStore::from(blocks(4));
+        assert_eq!(store.validated_len(), 0);
+
+        store.mark_validated(10);
+        assert_eq!(store.validated_len(), 4);
+        store.push_validated(Block::new(4, "Next".to_string(), "0".to_string()));
+        assert_eq!(store.validated_len(), 5);
+
+        // Modifier un bloc invalide le préfixe à partir de ce bloc
+        store[2].data = "Tampered".to_string();
+        assert_eq!(store.validated_len(), 2);
+        store.push_validated(Block::new(5, "Next".to_string(), "0".to_string()));
+        assert_eq!(store.validated_len(), 2);
+
+        store.truncate(1);
+        assert_eq!(store.validated_len(), 1);
+    }
+
+    #[test]
+    fn test_serializes_as_plain_array() {
+        let store = BlockStore::from(blocks(3));
+        let json = serde_json::to_value(&store).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+
+        let decoded: BlockStore = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, store);
+    }
+}
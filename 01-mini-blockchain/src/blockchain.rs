@@ -1,12 +1,22 @@
 use crate::block::Block;
-use crate::proof_of_work;
+use crate::block_store::BlockStore;
+use crate::checkpoints::Checkpoints;
+use crate::consensus::{self, ConsensusEngine};
+use crate::metrics::{metrics, BLOCK_INTERVAL_WINDOW};
+use crate::proof_of_work::PowAlgorithm;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, RwLock};
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Erreurs possibles lors de l'utilisation de la blockchain
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum BlockchainError {
+    #[error("Invalid genesis block")]
+    InvalidGenesis,
+
     #[error("Invalid block hash")]
     InvalidHash,
 
@@ -16,23 +26,88 @@ pub enum BlockchainError {
     #[error("Invalid block index")]
     InvalidIndex,
 
+    #[error("Block does not match the checkpoint")]
+    CheckpointMismatch,
+
+    #[error("Block is not signed by the in-turn validator")]
+    UnauthorizedSigner,
+
+    #[error("Block timestamp is too far in the future")]
+    FutureBlock,
+
+    #[error("This node is not the in-turn validator")]
+    NotInTurn,
+
     #[error("Mining failed")]
     MiningFailed,
 
+    #[error("Mining cancelled")]
+    MiningCancelled,
+
     #[error("Empty data")]
     EmptyData,
+
+    #[error("Block data is not a canonical list of distinct transactions")]
+    InvalidTransactions,
+}
+
+/// Défaut détecté sur un bloc lors d'une validation détaillée
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFault {
+    /// Position du bloc dans la chaîne
+    pub position: usize,
+    /// Index déclaré par le bloc
+    pub index: u64,
+    pub error: BlockchainError,
+}
+
+impl fmt::Display for BlockFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at block index {}", self.error, self.index)
+    }
+}
+
+/// Étendue d'une validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Ne vérifier que les blocs qui suivent le préfixe déjà validé ou le
+    /// dernier point de contrôle atteint
+    Incremental,
+    /// Tout revérifier depuis le bloc genesis
+    Full,
+}
+
+/// Résultat d'une validation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub faults: Vec<BlockFault>,
+    /// Nombre de blocs réellement revérifiés
+    pub checked: usize,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.faults.is_empty()
+    }
 }
 
 /// Structure représentant la blockchain complète
+///
+/// Les blocs sont stockés dans un `BlockStore` : cloner la blockchain est
+/// immédiat et le clone sert de snapshot immuable.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Blockchain {
-    pub chain: Vec<Block>,
+    pub chain: BlockStore,
     pub difficulty: usize,
+    /// Points de contrôle de confiance (configuration du nœud, non sérialisés)
+    #[serde(skip)]
+    pub checkpoints: Checkpoints,
+    /// Moteur de consensus qui scelle et vérifie les blocs (Proof of Work
+    /// par défaut, non sérialisé)
+    #[serde(skip, default = "consensus::default_engine")]
+    pub consensus: Arc<dyn ConsensusEngine>,
 }
 
-/// Type pour partager la blockchain entre threads (pour l'API)
-pub type SharedBlockchain = Arc<RwLock<Blockchain>>;
-
 impl Blockchain {
     /// Créer une nouvelle blockchain avec le bloc genesis
     ///
@@ -42,10 +117,27 @@ impl Blockchain {
     /// # Returns
     /// Une nouvelle blockchain initialisée avec le bloc genesis
     pub fn new(difficulty: usize) -> Self {
-        let mut chain = Vec::new();
+        let mut chain = BlockStore::new();
         let genesis = Block::genesis();
-        chain.push(genesis);
-        Self { chain, difficulty }
+        chain.push_validated(genesis);
+        Self {
+            chain,
+            difficulty,
+            checkpoints: Checkpoints::default(),
+            consensus: consensus::default_engine(),
+        }
+    }
+
+    /// Choisir le moteur de consensus qui scelle et vérifie les blocs
+    pub fn with_consensus(mut self, consensus: Arc<dyn ConsensusEngine>) -> Self {
+        self.consensus = consensus;
+        self
+    }
+
+    /// Remplacer les points de contrôle utilisés par la validation
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = checkpoints;
+        self
     }
 
     /// Ajouter un nouveau bloc à la chaîne
@@ -56,6 +148,19 @@ impl Blockchain {
     /// # Returns
     /// Result contenant une référence au bloc ajouté ou une erreur
     pub fn add_block(&mut self, data: String) -> Result<&Block, BlockchainError> {
+        self.add_block_until(data, &AtomicBool::new(false))
+    }
+
+    /// Ajouter un nouveau bloc, en abandonnant le mining si `cancel` passe à true
+    ///
+    /// Le bloc est scellé par le moteur de consensus de la chaîne. Il n'est
+    /// ajouté qu'une fois scellé : une annulation laisse la chaîne
+    /// inchangée et retourne `MiningCancelled`.
+    pub fn add_block_until(
+        &mut self,
+        data: String,
+        cancel: &AtomicBool,
+    ) -> Result<&Block, BlockchainError> {
         // Vérifier que les données ne sont pas vides
         if data.is_empty() {
             return Err(BlockchainError::EmptyData);
@@ -70,59 +175,326 @@ impl Blockchain {
         // Créer un nouveau bloc
         let mut new_block = Block::new(new_index, data, last_block.hash.clone());
 
-        // Miner le bloc avec la difficulté configurée
-        proof_of_work::mine_block(&mut new_block, self.difficulty);
+        // Sceller le bloc (mining en Proof of Work) avec la difficulté configurée
+        self.consensus.authorize_seal(&self.chain, &new_block)?;
+        if !self.consensus.seal(&mut new_block, self.difficulty, cancel) {
+            return Err(BlockchainError::MiningCancelled);
+        }
 
-        // Ajouter le bloc à la chaîne
-        self.chain.push(new_block);
+        // Ajouter le bloc à la chaîne (miné sur le sommet : déjà valide)
+        self.chain.push_validated(new_block);
+        self.record_metrics();
 
         // Retourner une référence au dernier bloc
         Ok(self.latest_block())
     }
 
-    /// Valider l'intégrité de toute la chaîne
+    /// Vérifier qu'un bloc déjà miné (import, synchronisation) prolonge le sommet
+    ///
+    /// # Returns
+    /// Ok si l'index, le lien, le hash et le Proof of Work sont corrects
+    pub fn validate_next(&self, block: &Block) -> Result<(), BlockchainError> {
+        self.check_next(block, || self.verify_block(block))
+    }
+
+    /// Comme `validate_next`, le hash et le Proof of Work étant vérifiés par
+    /// `verified` (éventuellement calculé à l'avance, en parallèle)
+    fn check_next(
+        &self,
+        block: &Block,
+        verified: impl FnOnce() -> bool,
+    ) -> Result<(), BlockchainError> {
+        let tip = self.latest_block();
+        if block.index != tip.index + 1 {
+            return Err(BlockchainError::InvalidIndex);
+        }
+        if block.previous_hash != tip.hash {
+            return Err(BlockchainError::InvalidPreviousHash);
+        }
+        if self
+            .checkpoints
+            .get(block.index)
+            .is_some_and(|hash| hash != block.hash)
+        {
+            return Err(BlockchainError::CheckpointMismatch);
+        }
+        if !verified() {
+            return Err(BlockchainError::InvalidHash);
+        }
+        self.consensus.verify_arrival(block)?;
+        self.consensus
+            .verify_authority(&self.chain, self.chain.len(), block)
+    }
+
+    /// Vérifier le hash et le sceau (Proof of Work...) d'un bloc,
+    /// indépendamment de ses voisins (ces vérifications peuvent donc
+    /// s'exécuter en parallèle)
+    ///
+    /// Un bloc `Sha256d` doit aussi avoir des transactions canoniques : son
+    /// en-tête ne porte que leur racine de Merkle, qui pourrait être celle
+    /// d'autres données. Le sceau, lui, se vérifie sur un en-tête seul.
+    pub fn verify_block(&self, block: &Block) -> bool {
+        block.hash == block.calculate_hash()
+            && (block.algorithm != PowAlgorithm::Sha256d || block.has_canonical_transactions())
+            && self.consensus.verify_seal(block, self.difficulty)
+    }
+
+    /// Poids de la chaîne pour le choix de fork : somme des poids de ses blocs
+    /// (genesis exclu) selon le moteur de consensus
+    pub fn chain_weight(&self) -> u128 {
+        self.chain.iter().skip(1).fold(0u128, |weight, block| {
+            weight.saturating_add(self.consensus.block_weight(block, self.difficulty))
+        })
+    }
+
+    /// Ajouter un bloc déjà miné après l'avoir validé
+    ///
+    /// # Returns
+    /// Result contenant une référence au bloc ajouté ou l'erreur de validation
+    pub fn append_block(&mut self, block: Block) -> Result<&Block, BlockchainError> {
+        self.validate_next(&block)?;
+        self.chain.push_validated(block);
+        self.record_metrics();
+        Ok(self.latest_block())
+    }
+
+    /// Comme `append_block`, avec le résultat de `verify_block` déjà connu
+    pub(crate) fn append_verified(
+        &mut self,
+        block: Block,
+        verified: bool,
+    ) -> Result<&Block, BlockchainError> {
+        self.check_next(&block, || verified)?;
+        self.chain.push_validated(block);
+        self.record_metrics();
+        Ok(self.latest_block())
+    }
+
+    /// Remplacer le genesis d'une chaîne vierge par celui d'un autre nœud
+    /// (import ou synchronisation d'un nouvel environnement)
+    ///
+    /// # Returns
+    /// `InvalidIndex` si la chaîne contient déjà d'autres blocs, l'erreur de
+    /// validation si le genesis est invalide
+    pub fn adopt_genesis(&mut self, genesis: Block) -> Result<(), BlockchainError> {
+        if self.len() != 1 || genesis.index != 0 {
+            return Err(BlockchainError::InvalidIndex);
+        }
+        let candidate = Blockchain {
+            chain: BlockStore::from(vec![genesis]),
+            difficulty: self.difficulty,
+            checkpoints: self.checkpoints.clone(),
+            consensus: self.consensus.clone(),
+        };
+        if let Some(fault) = candidate.validate_detailed().into_iter().next() {
+            return Err(fault.error);
+        }
+        self.chain = candidate.chain;
+        Ok(())
+    }
+
+    /// Mettre à jour les métriques de la chaîne (hauteur, difficulté, intervalle)
+    pub fn record_metrics(&self) {
+        let metrics = metrics();
+        metrics.chain_height.set(self.latest_block().index as i64);
+        metrics.difficulty.set(self.difficulty as i64);
+        if let Some(interval) = self.average_block_interval(BLOCK_INTERVAL_WINDOW) {
+            metrics.block_interval_seconds.set(interval);
+        }
+    }
+
+    /// Intervalle moyen (en secondes) entre les `window` derniers blocs
+    ///
+    /// # Returns
+    /// None si la chaîne ne contient que le bloc genesis
+    pub fn average_block_interval(&self, window: usize) -> Option<f64> {
+        let intervals = window.min(self.chain.len().saturating_sub(1));
+        if intervals == 0 {
+            return None;
+        }
+        let last = self.latest_block().timestamp;
+        let first = self.chain[self.chain.len() - 1 - intervals].timestamp;
+        let elapsed = (last - first).num_milliseconds() as f64 / 1000.0;
+        Some(elapsed / intervals as f64)
+    }
+
+    /// Valider l'intégrité de la chaîne (incrémentale)
     ///
     /// # Returns
     /// true si la chaîne est valide, false sinon
     pub fn is_valid(&self) -> bool {
-        // Vérifier le bloc genesis par ses propriétés et son hash
-        let genesis = &self.chain[0];
-        if genesis.index != 0 || genesis.previous_hash != "0" || genesis.data != "Genesis Block" {
-            println!("Genesis block properties are invalid");
-            return false;
+        let report = self.validate(ValidationMode::Incremental);
+        for fault in &report.faults {
+            tracing::warn!("{}", fault);
         }
+        report.is_valid()
+    }
+
+    /// Valider la chaîne en réutilisant le préfixe déjà validé
+    ///
+    /// En mode incrémental, seuls les blocs qui suivent le préfixe validé (ou
+    /// le plus haut point de contrôle respecté) sont revérifiés. Les points de
+    /// contrôle sont toujours comparés. Le préfixe sans défaut est retenu pour
+    /// les validations suivantes.
+    pub fn validate(&self, mode: ValidationMode) -> ValidationReport {
+        self.run_validation(mode, false)
+    }
+
+    /// Comme `validate`, en vérifiant les hashes et le Proof of Work sur tous
+    /// les cœurs : le rapport est identique à celui du chemin séquentiel
+    pub fn validate_parallel(&self, mode: ValidationMode) -> ValidationReport {
+        self.run_validation(mode, true)
+    }
 
-        // Vérifier que le hash du genesis est correct
-        if genesis.hash != genesis.calculate_hash() {
-            println!("Genesis block hash is invalid");
-            return false;
+    fn run_validation(&self, mode: ValidationMode, parallel: bool) -> ValidationReport {
+        let start = match mode {
+            ValidationMode::Incremental => self.chain.validated_len().max(self.trusted_len()),
+            ValidationMode::Full => 0,
+        };
+
+        let mut faults = self.checkpoint_faults();
+        faults.extend(self.validate_from(start, parallel));
+        faults.sort_by_key(|fault| fault.position);
+
+        let valid_prefix = faults.first().map_or(self.len(), |fault| fault.position);
+        self.chain.mark_validated(valid_prefix);
+        if !faults.is_empty() {
+            metrics().validation_failures_total.inc();
         }
 
-        // Vérifier tous les autres blocs
-        for i in 1..self.chain.len() {
-            let current = &self.chain[i];
-            let previous = &self.chain[i - 1];
+        ValidationReport {
+            faults,
+            checked: self.len() - start,
+        }
+    }
+
+    /// Vérifier toute la chaîne depuis le genesis sans s'arrêter au premier
+    /// défaut
+    ///
+    /// # Returns
+    /// La liste de tous les défauts trouvés, vide si la chaîne est valide
+    pub fn validate_detailed(&self) -> Vec<BlockFault> {
+        self.run_validation(ValidationMode::Full, false).faults
+    }
+
+    /// Comme `validate_detailed`, en parallèle sur tous les cœurs
+    pub fn validate_detailed_parallel(&self) -> Vec<BlockFault> {
+        self.run_validation(ValidationMode::Full, true).faults
+    }
+
+    /// Nombre de blocs couverts par le plus haut point de contrôle respecté
+    fn trusted_len(&self) -> usize {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|(height, hash)| {
+                self.get_block(*height)
+                    .is_some_and(|block| block.hash == *hash)
+            })
+            .map_or(0, |(height, _)| height as usize + 1)
+    }
+
+    /// Blocs présents dont le hash diffère de leur point de contrôle
+    fn checkpoint_faults(&self) -> Vec<BlockFault> {
+        self.checkpoints
+            .iter()
+            .filter_map(|(height, hash)| {
+                let block = self.get_block(height)?;
+                (block.hash != hash).then_some(BlockFault {
+                    position: height as usize,
+                    index: block.index,
+                    error: BlockchainError::CheckpointMismatch,
+                })
+            })
+            .collect()
+    }
+
+    /// Vérifier les blocs à partir de la position `start` (hash, Proof of
+    /// Work, lien et index ; propriétés du genesis si `start` vaut 0)
+    ///
+    /// Les hashes sont vérifiés d'abord, bloc par bloc (en parallèle si
+    /// `parallel`), puis les liens dans l'ordre : les défauts sont donc les
+    /// mêmes, dans le même ordre, quel que soit le chemin.
+    fn validate_from(&self, start: usize, parallel: bool) -> Vec<BlockFault> {
+        let mut faults = Vec::new();
+        let mut report = |position: usize, block: &Block, error| {
+            faults.push(BlockFault {
+                position,
+                index: block.index,
+                error,
+            })
+        };
+
+        // Vérifier le bloc genesis par ses propriétés et son hash
+        let Some(genesis) = self.chain.first() else {
+            return faults;
+        };
+        if start == 0 {
+            if genesis.index != 0 || genesis.previous_hash != "0" || genesis.data != "Genesis Block"
+            {
+                report(0, genesis, BlockchainError::InvalidGenesis);
+            } else if genesis.hash != genesis.calculate_hash() {
+                report(0, genesis, BlockchainError::InvalidHash);
+            }
+        }
 
-            // Vérifier que le hash est correct
-            if current.hash != current.calculate_hash() {
-                println!("Invalid hash at block index {}", current.index);
-                return false;
+        // Vérifier le hash et le sceau (Proof of Work...) des autres blocs
+        let first = start.max(1);
+        let blocks: Vec<&Block> = self.chain.iter_from(first).collect();
+        let verified: Vec<bool> = if parallel {
+            blocks
+                .par_iter()
+                .map(|block| self.verify_block(block))
+                .collect()
+        } else {
+            blocks
+                .iter()
+                .map(|block| self.verify_block(block))
+                .collect()
+        };
+
+        // Puis chaque bloc avec son prédécesseur
+        let previous_blocks = self.chain.iter_from(first - 1);
+        for (offset, (previous, current)) in previous_blocks.zip(blocks).enumerate() {
+            let position = first + offset;
+
+            // Vérifier que le hash est correct, puis les règles du consensus
+            // qui dépendent des blocs précédents (signataire attendu...)
+            if !verified[offset] {
+                report(position, current, BlockchainError::InvalidHash);
+            } else if let Err(error) =
+                self.consensus
+                    .verify_authority(&self.chain, position, current)
+            {
+                report(position, current, error);
             }
 
             // Vérifier le lien avec le bloc précédent
             if current.previous_hash != previous.hash {
-                println!("Invalid previous hash at block index {}", current.index);
-                return false;
+                report(position, current, BlockchainError::InvalidPreviousHash);
             }
 
             // Vérifier que l'index est séquentiel
             if current.index != previous.index + 1 {
-                println!("Invalid index at block index {}", current.index);
-                return false;
+                report(position, current, BlockchainError::InvalidIndex);
             }
         }
 
-        true
+        faults
+    }
+
+    /// Couper la chaîne pour que `height` devienne le dernier bloc
+    ///
+    /// # Returns
+    /// Le nombre de blocs supprimés
+    pub fn truncate(&mut self, height: u64) -> usize {
+        let keep = (height as usize)
+            .saturating_add(1)
+            .min(self.chain.len())
+            .max(1);
+        let removed = self.chain.len() - keep;
+        self.chain.truncate(keep);
+        removed
     }
 
     /// Obtenir le dernier bloc de la chaîne
@@ -146,6 +518,17 @@ impl Blockchain {
         self.chain.get(index as usize)
     }
 
+    /// Obtenir un bloc par son hash
+    ///
+    /// # Arguments
+    /// * `hash` - Hash hexadécimal du bloc recherché
+    ///
+    /// # Returns
+    /// Option contenant une référence au bloc si trouvé
+    pub fn get_block_by_hash(&self, hash: &str) -> Option<&Block> {
+        self.chain.iter().find(|block| block.hash == hash)
+    }
+
     /// Obtenir la taille de la chaîne
     ///
     /// # Returns
@@ -236,4 +619,262 @@ mod tests {
         // Vérifier que la chaîne est maintenant invalide
         assert!(!blockchain.is_valid());
     }
+
+    #[test]
+    fn test_average_block_interval() {
+        let mut blockchain = Blockchain::new(1);
+        assert!(blockchain.average_block_interval(10).is_none());
+
+        blockchain.add_block("Block 1".to_string()).unwrap();
+        blockchain.add_block("Block 2".to_string()).unwrap();
+        blockchain.chain[1].timestamp =
+            blockchain.chain[0].timestamp + chrono::Duration::seconds(10);
+        blockchain.chain[2].timestamp =
+            blockchain.chain[1].timestamp + chrono::Duration::seconds(20);
+
+        assert_eq!(blockchain.average_block_interval(10), Some(15.0));
+        assert_eq!(blockchain.average_block_interval(1), Some(20.0));
+    }
+
+    #[test]
+    fn test_validate_detailed_reports_every_fault() {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..=4 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        assert!(blockchain.validate_detailed().is_empty());
+
+        blockchain.chain[1].data = "Tampered".to_string();
+        blockchain.chain[3].index = 42;
+
+        let faults = blockchain.validate_detailed();
+        let summary: Vec<(usize, BlockchainError)> = faults
+            .iter()
+            .map(|f| (f.position, f.error.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, BlockchainError::InvalidHash),
+                (3, BlockchainError::InvalidHash),
+                (3, BlockchainError::InvalidIndex),
+                (4, BlockchainError::InvalidIndex),
+            ]
+        );
+        assert!(!blockchain.is_valid());
+    }
+
+    #[test]
+    fn test_parallel_validation_matches_serial() {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..300 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        assert!(blockchain.validate_detailed_parallel().is_empty());
+
+        // Hash cohérent mais Proof of Work insuffisant
+        let block = &mut blockchain.chain[200];
+        while crate::proof_of_work::verify_proof_of_work(&block.hash, 1) {
+            block.nonce += 1;
+            block.hash = block.calculate_hash();
+        }
+        blockchain.chain[5].data = "Tampered".to_string();
+        blockchain.chain[100].index = 7;
+
+        let serial = blockchain.validate_detailed();
+        assert_eq!(blockchain.validate_detailed_parallel(), serial);
+        assert!(serial
+            .iter()
+            .any(|f| f.position == 200 && f.error == BlockchainError::InvalidHash));
+        assert!(serial
+            .iter()
+            .any(|f| f.position == 201 && f.error == BlockchainError::InvalidPreviousHash));
+
+        let report = blockchain.validate_parallel(ValidationMode::Full);
+        assert_eq!(report.faults, serial);
+        assert_eq!(report.checked, 300);
+    }
+
+    /// Moteur minimal : aucune preuve, chaque bloc pèse 1
+    #[derive(Debug)]
+    struct Unsealed;
+
+    impl ConsensusEngine for Unsealed {
+        fn name(&self) -> &'static str {
+            "unsealed"
+        }
+
+        fn seal(&self, block: &mut Block, _difficulty: usize, _cancel: &AtomicBool) -> bool {
+            block.hash = block.calculate_hash();
+            true
+        }
+
+        fn verify_seal(&self, _block: &Block, _difficulty: usize) -> bool {
+            true
+        }
+
+        fn block_weight(&self, _block: &Block, _difficulty: usize) -> u128 {
+            1
+        }
+    }
+
+    #[test]
+    fn test_pluggable_consensus_engine() {
+        // Difficulté inatteignable en Proof of Work : seul le moteur choisi scelle
+        let mut blockchain = Blockchain::new(64).with_consensus(Arc::new(Unsealed));
+        for i in 1..=3 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        assert!(blockchain.validate_detailed().is_empty());
+        assert_eq!(blockchain.chain_weight(), 3);
+
+        // Les mêmes blocs ne passent pas la vérification du Proof of Work
+        let pow = blockchain.with_consensus(consensus::default_engine());
+        assert_eq!(pow.validate_detailed().len(), 3);
+        assert_eq!(Blockchain::new(2).chain_weight(), 0);
+    }
+
+    #[test]
+    fn test_append_block() {
+        let mut source = Blockchain::new(1);
+        source.add_block("Block 1".to_string()).unwrap();
+
+        let mut target = source.clone();
+        target.truncate(0);
+        let mut tampered = source.chain[1].clone();
+        tampered.data = "Tampered".to_string();
+        assert_eq!(
+            target.append_block(tampered),
+            Err(BlockchainError::InvalidHash)
+        );
+
+        assert!(target.append_block(source.chain[1].clone()).is_ok());
+        assert_eq!(
+            target.append_block(source.chain[1].clone()),
+            Err(BlockchainError::InvalidIndex)
+        );
+        assert_eq!(target.chain, source.chain);
+    }
+
+    #[test]
+    fn test_cancelled_mining_leaves_chain_unchanged() {
+        use std::sync::atomic::Ordering;
+        use std::sync::Arc;
+
+        let mut blockchain = Blockchain::new(1);
+        blockchain.add_block("Block 1".to_string()).unwrap();
+        let before = blockchain.chain.clone();
+
+        // Difficulté trop élevée pour aboutir : seul l'arrêt termine le mining
+        blockchain.difficulty = 64;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let miner = std::thread::spawn({
+            let cancel = cancel.clone();
+            move || {
+                let result = blockchain.add_block_until("Block 2".to_string(), &cancel);
+                assert_eq!(result, Err(BlockchainError::MiningCancelled));
+                blockchain
+            }
+        });
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        cancel.store(true, Ordering::SeqCst);
+        let blockchain = miner.join().unwrap();
+
+        assert_eq!(blockchain.chain, before);
+        assert!(blockchain.is_valid());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut blockchain = Blockchain::new(1);
+        blockchain.add_block("Block 1".to_string()).unwrap();
+        blockchain.add_block("Block 2".to_string()).unwrap();
+
+        assert_eq!(blockchain.truncate(5), 0);
+        assert_eq!(blockchain.truncate(1), 1);
+        assert_eq!(blockchain.latest_block().index, 1);
+        assert_eq!(blockchain.truncate(0), 1);
+        assert_eq!(blockchain.len(), 1);
+        assert!(blockchain.is_valid());
+    }
+
+    /// Copie de la chaîne dont aucun bloc n'est encore validé (chargement)
+    fn reloaded(blockchain: &Blockchain, checkpoints: Checkpoints) -> Blockchain {
+        Blockchain {
+            chain: BlockStore::from(blockchain.chain.to_vec()),
+            difficulty: blockchain.difficulty,
+            checkpoints,
+            consensus: blockchain.consensus.clone(),
+        }
+    }
+
+    #[test]
+    fn test_incremental_validation() {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..=4 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        // Les blocs minés localement sont validés à l'ajout
+        assert_eq!(blockchain.validate(ValidationMode::Incremental).checked, 0);
+
+        let loaded = reloaded(&blockchain, Checkpoints::default());
+        assert_eq!(loaded.validate(ValidationMode::Incremental).checked, 5);
+        assert_eq!(loaded.validate(ValidationMode::Incremental).checked, 0);
+
+        let full = loaded.validate(ValidationMode::Full);
+        assert!(full.is_valid());
+        assert_eq!(full.checked, 5);
+
+        // Un bloc modifié est revérifié, avec tous ceux qui le suivent
+        let mut tampered = loaded.clone();
+        tampered.chain[3].data = "Tampered".to_string();
+        let report = tampered.validate(ValidationMode::Incremental);
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.faults[0].position, 3);
+        assert_eq!(tampered.chain.validated_len(), 3);
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..=4 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        let hash = blockchain.chain[2].hash.clone();
+
+        // Le préfixe couvert par un point de contrôle respecté n'est pas revérifié
+        let trusted = reloaded(&blockchain, [(2, hash)].into_iter().collect());
+        let report = trusted.validate(ValidationMode::Incremental);
+        assert!(report.is_valid());
+        assert_eq!(report.checked, 2);
+        assert_eq!(trusted.validate(ValidationMode::Full).checked, 5);
+
+        // Un bloc qui contredit un point de contrôle est signalé et refusé
+        let checkpoints: Checkpoints = [(3, "00".repeat(32))].into_iter().collect();
+        let mismatched = reloaded(&blockchain, checkpoints.clone());
+        let report = mismatched.validate(ValidationMode::Incremental);
+        assert_eq!(report.faults.len(), 1);
+        assert_eq!(report.faults[0].error, BlockchainError::CheckpointMismatch);
+
+        let mut target = Blockchain::new(1).with_checkpoints(checkpoints);
+        target.chain = BlockStore::from(blockchain.chain.to_vec()[..3].to_vec());
+        assert_eq!(
+            target.append_block(blockchain.chain[3].clone()),
+            Err(BlockchainError::CheckpointMismatch)
+        );
+    }
+
+    #[test]
+    fn test_get_block_by_hash() {
+        let mut blockchain = Blockchain::new(1);
+        let hash = blockchain
+            .add_block("Block 1".to_string())
+            .unwrap()
+            .hash
+            .clone();
+
+        assert_eq!(blockchain.get_block_by_hash(&hash).unwrap().index, 1);
+        assert!(blockchain.get_block_by_hash("unknown").is_none());
+    }
 }
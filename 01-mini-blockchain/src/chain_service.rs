@@ -0,0 +1,623 @@
+use crate::bitcoin;
+use crate::block::Block;
+use crate::blockchain::{Blockchain, BlockchainError, ValidationMode};
+use crate::events::{EventBus, NodeEvent};
+use crate::export::TransferError;
+use crate::merkle;
+use crate::shutdown::Shutdown;
+use arc_swap::ArcSwap;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Nombre de commandes en attente avant que les appelants ne patientent
+const COMMAND_CAPACITY: usize = 256;
+
+/// Erreurs renvoyées par le service de chaîne
+#[derive(Error, Debug)]
+pub enum ServiceError {
+    #[error("Chain service is not running")]
+    Stopped,
+
+    #[error(transparent)]
+    Chain(#[from] BlockchainError),
+
+    #[error(transparent)]
+    Transfer(#[from] TransferError),
+}
+
+/// Modification de la chaîne ; la closure renvoyée envoie la réponse
+type WriteFn = Box<dyn FnOnce(&mut Blockchain) -> Box<dyn FnOnce() + Send> + Send>;
+type AppendReply = oneshot::Sender<Result<Block, BlockchainError>>;
+
+/// Commandes reçues par la tâche qui possède la blockchain
+enum Command {
+    /// Modifier la chaîne (import, réparation, synchronisation), la réponse
+    /// est envoyée une fois le nouveau snapshot publié
+    Write(WriteFn),
+    /// Miner un bloc contenant `data` puis l'ajouter au sommet
+    Append { data: String, reply: AppendReply },
+}
+
+/// Handle typé vers le service de chaîne, clonable à volonté
+///
+/// La `Blockchain` appartient à une tâche unique qui traite les écritures une
+/// par une. Après chaque modification, la tâche publie un snapshot immuable
+/// (le clone d'une `Blockchain` est immédiat) : les lectures ne passent ni par
+/// le canal ni par un verrou, et ne voient jamais un état intermédiaire. Le
+/// mining s'exécute hors de cette tâche (`spawn_blocking`).
+#[derive(Clone)]
+pub struct ChainHandle {
+    sender: mpsc::Sender<Command>,
+    view: Arc<ArcSwap<Blockchain>>,
+    events: EventBus,
+}
+
+impl ChainHandle {
+    /// Démarrer le service de chaîne sur le runtime tokio courant
+    ///
+    /// # Arguments
+    /// * `blockchain` - Chaîne confiée au service
+    /// * `events` - Bus sur lequel les nouveaux blocs sont publiés
+    /// * `shutdown` - Arrêt du nœud : annule le mining en cours
+    ///
+    /// Une chaîne invalide est tronquée au dernier bloc valide : le service ne
+    /// prolonge jamais un bloc défectueux.
+    pub fn spawn(mut blockchain: Blockchain, events: EventBus, shutdown: Shutdown) -> Self {
+        // Valider une fois la chaîne reçue : les ajouts suivants prolongent
+        // le préfixe validé et la validation incrémentale n'a plus rien à refaire
+        let report = blockchain.validate_parallel(ValidationMode::Incremental);
+        if let Some(fault) = report.faults.first() {
+            for fault in &report.faults {
+                tracing::error!("Chain handed to the chain service is invalid: {}", fault);
+            }
+            let removed = blockchain.truncate(fault.position.saturating_sub(1) as u64);
+            tracing::error!(
+                "Dropped {} blocks from position {} to keep a valid chain",
+                removed,
+                fault.position
+            );
+        }
+
+        let (sender, receiver) = mpsc::channel(COMMAND_CAPACITY);
+        let view = Arc::new(ArcSwap::from_pointee(blockchain.clone()));
+        let service = ChainService {
+            blockchain,
+            view: view.clone(),
+            events: events.clone(),
+            shutdown,
+            receiver,
+            pending: VecDeque::new(),
+        };
+        tokio::spawn(service.run());
+        Self {
+            sender,
+            view,
+            events,
+        }
+    }
+
+    /// Exécuter une modification de la chaîne
+    ///
+    /// Si la modification déplace le sommet, le mining en cours est annulé et
+    /// repris sur le nouveau sommet. Au retour, `snapshot` voit déjà la
+    /// chaîne modifiée.
+    pub async fn write<R, F>(&self, f: F) -> Result<R, ServiceError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Blockchain) -> R + Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Write(Box::new(move |bc| {
+            let result = f(bc);
+            Box::new(move || {
+                let _ = reply.send(result);
+            })
+        })))
+        .await?;
+        response.await.map_err(|_| ServiceError::Stopped)
+    }
+
+    /// Remplacer les blocs de la chaîne par ceux de `staged`, préparée (et
+    /// validée) hors du service à partir du snapshot `base`
+    ///
+    /// Le service ne fait que comparer les sommets puis échanger les blocs :
+    /// les écritures suivantes n'attendent pas la validation de `staged`.
+    ///
+    /// # Returns
+    /// false, sans rien modifier, si la chaîne a changé depuis `base`
+    pub async fn commit(
+        &self,
+        base: &Blockchain,
+        staged: Blockchain,
+    ) -> Result<bool, ServiceError> {
+        let (len, tip) = (base.len(), base.latest_block().hash.clone());
+        self.write(move |bc| {
+            if bc.len() != len || bc.latest_block().hash != tip {
+                return false;
+            }
+            bc.chain = staged.chain;
+            bc.record_metrics();
+            true
+        })
+        .await
+    }
+
+    /// Miner et ajouter un bloc : `TxAccepted` est publié pour chaque
+    /// transaction mise en attente, puis `BlockAdded` une fois le bloc ajouté,
+    /// ou `TxDropped` si le bloc n'est finalement pas ajouté
+    ///
+    /// # Returns
+    /// Le bloc ajouté, `EmptyData` ou `MiningCancelled` si le nœud s'arrête
+    pub async fn append(&self, data: String) -> Result<Block, ServiceError> {
+        if data.is_empty() {
+            return Err(BlockchainError::EmptyData.into());
+        }
+
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Append { data, reply }).await?;
+        Ok(response.await.map_err(|_| ServiceError::Stopped)??)
+    }
+
+    /// Dernier état publié de la chaîne, sans attente ni verrou
+    ///
+    /// Le snapshot reste valide (et inchangé) pendant que la chaîne grandit.
+    /// Il reste disponible après l'arrêt du service, pour la sauvegarde finale.
+    pub fn snapshot(&self) -> Arc<Blockchain> {
+        self.view.load_full()
+    }
+
+    /// Bus d'événements sur lequel le service publie
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// S'abonner aux événements du nœud
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
+
+    async fn send(&self, command: Command) -> Result<(), ServiceError> {
+        self.sender
+            .send(command)
+            .await
+            .map_err(|_| ServiceError::Stopped)
+    }
+}
+
+/// Demande d'ajout en attente de mining
+struct PendingAppend {
+    data: String,
+    reply: AppendReply,
+}
+
+/// Résultat d'un mining : la chaîne prolongée par le bloc scellé et vérifié,
+/// None si le mining a été annulé
+type Mined = Option<Result<Blockchain, BlockchainError>>;
+
+/// Mining en cours dans un thread bloquant
+struct MiningJob {
+    /// Hash du sommet sur lequel le bloc est miné
+    parent: String,
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<Mined>,
+}
+
+impl MiningJob {
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+}
+
+/// Tâche propriétaire de la blockchain
+struct ChainService {
+    blockchain: Blockchain,
+    /// Snapshot lu par les `ChainHandle`
+    view: Arc<ArcSwap<Blockchain>>,
+    events: EventBus,
+    shutdown: Shutdown,
+    receiver: mpsc::Receiver<Command>,
+    /// Ajouts en attente, le premier est celui en cours de mining
+    pending: VecDeque<PendingAppend>,
+}
+
+impl ChainService {
+    async fn run(mut self) {
+        let mut job: Option<MiningJob> = None;
+
+        loop {
+            tokio::select! {
+                command = self.receiver.recv() => match command {
+                    Some(command) => {
+                        self.handle(command);
+                        self.cancel_stale(&job);
+                    }
+                    // Tous les handles ont été libérés
+                    None => break,
+                },
+                mined = wait_job(&mut job) => {
+                    job = None;
+                    self.finish_mining(mined);
+                }
+                // Une seule fois par mining : le drapeau reste levé ensuite
+                _ = self.shutdown.wait(), if job.as_ref().is_some_and(|job| !job.is_cancelled()) => {
+                    if let Some(job) = &job {
+                        job.cancel.store(true, Ordering::SeqCst);
+                    }
+                }
+            }
+
+            if job.is_none() {
+                job = self.start_mining();
+            }
+        }
+
+        if let Some(job) = job {
+            job.cancel.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn handle(&mut self, command: Command) {
+        match command {
+            Command::Write(f) => {
+                let reply = f(&mut self.blockchain);
+                self.publish();
+                reply();
+            }
+            Command::Append { data, reply } => {
+                if self.shutdown.is_triggered() {
+                    let _ = reply.send(Err(BlockchainError::MiningCancelled));
+                } else {
+                    for txid in txids(&data) {
+                        self.events.publish(NodeEvent::TxAccepted { txid });
+                    }
+                    self.pending.push_back(PendingAppend { data, reply });
+                }
+            }
+        }
+    }
+
+    /// Annuler le mining d'un bloc dont le parent n'est plus le sommet : il
+    /// serait rejeté une fois scellé
+    fn cancel_stale(&self, job: &Option<MiningJob>) {
+        if let Some(job) = job {
+            if job.parent != self.blockchain.latest_block().hash {
+                job.cancel.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Lancer le mining du premier ajout en attente sur le sommet actuel
+    fn start_mining(&mut self) -> Option<MiningJob> {
+        let (parent, mut block) = loop {
+            let request = self.pending.front()?;
+            if self.shutdown.is_triggered() {
+                self.cancel_pending();
+                return None;
+            }
+
+            let tip = self.blockchain.latest_block();
+            let parent = tip.hash.clone();
+            let block = Block::new(tip.index + 1, request.data.clone(), parent.clone());
+
+            // Un nœud qui ne peut pas sceller ce bloc (PoA hors de son tour)
+            // répond tout de suite et passe à la demande suivante
+            match self
+                .blockchain
+                .consensus
+                .authorize_seal(&self.blockchain.chain, &block)
+            {
+                Ok(()) => break (parent, block),
+                Err(error) => {
+                    if let Some(request) = self.pending.pop_front() {
+                        self.drop_request(request, error);
+                    }
+                }
+            }
+        };
+        let mut staged = self.blockchain.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        // Le bloc scellé est aussi vérifié sur ce thread, sur une copie de la
+        // chaîne : le service n'aura plus qu'à échanger les blocs
+        let handle = tokio::task::spawn_blocking({
+            let cancel = cancel.clone();
+            move || {
+                let consensus = staged.consensus.clone();
+                if !consensus.seal(&mut block, staged.difficulty, &cancel) {
+                    return None;
+                }
+                if let Err(error) = staged.append_block(block) {
+                    return Some(Err(error));
+                }
+                Some(Ok(staged))
+            }
+        });
+
+        Some(MiningJob {
+            parent,
+            cancel,
+            handle,
+        })
+    }
+
+    /// Ajouter le bloc miné, ou relancer le mining si le sommet a changé
+    ///
+    /// Le bloc a déjà été vérifié hors du service : il suffit de s'assurer que
+    /// le sommet n'a pas bougé avant d'échanger les blocs, comme `commit`.
+    fn finish_mining(&mut self, (parent, mined): (String, Mined)) {
+        if parent != self.blockchain.latest_block().hash {
+            // La chaîne a été modifiée pendant le mining : `start_mining`
+            // reprendra la même demande sur le nouveau sommet
+            return;
+        }
+        let Some(staged) = mined else {
+            // Mining annulé (arrêt du nœud) ou thread interrompu
+            self.cancel_pending();
+            return;
+        };
+
+        let Some(request) = self.pending.pop_front() else {
+            return;
+        };
+        match staged {
+            Ok(staged) => {
+                self.blockchain.chain = staged.chain;
+                self.blockchain.record_metrics();
+                self.publish();
+                let block = self.blockchain.latest_block().clone();
+                self.events.publish(NodeEvent::BlockAdded {
+                    block: block.clone(),
+                });
+                let _ = request.reply.send(Ok(block));
+            }
+            Err(error) => self.drop_request(request, error),
+        }
+    }
+
+    /// Publier l'état courant pour les lecteurs
+    fn publish(&self) {
+        self.view.store(Arc::new(self.blockchain.clone()));
+    }
+
+    fn cancel_pending(&mut self) {
+        for request in std::mem::take(&mut self.pending) {
+            self.drop_request(request, BlockchainError::MiningCancelled);
+        }
+    }
+
+    /// Refuser un ajout en attente : ses transactions, annoncées par
+    /// `TxAccepted`, sont retirées par `TxDropped`
+    fn drop_request(&self, request: PendingAppend, error: BlockchainError) {
+        for txid in txids(&request.data) {
+            self.events.publish(NodeEvent::TxDropped {
+                txid,
+                reason: error.to_string(),
+            });
+        }
+        let _ = request.reply.send(Err(error));
+    }
+}
+
+/// Txids des transactions de `data`, une par ligne comme dans
+/// `Block::transactions`
+fn txids(data: &str) -> impl Iterator<Item = String> + '_ {
+    data.split('\n')
+        .map(|transaction| bitcoin::to_display_hex(&merkle::txid(transaction)))
+}
+
+/// Attendre la fin du mining en cours (jamais prêt s'il n'y en a pas)
+///
+/// # Returns
+/// Le hash du parent et le résultat du mining (None si le mining a été
+/// annulé)
+async fn wait_job(job: &mut Option<MiningJob>) -> (String, Mined) {
+    match job {
+        Some(job) => {
+            let mined = (&mut job.handle).await.ok().flatten();
+            (job.parent.clone(), mined)
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn spawn(difficulty: usize) -> (ChainHandle, Shutdown) {
+        let shutdown = Shutdown::new();
+        let handle = ChainHandle::spawn(
+            Blockchain::new(difficulty),
+            EventBus::default(),
+            shutdown.clone(),
+        );
+        (handle, shutdown)
+    }
+
+    #[tokio::test]
+    async fn test_append_and_read() {
+        let (chain, _shutdown) = spawn(1);
+        let mut events = chain.subscribe();
+
+        let block = chain.append("Block 1".to_string()).await.unwrap();
+        assert_eq!(block.index, 1);
+        let txid = bitcoin::to_display_hex(&merkle::txid("Block 1"));
+        assert!(matches!(
+            events.recv().await,
+            Ok(NodeEvent::TxAccepted { txid: t }) if t == txid
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Ok(NodeEvent::BlockAdded { block: b }) if b == block
+        ));
+
+        let snapshot = chain.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert!(snapshot.is_valid());
+
+        assert!(matches!(
+            chain.append(String::new()).await,
+            Err(ServiceError::Chain(BlockchainError::EmptyData))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_service_stays_responsive_while_mining() {
+        // Difficulté inatteignable : le mining ne se termine que par l'arrêt
+        let (chain, shutdown) = spawn(64);
+        let before = chain.snapshot();
+        let mut events = chain.subscribe();
+
+        let append = tokio::spawn({
+            let chain = chain.clone();
+            async move { chain.append("Never mined".to_string()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Les écritures sont traitées pendant le mining
+        let len = tokio::time::timeout(Duration::from_secs(1), chain.write(|bc| bc.len()))
+            .await
+            .expect("write served during mining")
+            .unwrap();
+        assert_eq!(len, 1);
+
+        shutdown.trigger();
+        assert!(matches!(
+            append.await.unwrap(),
+            Err(ServiceError::Chain(BlockchainError::MiningCancelled))
+        ));
+        assert_eq!(chain.snapshot().chain, before.chain);
+
+        // La transaction annoncée est retirée
+        let txid = bitcoin::to_display_hex(&merkle::txid("Never mined"));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(NodeEvent::TxAccepted { txid: t }) if t == txid
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(NodeEvent::TxDropped { txid: t, .. }) if t == txid
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_moving_the_tip_restarts_mining() {
+        // Difficulté inatteignable sur l'ancien sommet
+        let (chain, _shutdown) = spawn(64);
+        let append = tokio::spawn({
+            let chain = chain.clone();
+            async move { chain.append("Block 1".to_string()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Le mining abandonne l'ancien sommet et reprend sur le nouveau genesis
+        let mut genesis = Block::with_timestamp(
+            0,
+            "Genesis Block".to_string(),
+            "0".to_string(),
+            chain.snapshot().chain[0].timestamp - chrono::Duration::hours(1),
+        );
+        genesis.hash = genesis.calculate_hash();
+        chain
+            .write(move |bc| {
+                bc.difficulty = 1;
+                bc.adopt_genesis(genesis)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        let block = tokio::time::timeout(Duration::from_secs(5), append)
+            .await
+            .expect("mining restarted on the new tip")
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.previous_hash, chain.snapshot().chain[0].hash);
+        assert_eq!(chain.snapshot().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_immutable() {
+        let (chain, _shutdown) = spawn(1);
+        let before = chain.snapshot();
+
+        chain.append("Block 1".to_string()).await.unwrap();
+
+        assert_eq!(before.len(), 1);
+        assert_eq!(chain.snapshot().len(), 2);
+
+        // Une écriture terminée est visible dans le snapshot suivant
+        let mut source = Blockchain::new(1);
+        source.add_block("Block 1".to_string()).unwrap();
+        chain.write(|bc| bc.truncate(0)).await.unwrap();
+        let genesis = source.chain[0].clone();
+        chain
+            .write(move |bc| bc.adopt_genesis(genesis))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(chain.snapshot().len(), 1);
+        assert_eq!(chain.snapshot().chain[0], source.chain[0]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_truncates_an_invalid_chain() {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..=4 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        blockchain.chain.get_mut(3).unwrap().data = "Forged".to_string();
+
+        let chain = ChainHandle::spawn(blockchain, EventBus::default(), Shutdown::new());
+        let snapshot = chain.snapshot();
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.chain.validated_len(), 3);
+
+        // Le service prolonge le dernier bloc valide
+        let block = chain.append("Block 3".to_string()).await.unwrap();
+        assert_eq!(block.index, 3);
+        assert!(chain.snapshot().is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_commit_requires_an_unchanged_tip() {
+        let (chain, _shutdown) = spawn(1);
+        let base = chain.snapshot();
+        let mut staged = Blockchain::clone(&base);
+        staged.add_block("Staged".to_string()).unwrap();
+
+        // La chaîne a avancé depuis `base` : rien n'est remplacé
+        chain.append("Block 1".to_string()).await.unwrap();
+        assert!(!chain.commit(&base, staged.clone()).await.unwrap());
+        assert_eq!(chain.snapshot().latest_block().data, "Block 1");
+
+        let base = chain.snapshot();
+        let mut staged = Blockchain::clone(&base);
+        staged.add_block("Staged".to_string()).unwrap();
+        assert!(chain.commit(&base, staged.clone()).await.unwrap());
+        assert_eq!(chain.snapshot().chain, staged.chain);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_appends_form_a_valid_chain() {
+        let (chain, _shutdown) = spawn(1);
+
+        let appends: Vec<_> = (0..5)
+            .map(|i| {
+                let chain = chain.clone();
+                tokio::spawn(async move { chain.append(format!("Block {}", i)).await })
+            })
+            .collect();
+        for append in appends {
+            append.await.unwrap().unwrap();
+        }
+
+        let blockchain = chain.snapshot();
+        assert_eq!(blockchain.len(), 6);
+        assert!(blockchain.validate_detailed().is_empty());
+    }
+}
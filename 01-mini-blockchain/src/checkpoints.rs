@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Points de contrôle intégrés au binaire (hauteur, hash)
+///
+/// Chaque nœud crée son propre bloc genesis : la liste est vide par défaut et
+/// se complète avec `BLOCKCHAIN_CHECKPOINTS` pour une chaîne déployée.
+pub const CHECKPOINTS: &[(u64, &str)] = &[];
+
+/// Hashes attendus à certaines hauteurs
+///
+/// Un bloc qui correspond à son point de contrôle rend tout le préfixe qui le
+/// précède digne de confiance : la validation incrémentale ne le revérifie pas.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checkpoints(Arc<BTreeMap<u64, String>>);
+
+impl Checkpoints {
+    /// Points de contrôle intégrés (`CHECKPOINTS`)
+    pub fn builtin() -> Self {
+        CHECKPOINTS
+            .iter()
+            .map(|(height, hash)| (*height, hash.to_string()))
+            .collect()
+    }
+
+    /// Ajouter (ou remplacer) des points de contrôle
+    pub fn merge(&self, other: &Checkpoints) -> Self {
+        let mut merged = (*self.0).clone();
+        merged.extend(other.iter().map(|(h, hash)| (h, hash.to_string())));
+        Self(Arc::new(merged))
+    }
+
+    /// Hash attendu à la hauteur `height`
+    pub fn get(&self, height: u64) -> Option<&str> {
+        self.0.get(&height).map(String::as_str)
+    }
+
+    /// Parcourir les points de contrôle par hauteur croissante
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (u64, &str)> + '_ {
+        self.0.iter().map(|(height, hash)| (*height, hash.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<(u64, String)> for Checkpoints {
+    fn from_iter<I: IntoIterator<Item = (u64, String)>>(iter: I) -> Self {
+        Self(Arc::new(iter.into_iter().collect()))
+    }
+}
+
+/// Format `hauteur:hash`, séparés par des virgules
+impl FromStr for Checkpoints {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (height, hash) = entry.split_once(':').ok_or_else(|| {
+                    format!("Invalid checkpoint '{}', expected height:hash", entry)
+                })?;
+                let height = height
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid checkpoint height '{}'", height))?;
+                let hash = hash.trim().to_lowercase();
+                if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("Invalid checkpoint hash '{}'", hash));
+                }
+                Ok((height, hash))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_merge() {
+        let hash = "ab".repeat(32);
+        let parsed: Checkpoints = format!("10:{}, 2:{}", hash.to_uppercase(), hash)
+            .parse()
+            .unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.get(10), Some(hash.as_str()));
+        assert_eq!(parsed.iter().next().map(|(h, _)| h), Some(2));
+
+        let merged = Checkpoints::builtin().merge(&parsed);
+        assert_eq!(merged, parsed);
+
+        assert!("10".parse::<Checkpoints>().is_err());
+        assert!("x:abc".parse::<Checkpoints>().is_err());
+        assert!("1:abc".parse::<Checkpoints>().is_err());
+        assert!("".parse::<Checkpoints>().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,321 @@
+use crate::api::auth::{ApiKey, Scope};
+use crate::checkpoints::Checkpoints;
+use crate::consensus::{ConsensusEngine, ConsensusKind, ConsensusParams};
+use crate::orphans::{MAX_ORPHANS, ORPHAN_TTL};
+use crate::peers::PeerConfig;
+use crate::proof_of_work::PowAlgorithm;
+use crate::storage::DataDir;
+use crate::wallet::Wallet;
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Configuration du nœud, lue depuis les variables d'environnement
+///
+/// | Variable                          | Défaut           |
+/// |-----------------------------------|------------------|
+/// | `BLOCKCHAIN_ADDR`                 | `localhost:8090` |
+/// | `BLOCKCHAIN_DIFFICULTY`           | `2`              |
+/// | `BLOCKCHAIN_API_KEYS`             | aucune (écritures refusées) |
+/// | `BLOCKCHAIN_REQUIRE_READ_AUTH`    | `false`          |
+/// | `BLOCKCHAIN_ALLOW_ANONYMOUS_WRITES` | `false` (écritures sans clé) |
+/// | `BLOCKCHAIN_KEY_RATE_LIMIT`       | `30` (requêtes/minute, 0 = illimité) |
+/// | `BLOCKCHAIN_IP_RATE_LIMIT`        | `60` (requêtes/minute, 0 = illimité) |
+/// | `BLOCKCHAIN_PEER_RATE_LIMIT`      | `120` (messages de pairs/minute par IP, 0 = illimité) |
+/// | `BLOCKCHAIN_MAX_BODY_BYTES`       | `65536`          |
+/// | `BLOCKCHAIN_MAX_IMPORT_BYTES`     | `67108864` (POST /import) |
+/// | `BLOCKCHAIN_CORS_ORIGINS`         | aucune (permissif) |
+/// | `BLOCKCHAIN_SHUTDOWN_TIMEOUT`     | `10` (secondes) |
+/// | `BLOCKCHAIN_DATA_DIR`             | aucun (chaîne en mémoire) |
+/// | `BLOCKCHAIN_CHECKPOINTS`          | `CHECKPOINTS` (`hauteur:hash,...`) |
+/// | `BLOCKCHAIN_CONSENSUS`            | `pow` (`pow`, `poa` ou `pos`) |
+/// | `BLOCKCHAIN_POW_ALGORITHM`        | `sha256` (`sha256`, `argon2id` ou `sha256d`) |
+/// | `BLOCKCHAIN_VALIDATORS`           | aucun (clés publiques, PoA) |
+/// | `BLOCKCHAIN_STAKES`               | aucune (`clé:montant,...`, PoS) |
+/// | `BLOCKCHAIN_VALIDATOR_KEY`        | aucune (clé secrète hexadécimale, PoA/PoS) |
+/// | `BLOCKCHAIN_PEERS`                | aucun (URL des pairs de départ) |
+/// | `BLOCKCHAIN_PUBLIC_URL`           | aucune (URL annoncée aux pairs) |
+/// | `BLOCKCHAIN_MAX_OUTBOUND`         | `8` (connexions sortantes) |
+/// | `BLOCKCHAIN_MAX_INBOUND`          | `32` (connexions entrantes) |
+/// | `BLOCKCHAIN_BAN_DURATION`         | `86400` (secondes) |
+/// | `BLOCKCHAIN_MAX_ORPHANS`          | `100` (blocs orphelins conservés) |
+/// | `BLOCKCHAIN_ORPHAN_TTL`           | `1200` (secondes) |
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    pub listen_addr: String,
+    pub difficulty: usize,
+    pub api_keys: Vec<ApiKey>,
+    pub require_read_auth: bool,
+    /// Accepter les écritures sans clé d'API (refusées par défaut)
+    pub allow_anonymous_writes: bool,
+    pub key_rate_limit: u32,
+    pub ip_rate_limit: u32,
+    /// Messages `/peers/addr` et `/peers/blocks` par minute et par IP
+    pub peer_rate_limit: u32,
+    pub max_body_bytes: usize,
+    pub max_import_bytes: usize,
+    pub cors_origins: Vec<String>,
+    pub shutdown_timeout: Duration,
+    pub data_dir: Option<PathBuf>,
+    pub checkpoints: Checkpoints,
+    pub consensus: ConsensusKind,
+    /// Algorithme de Proof of Work
+    pub pow_algorithm: PowAlgorithm,
+    /// Validateurs initiaux (Proof of Authority)
+    pub validators: Vec<String>,
+    /// Mises initiales (Proof of Stake)
+    pub stakes: Vec<(String, u64)>,
+    /// Clé avec laquelle ce nœud signe ses blocs (Proof of Authority/Stake)
+    pub validator_key: Option<Wallet>,
+    /// API REST des pairs de départ (seeds), d'où la chaîne est téléchargée
+    pub peers: Vec<String>,
+    /// URL sous laquelle les pairs peuvent joindre ce nœud
+    pub public_url: Option<String>,
+    pub max_outbound: usize,
+    pub max_inbound: usize,
+    /// Durée de bannissement d'un pair qui se conduit mal
+    pub ban_duration: Duration,
+    /// Taille de la réserve de blocs orphelins
+    pub max_orphans: usize,
+    /// Durée de conservation d'un bloc orphelin
+    pub orphan_ttl: Duration,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: "localhost:8090".to_string(),
+            difficulty: 2,
+            api_keys: Vec::new(),
+            require_read_auth: false,
+            allow_anonymous_writes: false,
+            key_rate_limit: 30,
+            ip_rate_limit: 60,
+            peer_rate_limit: 120,
+            max_body_bytes: 64 * 1024,
+            max_import_bytes: 64 * 1024 * 1024,
+            cors_origins: Vec::new(),
+            shutdown_timeout: Duration::from_secs(10),
+            data_dir: None,
+            checkpoints: Checkpoints::builtin(),
+            consensus: ConsensusKind::default(),
+            pow_algorithm: PowAlgorithm::default(),
+            validators: Vec::new(),
+            stakes: Vec::new(),
+            validator_key: None,
+            peers: Vec::new(),
+            public_url: None,
+            max_outbound: 8,
+            max_inbound: 32,
+            ban_duration: Duration::from_secs(24 * 60 * 60),
+            max_orphans: MAX_ORPHANS,
+            orphan_ttl: ORPHAN_TTL,
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Charger la configuration depuis l'environnement
+    ///
+    /// # Returns
+    /// La configuration, ou un message décrivant la variable invalide
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            listen_addr: env::var("BLOCKCHAIN_ADDR").unwrap_or(defaults.listen_addr),
+            difficulty: parse_var("BLOCKCHAIN_DIFFICULTY", defaults.difficulty)?,
+            api_keys: match env::var("BLOCKCHAIN_API_KEYS") {
+                Ok(list) => parse_api_keys(&list)?,
+                Err(_) => defaults.api_keys,
+            },
+            require_read_auth: parse_var(
+                "BLOCKCHAIN_REQUIRE_READ_AUTH",
+                defaults.require_read_auth,
+            )?,
+            allow_anonymous_writes: parse_var(
+                "BLOCKCHAIN_ALLOW_ANONYMOUS_WRITES",
+                defaults.allow_anonymous_writes,
+            )?,
+            key_rate_limit: parse_var("BLOCKCHAIN_KEY_RATE_LIMIT", defaults.key_rate_limit)?,
+            ip_rate_limit: parse_var("BLOCKCHAIN_IP_RATE_LIMIT", defaults.ip_rate_limit)?,
+            peer_rate_limit: parse_var("BLOCKCHAIN_PEER_RATE_LIMIT", defaults.peer_rate_limit)?,
+            max_body_bytes: parse_var("BLOCKCHAIN_MAX_BODY_BYTES", defaults.max_body_bytes)?,
+            max_import_bytes: parse_var("BLOCKCHAIN_MAX_IMPORT_BYTES", defaults.max_import_bytes)?,
+            cors_origins: env::var("BLOCKCHAIN_CORS_ORIGINS")
+                .map(|list| split_list(&list))
+                .unwrap_or(defaults.cors_origins),
+            shutdown_timeout: Duration::from_secs(parse_var(
+                "BLOCKCHAIN_SHUTDOWN_TIMEOUT",
+                defaults.shutdown_timeout.as_secs(),
+            )?),
+            data_dir: env::var_os("BLOCKCHAIN_DATA_DIR").map(PathBuf::from),
+            checkpoints: match env::var("BLOCKCHAIN_CHECKPOINTS") {
+                Ok(list) => defaults.checkpoints.merge(
+                    &list
+                        .parse()
+                        .map_err(|e| format!("BLOCKCHAIN_CHECKPOINTS: {}", e))?,
+                ),
+                Err(_) => defaults.checkpoints,
+            },
+            consensus: parse_var("BLOCKCHAIN_CONSENSUS", defaults.consensus)?,
+            pow_algorithm: parse_var("BLOCKCHAIN_POW_ALGORITHM", defaults.pow_algorithm)?,
+            validators: env::var("BLOCKCHAIN_VALIDATORS")
+                .map(|list| split_list(&list))
+                .unwrap_or(defaults.validators),
+            stakes: match env::var("BLOCKCHAIN_STAKES") {
+                Ok(list) => parse_stakes(&list).map_err(|e| format!("BLOCKCHAIN_STAKES: {}", e))?,
+                Err(_) => defaults.stakes,
+            },
+            validator_key: match env::var("BLOCKCHAIN_VALIDATOR_KEY") {
+                Ok(secret) => Some(
+                    Wallet::from_secret_hex(secret.trim())
+                        .map_err(|e| format!("BLOCKCHAIN_VALIDATOR_KEY: {}", e))?,
+                ),
+                Err(_) => defaults.validator_key,
+            },
+            peers: env::var("BLOCKCHAIN_PEERS")
+                .map(|list| split_list(&list))
+                .unwrap_or(defaults.peers),
+            public_url: env::var("BLOCKCHAIN_PUBLIC_URL").ok(),
+            max_outbound: parse_var("BLOCKCHAIN_MAX_OUTBOUND", defaults.max_outbound)?,
+            max_inbound: parse_var("BLOCKCHAIN_MAX_INBOUND", defaults.max_inbound)?,
+            ban_duration: Duration::from_secs(parse_var(
+                "BLOCKCHAIN_BAN_DURATION",
+                defaults.ban_duration.as_secs(),
+            )?),
+            max_orphans: parse_var("BLOCKCHAIN_MAX_ORPHANS", defaults.max_orphans)?,
+            orphan_ttl: Duration::from_secs(parse_var(
+                "BLOCKCHAIN_ORPHAN_TTL",
+                defaults.orphan_ttl.as_secs(),
+            )?),
+        })
+    }
+
+    /// Instancier le moteur de consensus configuré
+    ///
+    /// # Returns
+    /// Le moteur, ou un message si ses paramètres sont invalides
+    pub fn consensus_engine(&self) -> Result<Arc<dyn ConsensusEngine>, String> {
+        let params = ConsensusParams {
+            pow_algorithm: self.pow_algorithm,
+            validators: self.validators.clone(),
+            stakes: self.stakes.clone(),
+            validator_key: self.validator_key.clone(),
+        };
+        self.consensus
+            .engine(&params)
+            .map_err(|e| format!("BLOCKCHAIN_CONSENSUS={}: {}", self.consensus, e))
+    }
+
+    /// Paramètres du gestionnaire de pairs
+    ///
+    /// Le carnet d'adresses est sauvegardé dans le répertoire de données,
+    /// s'il y en a un.
+    pub fn peer_config(&self) -> PeerConfig {
+        PeerConfig {
+            seeds: self.peers.clone(),
+            max_outbound: self.max_outbound,
+            max_inbound: self.max_inbound,
+            public_url: self.public_url.clone(),
+            book_path: self
+                .data_dir
+                .as_ref()
+                .map(|dir| DataDir::new(dir).peers_path()),
+            ban_duration: self.ban_duration,
+        }
+    }
+}
+
+/// Lire une variable d'environnement typée, avec valeur par défaut
+fn parse_var<T: FromStr>(name: &str, default: T) -> Result<T, String> {
+    match env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid value for {}: '{}'", name, value)),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Découper une liste séparée par des virgules
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parser une liste de clés au format `nom:token:scope,...` (scope = read|write)
+pub fn parse_api_keys(list: &str) -> Result<Vec<ApiKey>, String> {
+    split_list(list)
+        .into_iter()
+        .map(|entry| {
+            let parts: Vec<&str> = entry.split(':').collect();
+            let [name, token, scope] = parts.as_slice() else {
+                return Err(format!(
+                    "Invalid API key entry '{}', expected name:token:scope",
+                    entry
+                ));
+            };
+            Ok(ApiKey {
+                name: name.to_string(),
+                token: token.to_string(),
+                scope: scope.parse::<Scope>()?,
+            })
+        })
+        .collect()
+}
+
+/// Parser une liste de mises au format `clé_publique:montant,...`
+pub fn parse_stakes(list: &str) -> Result<Vec<(String, u64)>, String> {
+    split_list(list)
+        .iter()
+        .map(|entry| parse_stake(entry))
+        .collect()
+}
+
+/// Parser une mise au format `clé_publique:montant`
+pub fn parse_stake(entry: &str) -> Result<(String, u64), String> {
+    let (key, amount) = entry
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid stake '{}', expected public_key:amount", entry))?;
+    let amount = amount
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid stake amount '{}'", amount))?;
+    Ok((key.trim().to_string(), amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_api_keys() {
+        let keys = parse_api_keys("ci:abc123:write, dashboard:xyz:read").unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].name, "ci");
+        assert_eq!(keys[0].scope, Scope::Write);
+        assert_eq!(keys[1].token, "xyz");
+        assert_eq!(keys[1].scope, Scope::Read);
+
+        assert!(parse_api_keys("missing-scope:abc").is_err());
+        assert!(parse_api_keys("ci:abc:admin").is_err());
+    }
+
+    #[test]
+    fn test_parse_stakes() {
+        let stakes = parse_stakes("02ab:100, 03cd:5").unwrap();
+        assert_eq!(
+            stakes,
+            vec![("02ab".to_string(), 100), ("03cd".to_string(), 5)]
+        );
+
+        assert!(parse_stakes("02ab").is_err());
+        assert!(parse_stakes("02ab:-1").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::block::Block;
+use crate::block_store::BlockStore;
+use crate::blockchain::BlockchainError;
+use crate::proof_of_work::PowAlgorithm;
+use crate::wallet::{self, Wallet};
+use secp256k1::PublicKey;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+mod poa;
+mod pos;
+mod pow;
+
+pub use poa::{ProofOfAuthority, ValidatorSet, ValidatorVote};
+pub use pos::{DoubleSignEvidence, ProofOfStake, StakeAction, StakeChange, StakeRegistry};
+pub use pow::ProofOfWork;
+
+/// Nombre d'états dérivés gardés en cache (un par bloc rejoué)
+const STATE_CACHE_LEN: usize = 4096;
+
+/// Moteur de consensus : comment un bloc est scellé et quelle chaîne l'emporte
+///
+/// La blockchain garde la vérification du hash (`Block::calculate_hash`), des
+/// liens et des index ; le moteur ne s'occupe que de la preuve portée par le
+/// bloc (nonce, signature...), des règles qui dépendent des blocs précédents
+/// et du poids utilisé pour choisir entre deux chaînes concurrentes.
+pub trait ConsensusEngine: fmt::Debug + Send + Sync {
+    /// Nom du moteur (configuration, journaux)
+    fn name(&self) -> &'static str;
+
+    /// Vérifier que ce nœud peut sceller `block` au sommet de `chain`
+    ///
+    /// Appelé avant `seal` : un refus évite de miner un bloc qui serait rejeté.
+    fn authorize_seal(&self, _chain: &BlockStore, _block: &Block) -> Result<(), BlockchainError> {
+        Ok(())
+    }
+
+    /// Sceller un bloc rattaché au sommet : remplir sa preuve puis son hash
+    ///
+    /// # Arguments
+    /// * `block` - Bloc à sceller
+    /// * `difficulty` - Difficulté de la chaîne (ignorée par les moteurs qui n'en ont pas)
+    /// * `cancel` - Abandonner le scellement dès qu'il passe à true
+    ///
+    /// # Returns
+    /// false si le scellement a été annulé (le hash du bloc reste alors vide)
+    fn seal(&self, block: &mut Block, difficulty: usize, cancel: &AtomicBool) -> bool;
+
+    /// Vérifier la preuve d'un bloc dont le hash est correct
+    ///
+    /// Ne dépend que du bloc : la vérification peut s'exécuter en parallèle.
+    fn verify_seal(&self, block: &Block, difficulty: usize) -> bool;
+
+    /// Vérifier les règles qui dépendent des blocs précédents
+    ///
+    /// # Arguments
+    /// * `chain` - Blocs dont les `position` premiers précèdent `block`
+    /// * `position` - Position de `block` dans la chaîne
+    /// * `block` - Bloc dont le sceau a déjà été vérifié
+    fn verify_authority(
+        &self,
+        _chain: &BlockStore,
+        _position: usize,
+        _block: &Block,
+    ) -> Result<(), BlockchainError> {
+        Ok(())
+    }
+
+    /// Vérifier les règles qui dépendent de l'horloge locale, à la réception
+    /// d'un bloc qui prolonge le sommet
+    ///
+    /// Jamais appelé en revalidant une chaîne stockée : la validité d'une
+    /// chaîne ne dépend pas du moment où on la vérifie.
+    fn verify_arrival(&self, _block: &Block) -> Result<(), BlockchainError> {
+        Ok(())
+    }
+
+    /// Poids d'un bloc pour le choix de fork (la chaîne la plus lourde l'emporte)
+    fn block_weight(&self, block: &Block, difficulty: usize) -> u128;
+}
+
+/// Moteurs disponibles, choisis à la création de la chaîne (`BLOCKCHAIN_CONSENSUS`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsensusKind {
+    /// Proof of Work (SHA-256 ou Argon2id)
+    #[default]
+    Pow,
+    /// Proof of Authority : des validateurs signent les blocs à tour de rôle
+    Poa,
+    /// Proof of Stake : un validateur tiré au sort selon sa mise signe chaque bloc
+    Pos,
+}
+
+/// Paramètres des moteurs qui en ont besoin
+#[derive(Debug, Clone, Default)]
+pub struct ConsensusParams {
+    /// Algorithme de Proof of Work
+    pub pow_algorithm: PowAlgorithm,
+    /// Validateurs initiaux (clés publiques compressées), Proof of Authority
+    pub validators: Vec<String>,
+    /// Mises initiales (clé publique, montant), Proof of Stake
+    pub stakes: Vec<(String, u64)>,
+    /// Clé avec laquelle ce nœud signe ses blocs, s'il est validateur
+    pub validator_key: Option<Wallet>,
+}
+
+impl ConsensusKind {
+    /// Instancier le moteur
+    ///
+    /// # Returns
+    /// Le moteur, ou un message si ses paramètres sont invalides
+    pub fn engine(&self, params: &ConsensusParams) -> Result<Arc<dyn ConsensusEngine>, String> {
+        Ok(match self {
+            ConsensusKind::Pow => Arc::new(ProofOfWork::new(params.pow_algorithm)),
+            ConsensusKind::Poa => Arc::new(ProofOfAuthority::new(
+                ValidatorSet::new(params.validators.iter().cloned())?,
+                params.validator_key.clone(),
+            )),
+            ConsensusKind::Pos => Arc::new(ProofOfStake::new(
+                StakeRegistry::new(params.stakes.iter().cloned())?,
+                params.validator_key.clone(),
+            )),
+        })
+    }
+}
+
+impl fmt::Display for ConsensusKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsensusKind::Pow => f.write_str("pow"),
+            ConsensusKind::Poa => f.write_str("poa"),
+            ConsensusKind::Pos => f.write_str("pos"),
+        }
+    }
+}
+
+impl FromStr for ConsensusKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pow" => Ok(ConsensusKind::Pow),
+            "poa" => Ok(ConsensusKind::Poa),
+            "pos" => Ok(ConsensusKind::Pos),
+            other => Err(format!(
+                "Unknown consensus engine '{}', expected pow, poa or pos",
+                other
+            )),
+        }
+    }
+}
+
+/// Moteur utilisé quand aucun n'est choisi (chaîne chargée depuis un fichier)
+pub fn default_engine() -> Arc<dyn ConsensusEngine> {
+    Arc::new(ProofOfWork::default())
+}
+
+/// Clé publique compressée en hexadécimal minuscule
+fn normalize_key(key: &str) -> Result<String, String> {
+    PublicKey::from_str(key.trim())
+        .map(|key| key.to_string())
+        .map_err(|_| format!("Invalid validator public key '{}'", key))
+}
+
+/// Sceller un bloc par signature : nonce à 0, signature de `signing_payload`
+fn sign_block(key: &Wallet, block: &mut Block) {
+    block.nonce = 0;
+    block.signature = Some(key.sign(block.signing_payload().as_bytes()));
+    block.hash = block.calculate_hash();
+}
+
+/// Forme d'un sceau par signature : nonce à 0, signature compacte de 64 octets
+/// et en-tête texte (l'en-tête Bitcoin n'engage pas la signature)
+fn is_signature_seal(block: &Block) -> bool {
+    block.nonce == 0
+        && block.algorithm != PowAlgorithm::Sha256d
+        && block
+            .signature
+            .as_deref()
+            .and_then(|signature| hex::decode(signature).ok())
+            .is_some_and(|bytes| bytes.len() == 64)
+}
+
+/// Le bloc est-il signé par la clé publique `signer`
+fn signed_by(block: &Block, signer: &str) -> bool {
+    block.signature.as_deref().is_some_and(|signature| {
+        wallet::verify_signature(signer, block.signing_payload().as_bytes(), signature)
+    })
+}
+
+/// États dérivés de la chaîne (validateurs, mises...) après chaque bloc
+///
+/// Indexés par hash du bloc, recalculé et non lu dans le bloc : le hash
+/// couvre tout l'historique, l'état vaut donc sur toute branche. Un bloc
+/// forgé qui reprend le hash d'un autre ne peut pas remplacer son état.
+struct ChainStates<T> {
+    genesis: Arc<T>,
+    states: Mutex<HashMap<String, Arc<T>>>,
+}
+
+impl<T: Clone> ChainStates<T> {
+    fn new(genesis: T) -> Self {
+        Self {
+            genesis: Arc::new(genesis),
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn genesis(&self) -> &T {
+        &self.genesis
+    }
+
+    /// État qui s'applique au bloc en position `position`
+    ///
+    /// Remonte jusqu'au dernier état connu (le genesis au pire), puis rejoue
+    /// les blocs suivants avec `apply`. Seuls les états des blocs reliés à
+    /// leur prédécesseur par son vrai hash sont retenus : au-delà d'un lien
+    /// rompu, le hash n'engage plus l'historique rejoué.
+    ///
+    /// # Arguments
+    /// * `chain` - Blocs dont les `position` premiers précèdent le bloc
+    /// * `apply` - Mettre à jour l'état avec un bloc (`Arc::make_mut` au besoin)
+    fn at(
+        &self,
+        chain: &BlockStore,
+        position: usize,
+        apply: impl Fn(&mut Arc<T>, &Block),
+    ) -> Arc<T> {
+        let position = position.min(chain.len());
+
+        let mut start = position;
+        let mut state = loop {
+            if start <= 1 {
+                break self.genesis.clone();
+            }
+            if let Some(state) = self.cached(&chain[start - 1].calculate_hash()) {
+                break state;
+            }
+            start -= 1;
+        };
+
+        let first = start.max(1);
+        let mut parent = (position > first).then(|| chain[first - 1].calculate_hash());
+        for block in chain.iter_from(first).take(position - first) {
+            apply(&mut state, block);
+            let hash = block.calculate_hash();
+            if parent.as_ref() == Some(&block.previous_hash) {
+                self.remember(&hash, state.clone());
+                parent = Some(hash);
+            } else {
+                parent = None;
+            }
+        }
+        state
+    }
+
+    fn cached(&self, hash: &str) -> Option<Arc<T>> {
+        self.states.lock().ok()?.get(hash).cloned()
+    }
+
+    fn remember(&self, hash: &str, state: Arc<T>) {
+        if let Ok(mut states) = self.states.lock() {
+            if states.len() >= STATE_CACHE_LEN {
+                states.clear();
+            }
+            states.insert(hash.to_string(), state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_parse_and_engine() {
+        let params = ConsensusParams::default();
+        let kind: ConsensusKind = "pow".parse().unwrap();
+        assert_eq!(kind, ConsensusKind::Pow);
+        assert_eq!(kind.to_string(), "pow");
+        assert_eq!(kind.engine(&params).unwrap().name(), "pow");
+        assert!("pbft".parse::<ConsensusKind>().is_err());
+
+        // Proof of Authority : au moins un validateur valide
+        let kind: ConsensusKind = "poa".parse().unwrap();
+        assert!(kind.engine(&params).is_err());
+        let params = ConsensusParams {
+            validators: vec![Wallet::generate().public_key_hex()],
+            ..ConsensusParams::default()
+        };
+        assert_eq!(kind.engine(&params).unwrap().name(), "poa");
+
+        // Proof of Stake : au moins une mise non nulle
+        let kind: ConsensusKind = "pos".parse().unwrap();
+        assert!(kind.engine(&params).is_err());
+        let params = ConsensusParams {
+            stakes: vec![(Wallet::generate().public_key_hex(), 10)],
+            ..ConsensusParams::default()
+        };
+        assert_eq!(kind.engine(&params).unwrap().name(), "pos");
+    }
+}
@@ -0,0 +1,398 @@
+use super::{
+    is_signature_seal, normalize_key, sign_block, signed_by, ChainStates, ConsensusEngine,
+};
+use crate::block::Block;
+use crate::block_store::BlockStore;
+use crate::blockchain::BlockchainError;
+use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Vote pour ajouter ou retirer un validateur
+///
+/// Un vote est un bloc dont les données valent
+/// `{"vote": {"validator": "<clé publique>", "authorize": true}}` : le votant
+/// est le validateur qui a signé le bloc.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorVote {
+    /// Clé publique compressée du validateur concerné
+    pub validator: String,
+    /// true pour l'ajouter, false pour le retirer
+    pub authorize: bool,
+}
+
+/// Forme des données d'un bloc de vote
+#[derive(Serialize, Deserialize)]
+struct VoteData {
+    vote: ValidatorVote,
+}
+
+impl ValidatorVote {
+    /// Données du bloc qui porte ce vote
+    pub fn to_data(&self) -> String {
+        serde_json::to_string(&VoteData { vote: self.clone() }).expect("vote serializes")
+    }
+
+    /// Lire le vote porté par les données d'un bloc, s'il y en a un
+    pub fn from_data(data: &str) -> Option<Self> {
+        if !data.starts_with('{') {
+            return None;
+        }
+        serde_json::from_str::<VoteData>(data)
+            .ok()
+            .map(|data| data.vote)
+    }
+}
+
+/// Validateurs autorisés et votes en cours à une hauteur donnée
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorSet {
+    /// Clés publiques triées : leur ordre fixe les tours de signature
+    validators: BTreeSet<String>,
+    /// Votants de chaque proposition (candidat, ajout ou retrait)
+    tallies: BTreeMap<(String, bool), BTreeSet<String>>,
+}
+
+impl ValidatorSet {
+    /// Créer l'ensemble initial des validateurs
+    ///
+    /// # Returns
+    /// Une erreur si une clé est invalide ou si l'ensemble est vide
+    pub fn new(validators: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let validators = validators
+            .into_iter()
+            .map(|key| normalize_key(&key))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        if validators.is_empty() {
+            return Err("Proof of Authority needs at least one validator".to_string());
+        }
+        Ok(Self {
+            validators,
+            tallies: BTreeMap::new(),
+        })
+    }
+
+    /// Parcourir les validateurs dans l'ordre des tours
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.validators.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.validators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validators.is_empty()
+    }
+
+    pub fn contains(&self, validator: &str) -> bool {
+        self.validators.contains(validator)
+    }
+
+    /// Validateur dont c'est le tour de signer le bloc `index`
+    pub fn in_turn(&self, index: u64) -> &str {
+        let turn = (index % self.validators.len() as u64) as usize;
+        self.iter().nth(turn).expect("validator set is never empty")
+    }
+
+    /// Compter le vote de `voter`, et appliquer la proposition dès qu'une
+    /// majorité stricte des validateurs l'a votée
+    ///
+    /// Chaque validateur a une voix par candidat (son dernier vote compte). Les
+    /// votes sans effet, invalides ou qui retireraient le dernier validateur
+    /// sont ignorés.
+    pub fn apply_vote(&mut self, voter: &str, vote: &ValidatorVote) {
+        let Ok(candidate) = normalize_key(&vote.validator) else {
+            return;
+        };
+        if vote.authorize == self.validators.contains(&candidate)
+            || (!vote.authorize && self.validators.len() == 1)
+        {
+            return;
+        }
+
+        if let Some(voters) = self.tallies.get_mut(&(candidate.clone(), !vote.authorize)) {
+            voters.remove(voter);
+        }
+        let voters = self
+            .tallies
+            .entry((candidate.clone(), vote.authorize))
+            .or_default();
+        voters.insert(voter.to_string());
+        if voters.len() * 2 <= self.validators.len() {
+            return;
+        }
+
+        if vote.authorize {
+            self.validators.insert(candidate.clone());
+        } else {
+            self.validators.remove(&candidate);
+            // Les votes d'un validateur retiré ne comptent plus
+            for voters in self.tallies.values_mut() {
+                voters.remove(&candidate);
+            }
+        }
+        self.tallies
+            .retain(|(target, _), voters| target != &candidate && !voters.is_empty());
+    }
+}
+
+/// Proof of Authority : les validateurs signent les blocs à tour de rôle
+///
+/// Le bloc `index` doit être signé par le validateur `index % n` (ensemble
+/// trié de `n` validateurs). La signature remplace le nonce, qui reste à 0.
+/// L'ensemble évolue par votes inscrits dans la chaîne (`ValidatorVote`).
+pub struct ProofOfAuthority {
+    /// Validateurs applicables après chaque bloc, depuis le genesis
+    states: ChainStates<ValidatorSet>,
+    /// Clé de ce nœud, s'il est validateur
+    key: Option<Wallet>,
+}
+
+impl ProofOfAuthority {
+    /// Créer le moteur
+    ///
+    /// # Arguments
+    /// * `genesis` - Validateurs au genesis
+    /// * `key` - Clé de signature de ce nœud, s'il est validateur
+    pub fn new(genesis: ValidatorSet, key: Option<Wallet>) -> Self {
+        Self {
+            states: ChainStates::new(genesis),
+            key,
+        }
+    }
+
+    /// Validateurs qui s'appliquent au bloc en position `position`
+    ///
+    /// Rejoue les votes depuis le dernier état connu (le genesis au pire).
+    ///
+    /// # Arguments
+    /// * `chain` - Blocs dont les `position` premiers précèdent le bloc
+    pub fn validators_at(&self, chain: &BlockStore, position: usize) -> Arc<ValidatorSet> {
+        self.states.at(chain, position, |state, block| {
+            if let Some(vote) = ValidatorVote::from_data(&block.data) {
+                let voter = state.in_turn(block.index).to_string();
+                Arc::make_mut(state).apply_vote(&voter, &vote);
+            }
+        })
+    }
+}
+
+impl fmt::Debug for ProofOfAuthority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProofOfAuthority")
+            .field("genesis", &self.states.genesis().validators)
+            .field("signer", &self.key.as_ref().map(Wallet::public_key_hex))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConsensusEngine for ProofOfAuthority {
+    fn name(&self) -> &'static str {
+        "poa"
+    }
+
+    fn authorize_seal(&self, chain: &BlockStore, block: &Block) -> Result<(), BlockchainError> {
+        let validators = self.validators_at(chain, chain.len());
+        match &self.key {
+            Some(key) if key.public_key_hex() == validators.in_turn(block.index) => Ok(()),
+            _ => Err(BlockchainError::NotInTurn),
+        }
+    }
+
+    /// Signer le bloc (sans clé, rien n'est signé : `authorize_seal` l'a refusé)
+    fn seal(&self, block: &mut Block, _difficulty: usize, cancel: &AtomicBool) -> bool {
+        let Some(key) = &self.key else {
+            return false;
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        sign_block(key, block);
+        true
+    }
+
+    /// Forme du sceau seulement : le signataire attendu dépend des votes
+    /// précédents et est vérifié par `verify_authority`
+    fn verify_seal(&self, block: &Block, _difficulty: usize) -> bool {
+        is_signature_seal(block)
+    }
+
+    fn verify_authority(
+        &self,
+        chain: &BlockStore,
+        position: usize,
+        block: &Block,
+    ) -> Result<(), BlockchainError> {
+        let validators = self.validators_at(chain, position);
+        if signed_by(block, validators.in_turn(block.index)) {
+            Ok(())
+        } else {
+            Err(BlockchainError::UnauthorizedSigner)
+        }
+    }
+
+    /// Tous les blocs se valent : la chaîne la plus longue l'emporte
+    fn block_weight(&self, _block: &Block, _difficulty: usize) -> u128 {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::Blockchain;
+
+    fn validators(count: usize) -> Vec<Wallet> {
+        let mut wallets: Vec<Wallet> = (0..count).map(|_| Wallet::generate()).collect();
+        wallets.sort_by_key(Wallet::public_key_hex);
+        wallets
+    }
+
+    /// Moteur de `signer` pour les validateurs initiaux `wallets`
+    fn engine(wallets: &[Wallet], signer: &Wallet) -> Arc<ProofOfAuthority> {
+        let set = ValidatorSet::new(wallets.iter().map(Wallet::public_key_hex)).unwrap();
+        Arc::new(ProofOfAuthority::new(set, Some(signer.clone())))
+    }
+
+    #[test]
+    fn test_round_robin_signing() {
+        let wallets = validators(2);
+
+        // Le bloc 1 revient au second validateur (1 % 2)
+        let mut first = Blockchain::new(0).with_consensus(engine(&wallets, &wallets[0]));
+        assert_eq!(
+            first.add_block("Out of turn".to_string()),
+            Err(BlockchainError::NotInTurn)
+        );
+        let mut second = first.clone().with_consensus(engine(&wallets, &wallets[1]));
+        let block = second.add_block("Block 1".to_string()).unwrap().clone();
+        assert_eq!(block.nonce, 0);
+        assert!(block.signature.is_some());
+
+        // Le premier validateur accepte le bloc et signe le suivant
+        first.append_block(block).unwrap();
+        first.add_block("Block 2".to_string()).unwrap();
+        assert!(first.validate_detailed().is_empty());
+
+        // Un bloc signé hors de son tour est rejeté
+        let mut forged = first.chain[2].clone();
+        forged.index = 3;
+        forged.previous_hash = first.latest_block().hash.clone();
+        forged.signature = Some(wallets[0].sign(forged.signing_payload().as_bytes()));
+        forged.hash = forged.calculate_hash();
+        assert_eq!(
+            first.validate_next(&forged),
+            Err(BlockchainError::UnauthorizedSigner)
+        );
+
+        // Comme une signature qui ne couvre pas le bloc
+        first.chain[2].data = "Tampered".to_string();
+        first.chain[2].hash = first.chain[2].calculate_hash();
+        let faults = first.validate_detailed();
+        assert_eq!(faults[0].position, 2);
+        assert_eq!(faults[0].error, BlockchainError::UnauthorizedSigner);
+    }
+
+    #[test]
+    fn test_votes_change_the_validator_set() {
+        let wallets = validators(3);
+        let (founders, candidate) = (&wallets[..2], &wallets[2]);
+        let vote = |authorize| ValidatorVote {
+            validator: candidate.public_key_hex(),
+            authorize,
+        };
+
+        let mut set = ValidatorSet::new(founders.iter().map(Wallet::public_key_hex)).unwrap();
+        let founder = |i: usize| founders[i].public_key_hex();
+
+        // Une voix sur deux ne suffit pas, voter deux fois non plus
+        set.apply_vote(&founder(0), &vote(true));
+        set.apply_vote(&founder(0), &vote(true));
+        assert_eq!(set.len(), 2);
+
+        // La majorité stricte ajoute le candidat
+        set.apply_vote(&founder(1), &vote(true));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&candidate.public_key_hex()));
+        assert!(set.tallies.is_empty());
+
+        // Le retirer demande deux voix sur trois
+        set.apply_vote(&founder(0), &vote(false));
+        assert_eq!(set.len(), 3);
+        set.apply_vote(&founder(1), &vote(false));
+        assert_eq!(set.len(), 2);
+
+        // Les votes invalides sont ignorés
+        set.apply_vote(
+            &founder(0),
+            &ValidatorVote {
+                validator: "not a key".to_string(),
+                authorize: true,
+            },
+        );
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_votes_are_read_from_the_chain() {
+        let wallets = validators(2);
+        let (founder, candidate) = (&wallets[0], &wallets[1]);
+        let vote = ValidatorVote {
+            validator: candidate.public_key_hex(),
+            authorize: true,
+        };
+        assert_eq!(
+            ValidatorVote::from_data(&vote.to_data()),
+            Some(vote.clone())
+        );
+        assert_eq!(ValidatorVote::from_data("plain data"), None);
+
+        // Seul validateur, le fondateur signe tous les blocs et vote
+        let mut chain = Blockchain::new(0).with_consensus(engine(&wallets[..1], founder));
+        chain.add_block(vote.to_data()).unwrap();
+        chain.add_block("Block 2".to_string()).unwrap();
+
+        // Le candidat signe désormais un bloc sur deux (3 % 2)
+        let mut joined = chain
+            .clone()
+            .with_consensus(engine(&wallets[..1], candidate));
+        assert_eq!(
+            chain.add_block("Block 3".to_string()),
+            Err(BlockchainError::NotInTurn)
+        );
+        let block = joined.add_block("Block 3".to_string()).unwrap().clone();
+        chain.append_block(block).unwrap();
+        chain.add_block("Block 4".to_string()).unwrap();
+        assert!(chain.validate_detailed().is_empty());
+        assert!(chain.validate_detailed_parallel().is_empty());
+    }
+
+    #[test]
+    fn test_forged_block_reusing_a_hash_keeps_the_validator_set() {
+        let wallets = validators(2);
+        let (founder, candidate) = (&wallets[0], &wallets[1]);
+        let engine = engine(&wallets[..1], founder);
+        let mut honest = Blockchain::new(0).with_consensus(engine.clone());
+        honest.add_block("Block 1".to_string()).unwrap();
+        honest.add_block("Block 2".to_string()).unwrap();
+
+        // Même hash annoncé que le bloc 1, mais un vote pour le candidat
+        let mut forged = honest.clone();
+        forged.chain[1].data = ValidatorVote {
+            validator: candidate.public_key_hex(),
+            authorize: true,
+        }
+        .to_data();
+        assert!(!forged.validate_detailed().is_empty());
+        assert_eq!(engine.validators_at(&forged.chain, 2).len(), 2);
+
+        // La chaîne honnête garde son seul validateur
+        assert_eq!(engine.validators_at(&honest.chain, 2).len(), 1);
+        assert!(honest.validate_detailed().is_empty());
+        honest.add_block("Block 3".to_string()).unwrap();
+    }
+}
@@ -0,0 +1,851 @@
+use super::{
+    is_signature_seal, normalize_key, sign_block, signed_by, ChainStates, ConsensusEngine,
+};
+use crate::block::Block;
+use crate::block_store::BlockStore;
+use crate::blockchain::BlockchainError;
+use crate::wallet::{self, Wallet};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Délai sans bloc après lequel le proposant tiré pour une hauteur est relayé
+/// par le tirage suivant
+pub const SLOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Avance tolérée de l'horodatage d'un bloc reçu sur l'horloge locale
+pub const MAX_CLOCK_DRIFT: Duration = Duration::from_secs(2);
+
+/// Nombre de blocs avant qu'un changement de mise compte pour le tirage
+///
+/// Les mises tirées sont figées avant que les hashes qui servent de graine
+/// soient connus : on ne peut pas déplacer sa mise vers la clé qu'un tirage
+/// à venir favorise.
+pub const STAKE_DELAY: usize = 8;
+
+/// Tentative à laquelle `block` a été proposé sur `parent` : 0, puis une de
+/// plus par `SLOT_TIMEOUT` écoulé depuis l'horodatage du parent
+///
+/// Ne dépend que des deux horodatages. Un bloc daté dans le futur, qui
+/// s'attribuerait une tentative pas encore commencée, est refusé à sa
+/// réception (`ConsensusEngine::verify_arrival`).
+///
+/// # Returns
+/// None si le bloc est daté avant son parent
+pub fn attempt(parent: &Block, block: &Block) -> Option<u64> {
+    let elapsed = (block.timestamp - parent.timestamp).to_std().ok()?;
+    u64::try_from(elapsed.as_millis() / SLOT_TIMEOUT.as_millis()).ok()
+}
+
+/// Preuve qu'un validateur a signé deux blocs différents à la même hauteur
+///
+/// Soumise comme données d'un bloc :
+/// `{"slash": {"validator": "<clé publique>", "first": {..}, "second": {..}}}`.
+/// Une fois la preuve inscrite dans la chaîne, toute la mise du validateur
+/// est confisquée.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoubleSignEvidence {
+    /// Clé publique compressée du validateur fautif
+    pub validator: String,
+    pub first: Block,
+    pub second: Block,
+}
+
+/// Forme des données d'un bloc de dénonciation
+#[derive(Serialize, Deserialize)]
+struct SlashData {
+    slash: DoubleSignEvidence,
+}
+
+impl DoubleSignEvidence {
+    /// Données du bloc qui porte cette preuve
+    pub fn to_data(&self) -> String {
+        serde_json::to_string(&SlashData {
+            slash: self.clone(),
+        })
+        .expect("evidence serializes")
+    }
+
+    /// Lire la preuve portée par les données d'un bloc, s'il y en a une
+    pub fn from_data(data: &str) -> Option<Self> {
+        if !data.starts_with('{') {
+            return None;
+        }
+        serde_json::from_str::<SlashData>(data)
+            .ok()
+            .map(|data| data.slash)
+    }
+
+    /// Vérifier que les deux blocs sont distincts, de même hauteur et signés
+    /// tous les deux par `validator`
+    pub fn verify(&self) -> bool {
+        self.first.index == self.second.index
+            && self.first.signing_payload() != self.second.signing_payload()
+            && signed_by(&self.first, &self.validator)
+            && signed_by(&self.second, &self.validator)
+    }
+}
+
+/// Opération sur une mise
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StakeAction {
+    /// Céder `amount` de sa mise au validateur `to`
+    Delegate { to: String, amount: u64 },
+    /// Retirer `amount` de sa mise
+    Unstake { amount: u64 },
+}
+
+/// Changement de mise signé par son détenteur
+///
+/// Soumis comme données d'un bloc :
+/// `{"stake": {"owner": "<clé publique>", "action": "delegate", "to": "<clé publique>", "amount": 5, "nonce": 0, "signature": ".."}}`,
+/// ou `"action": "unstake"` sans `to`. Les mises partent de celles du
+/// genesis : une nouvelle clé n'en obtient qu'en recevant une délégation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakeChange {
+    /// Clé publique compressée du détenteur de la mise
+    pub owner: String,
+    #[serde(flatten)]
+    pub action: StakeAction,
+    /// Numéro de l'opération pour `owner` (0, 1, 2...) : une opération
+    /// rejouée dans un autre bloc est ignorée
+    pub nonce: u64,
+    pub signature: String,
+}
+
+/// Forme des données d'un bloc de changement de mise
+#[derive(Serialize, Deserialize)]
+struct StakeData {
+    stake: StakeChange,
+}
+
+impl StakeChange {
+    /// Créer et signer l'opération `nonce` du détenteur `wallet`
+    pub fn new(wallet: &Wallet, action: StakeAction, nonce: u64) -> Self {
+        let mut change = Self {
+            owner: wallet.public_key_hex(),
+            action,
+            nonce,
+            signature: String::new(),
+        };
+        change.signature = wallet.sign(change.signing_payload().as_bytes());
+        change
+    }
+
+    /// Données couvertes par la signature
+    fn signing_payload(&self) -> String {
+        match &self.action {
+            StakeAction::Delegate { to, amount } => format!(
+                "stake:{}:delegate:{}:{}:{}",
+                self.owner, to, amount, self.nonce
+            ),
+            StakeAction::Unstake { amount } => {
+                format!("stake:{}:unstake:{}:{}", self.owner, amount, self.nonce)
+            }
+        }
+    }
+
+    /// Vérifier la signature du détenteur
+    pub fn verify(&self) -> bool {
+        wallet::verify_signature(
+            &self.owner,
+            self.signing_payload().as_bytes(),
+            &self.signature,
+        )
+    }
+
+    /// Données du bloc qui porte cette opération
+    pub fn to_data(&self) -> String {
+        serde_json::to_string(&StakeData {
+            stake: self.clone(),
+        })
+        .expect("stake change serializes")
+    }
+
+    /// Lire l'opération portée par les données d'un bloc, s'il y en a une
+    pub fn from_data(data: &str) -> Option<Self> {
+        if !data.starts_with('{') {
+            return None;
+        }
+        serde_json::from_str::<StakeData>(data)
+            .ok()
+            .map(|data| data.stake)
+    }
+}
+
+/// Mises des validateurs à une hauteur donnée
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakeRegistry {
+    /// Mise de chaque validateur (jamais nulle), triés par clé publique
+    stakes: BTreeMap<String, u64>,
+    /// Validateurs dont la mise a été confisquée
+    slashed: BTreeSet<String>,
+    /// Prochain `StakeChange::nonce` attendu de chaque détenteur
+    nonces: BTreeMap<String, u64>,
+}
+
+impl StakeRegistry {
+    /// Créer le registre initial (les mises d'une même clé s'additionnent)
+    ///
+    /// # Returns
+    /// Une erreur si une clé est invalide ou si aucune mise n'est positive
+    pub fn new(stakes: impl IntoIterator<Item = (String, u64)>) -> Result<Self, String> {
+        let mut registry = BTreeMap::new();
+        for (key, amount) in stakes {
+            let stake: &mut u64 = registry.entry(normalize_key(&key)?).or_default();
+            *stake = stake.saturating_add(amount);
+        }
+        registry.retain(|_, stake| *stake > 0);
+        if registry.is_empty() {
+            return Err("Proof of Stake needs at least one validator with stake".to_string());
+        }
+        Ok(Self {
+            stakes: registry,
+            slashed: BTreeSet::new(),
+            nonces: BTreeMap::new(),
+        })
+    }
+
+    /// Parcourir les validateurs et leur mise
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.stakes
+            .iter()
+            .map(|(key, stake)| (key.as_str(), *stake))
+    }
+
+    /// Mise d'un validateur (0 s'il n'en a pas ou a été sanctionné)
+    pub fn stake_of(&self, validator: &str) -> u64 {
+        self.stakes.get(validator).copied().unwrap_or(0)
+    }
+
+    /// Somme des mises
+    pub fn total_stake(&self) -> u128 {
+        self.stakes.values().map(|stake| u128::from(*stake)).sum()
+    }
+
+    pub fn is_slashed(&self, validator: &str) -> bool {
+        self.slashed.contains(validator)
+    }
+
+    /// `nonce` attendu pour la prochaine opération de `owner`
+    pub fn next_nonce(&self, owner: &str) -> u64 {
+        self.nonces.get(owner).copied().unwrap_or(0)
+    }
+
+    /// Validateur tiré au sort pour proposer le bloc qui suit `previous_hash`,
+    /// à la tentative `attempt`
+    ///
+    /// Tous les nœuds obtiennent le même proposant, avec une probabilité
+    /// proportionnelle à sa mise. Le proposant d'un bloc choisit son contenu,
+    /// donc son hash : il peut en essayer autant qu'il veut pour orienter le
+    /// tirage suivant. `excluded`, le signataire du parent, n'est donc jamais
+    /// tiré tant qu'un autre validateur a une mise : il ne peut pas se
+    /// désigner lui-même.
+    ///
+    /// # Arguments
+    /// * `previous_hash` - Hash du parent du bloc à proposer
+    /// * `attempt` - Tentative (voir `attempt`), pour relayer un proposant absent
+    /// * `excluded` - Signataire du parent, s'il y en a un
+    pub fn leader(&self, previous_hash: &str, attempt: u64, excluded: Option<&str>) -> &str {
+        let excluded = excluded.filter(|excluded| self.stakes.keys().any(|key| key != excluded));
+        let candidates = || {
+            self.iter()
+                .filter(move |(validator, _)| Some(*validator) != excluded)
+        };
+        let total: u128 = candidates().map(|(_, stake)| u128::from(stake)).sum();
+
+        let mut hasher = Sha256::new();
+        hasher.update(previous_hash.as_bytes());
+        hasher.update(attempt.to_be_bytes());
+        let seed: [u8; 32] = hasher.finalize().into();
+        let seed = u128::from_be_bytes(seed[..16].try_into().expect("16 bytes"));
+
+        let mut draw = seed % total;
+        for (validator, stake) in candidates() {
+            match draw.checked_sub(u128::from(stake)) {
+                Some(rest) => draw = rest,
+                None => return validator,
+            }
+        }
+        unreachable!("draw is below the total stake")
+    }
+
+    /// Appliquer un changement de mise inscrit dans la chaîne
+    ///
+    /// Les opérations mal signées, hors séquence (`nonce`), supérieures à la
+    /// mise du détenteur, vers un validateur sanctionné ou qui retireraient la
+    /// dernière mise sont ignorées.
+    ///
+    /// # Returns
+    /// true si le changement a été appliqué
+    pub fn apply(&mut self, change: &StakeChange) -> bool {
+        let Ok(owner) = normalize_key(&change.owner) else {
+            return false;
+        };
+        if change.nonce != self.next_nonce(&owner) || !change.verify() {
+            return false;
+        }
+        let balance = self.stake_of(&owner);
+        match &change.action {
+            StakeAction::Delegate { to, amount } => {
+                let Ok(to) = normalize_key(to) else {
+                    return false;
+                };
+                if to == owner || *amount == 0 || *amount > balance || self.is_slashed(&to) {
+                    return false;
+                }
+                self.withdraw(&owner, *amount);
+                let stake = self.stakes.entry(to).or_default();
+                *stake = stake.saturating_add(*amount);
+            }
+            StakeAction::Unstake { amount } => {
+                if *amount == 0 || *amount > balance || u128::from(*amount) == self.total_stake() {
+                    return false;
+                }
+                self.withdraw(&owner, *amount);
+            }
+        }
+        self.nonces.insert(owner, change.nonce + 1);
+        true
+    }
+
+    fn withdraw(&mut self, owner: &str, amount: u64) {
+        if let Some(stake) = self.stakes.get_mut(owner) {
+            *stake -= amount;
+            if *stake == 0 {
+                self.stakes.remove(owner);
+            }
+        }
+    }
+
+    /// Confisquer la mise du validateur dénoncé par `evidence`
+    ///
+    /// Les preuves invalides, visant un validateur sans mise ou qui
+    /// retireraient la dernière mise sont ignorées.
+    ///
+    /// # Returns
+    /// true si la mise a été confisquée
+    pub fn slash(&mut self, evidence: &DoubleSignEvidence) -> bool {
+        let Ok(validator) = normalize_key(&evidence.validator) else {
+            return false;
+        };
+        if !self.stakes.contains_key(&validator) || self.stakes.len() == 1 || !evidence.verify() {
+            return false;
+        }
+        self.stakes.remove(&validator);
+        self.slashed.insert(validator);
+        true
+    }
+}
+
+/// Proof of Stake : un validateur tiré au sort selon sa mise signe chaque bloc
+///
+/// Le proposant d'un bloc est tiré à partir du hash de son parent
+/// (`StakeRegistry::leader`) ; sa signature remplace le nonce, qui reste à 0.
+/// S'il ne propose rien pendant `SLOT_TIMEOUT`, un bloc daté plus tard est
+/// attendu du tirage suivant.
+/// Les mises sont dérivées de la chaîne : celles du genesis, puis les
+/// changements de mise (`StakeChange`) et les dénonciations de double
+/// signature (`DoubleSignEvidence`) inscrits dans les blocs. Le tirage
+/// utilise les mises d'il y a `STAKE_DELAY` blocs, sans les validateurs
+/// sanctionnés depuis.
+pub struct ProofOfStake {
+    /// Mises applicables après chaque bloc, depuis le genesis
+    states: ChainStates<StakeRegistry>,
+    /// Clé de ce nœud, s'il est validateur
+    key: Option<Wallet>,
+}
+
+impl ProofOfStake {
+    /// Créer le moteur
+    ///
+    /// # Arguments
+    /// * `genesis` - Mises au genesis
+    /// * `key` - Clé de signature de ce nœud, s'il est validateur
+    pub fn new(genesis: StakeRegistry, key: Option<Wallet>) -> Self {
+        Self {
+            states: ChainStates::new(genesis),
+            key,
+        }
+    }
+
+    /// Mises qui s'appliquent au bloc en position `position`
+    ///
+    /// # Arguments
+    /// * `chain` - Blocs dont les `position` premiers précèdent le bloc
+    pub fn stakes_at(&self, chain: &BlockStore, position: usize) -> Arc<StakeRegistry> {
+        self.states.at(chain, position, |state, block| {
+            if let Some(evidence) = DoubleSignEvidence::from_data(&block.data) {
+                let mut next = StakeRegistry::clone(state);
+                if next.slash(&evidence) {
+                    *state = Arc::new(next);
+                }
+            } else if let Some(change) = StakeChange::from_data(&block.data) {
+                let mut next = StakeRegistry::clone(state);
+                if next.apply(&change) {
+                    *state = Arc::new(next);
+                }
+            }
+        })
+    }
+
+    /// Mises parmi lesquelles est tiré le proposant du bloc en position
+    /// `position` : celles d'il y a `STAKE_DELAY` blocs, sans les validateurs
+    /// sanctionnés depuis
+    pub fn draw_stakes(&self, chain: &BlockStore, position: usize) -> StakeRegistry {
+        let current = self.stakes_at(chain, position);
+        let mut draw = StakeRegistry::clone(
+            &self.stakes_at(chain, position.saturating_sub(STAKE_DELAY).max(1)),
+        );
+        draw.stakes
+            .retain(|validator, _| !current.is_slashed(validator));
+        if draw.stakes.is_empty() {
+            return StakeRegistry::clone(&current);
+        }
+        draw
+    }
+
+    /// Proposant attendu pour `block` en position `position`
+    ///
+    /// # Returns
+    /// None sans parent ou si le bloc est daté avant son parent
+    fn proposer(&self, chain: &BlockStore, position: usize, block: &Block) -> Option<String> {
+        let parent = chain.get(position.checked_sub(1)?)?;
+        let attempt = attempt(parent, block)?;
+        let stakes = self.draw_stakes(chain, position);
+        let parent_signer = stakes
+            .iter()
+            .map(|(validator, _)| validator)
+            .find(|validator| signed_by(parent, validator));
+        let leader = stakes.leader(&parent.hash, attempt, parent_signer);
+        Some(leader.to_string())
+    }
+}
+
+impl fmt::Debug for ProofOfStake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProofOfStake")
+            .field("genesis", &self.states.genesis().stakes)
+            .field("signer", &self.key.as_ref().map(Wallet::public_key_hex))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConsensusEngine for ProofOfStake {
+    fn name(&self) -> &'static str {
+        "pos"
+    }
+
+    fn authorize_seal(&self, chain: &BlockStore, block: &Block) -> Result<(), BlockchainError> {
+        let proposer = self.proposer(chain, chain.len(), block);
+        match &self.key {
+            Some(key) if proposer == Some(key.public_key_hex()) => Ok(()),
+            _ => Err(BlockchainError::NotInTurn),
+        }
+    }
+
+    /// Signer le bloc (sans clé, rien n'est signé : `authorize_seal` l'a refusé)
+    fn seal(&self, block: &mut Block, _difficulty: usize, cancel: &AtomicBool) -> bool {
+        let Some(key) = &self.key else {
+            return false;
+        };
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        sign_block(key, block);
+        true
+    }
+
+    /// Forme du sceau seulement : le proposant attendu dépend des mises et est
+    /// vérifié par `verify_authority`
+    fn verify_seal(&self, block: &Block, _difficulty: usize) -> bool {
+        is_signature_seal(block)
+    }
+
+    fn verify_authority(
+        &self,
+        chain: &BlockStore,
+        position: usize,
+        block: &Block,
+    ) -> Result<(), BlockchainError> {
+        match self.proposer(chain, position, block) {
+            Some(proposer) if signed_by(block, &proposer) => Ok(()),
+            _ => Err(BlockchainError::UnauthorizedSigner),
+        }
+    }
+
+    /// Refuser un bloc daté plus de `MAX_CLOCK_DRIFT` après l'horloge locale :
+    /// il revendiquerait une tentative (`attempt`) qui n'a pas commencé
+    fn verify_arrival(&self, block: &Block) -> Result<(), BlockchainError> {
+        let drift = chrono::Duration::from_std(MAX_CLOCK_DRIFT).expect("drift fits chrono");
+        if block.timestamp > Utc::now() + drift {
+            return Err(BlockchainError::FutureBlock);
+        }
+        Ok(())
+    }
+
+    /// Tous les blocs se valent : la chaîne la plus longue l'emporte
+    fn block_weight(&self, _block: &Block, _difficulty: usize) -> u128 {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::Blockchain;
+
+    fn registry(stakes: &[(&Wallet, u64)]) -> StakeRegistry {
+        StakeRegistry::new(
+            stakes
+                .iter()
+                .map(|(wallet, stake)| (wallet.public_key_hex(), *stake)),
+        )
+        .unwrap()
+    }
+
+    fn engine(genesis: &StakeRegistry, key: Option<&Wallet>) -> Arc<ProofOfStake> {
+        Arc::new(ProofOfStake::new(genesis.clone(), key.cloned()))
+    }
+
+    /// Faire proposer `data` par le validateur tiré au sort
+    fn propose(chain: &mut Blockchain, genesis: &StakeRegistry, wallets: &[Wallet], data: &str) {
+        for wallet in wallets {
+            let mut proposer = chain.clone().with_consensus(engine(genesis, Some(wallet)));
+            if let Ok(block) = proposer.add_block(data.to_string()) {
+                chain.append_block(block.clone()).unwrap();
+                return;
+            }
+        }
+        panic!("no validator could propose block {}", chain.chain.len());
+    }
+
+    /// Wallet qui a signé `block`
+    fn signer<'a>(wallets: &'a [Wallet], block: &Block) -> &'a Wallet {
+        wallets
+            .iter()
+            .find(|wallet| signed_by(block, &wallet.public_key_hex()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_leader_selection_is_deterministic_and_stake_weighted() {
+        let (small, large) = (Wallet::generate(), Wallet::generate());
+        let stakes = registry(&[(&small, 1), (&large, 3), (&small, 0)]);
+        assert_eq!(stakes.total_stake(), 4);
+        assert!(StakeRegistry::new([(small.public_key_hex(), 0)]).is_err());
+
+        let hashes: Vec<String> = (0..2000).map(|i| format!("hash {}", i)).collect();
+        let leaders: Vec<&str> = hashes
+            .iter()
+            .map(|hash| stakes.leader(hash, 0, None))
+            .collect();
+        assert_eq!(leaders[7], stakes.leader(&hashes[7], 0, None));
+        let large_slots = leaders
+            .iter()
+            .filter(|leader| **leader == large.public_key_hex())
+            .count();
+        assert!((1350..1650).contains(&large_slots), "{}", large_slots);
+
+        // Le signataire du parent n'est jamais tiré, sauf s'il est seul
+        let large_key = large.public_key_hex();
+        assert!(hashes
+            .iter()
+            .all(|hash| stakes.leader(hash, 0, Some(&large_key)) == small.public_key_hex()));
+        let alone = registry(&[(&large, 3)]);
+        assert_eq!(alone.leader("hash", 0, Some(&large_key)), large_key);
+    }
+
+    #[test]
+    fn test_only_the_drawn_leader_can_propose() {
+        let wallets = [Wallet::generate(), Wallet::generate(), Wallet::generate()];
+        let genesis = registry(&[(&wallets[0], 5), (&wallets[1], 5), (&wallets[2], 5)]);
+        let mut chain = Blockchain::new(0).with_consensus(engine(&genesis, None));
+
+        for i in 1..=6 {
+            propose(&mut chain, &genesis, &wallets, &format!("Block {}", i));
+            // Le proposant d'un bloc ne peut pas se tirer pour le suivant
+            if i > 1 {
+                assert_ne!(
+                    signer(&wallets, &chain.chain[i - 1]).public_key_hex(),
+                    signer(&wallets, &chain.chain[i]).public_key_hex()
+                );
+            }
+        }
+        assert!(chain.validate_detailed().is_empty());
+        assert!(chain.validate_detailed_parallel().is_empty());
+
+        // Un validateur qui n'a pas été tiré ne peut pas signer le bloc
+        let block = chain.chain[3].clone();
+        let leader = signer(&wallets, &block).public_key_hex();
+        chain.chain.truncate(3);
+        for other in wallets.iter().filter(|w| w.public_key_hex() != leader) {
+            let mut forged = block.clone();
+            sign_block(other, &mut forged);
+            assert_eq!(
+                chain.validate_next(&forged),
+                Err(BlockchainError::UnauthorizedSigner)
+            );
+        }
+        assert_eq!(chain.validate_next(&block), Ok(()));
+    }
+
+    #[test]
+    fn test_missing_leader_is_replaced_after_timeout() {
+        let wallets = [Wallet::generate(), Wallet::generate()];
+        let genesis = registry(&[(&wallets[0], 5), (&wallets[1], 5)]);
+        let mut chain = Blockchain::new(0).with_consensus(engine(&genesis, None));
+        chain.chain[0].timestamp -= chrono::Duration::hours(1);
+        chain.chain[0].hash = chain.chain[0].calculate_hash();
+        let parent = chain.chain[0].clone();
+
+        // Le validateur qui n'a pas été tiré pour la première tentative attend
+        // la première tentative qui le désigne
+        let absent = genesis.leader(&parent.hash, 0, None);
+        let other = wallets
+            .iter()
+            .find(|wallet| wallet.public_key_hex() != absent)
+            .unwrap();
+        let turn = (1..64)
+            .find(|attempt| genesis.leader(&parent.hash, *attempt, None) != absent)
+            .unwrap();
+        let slot = chrono::Duration::from_std(SLOT_TIMEOUT).unwrap();
+        let block_at = |offset: chrono::Duration| {
+            let mut block = Block::with_timestamp(
+                1,
+                "Block 1".to_string(),
+                parent.hash.clone(),
+                parent.timestamp + offset,
+            );
+            sign_block(other, &mut block);
+            block
+        };
+
+        let early = block_at(slot * turn as i32 - chrono::Duration::seconds(1));
+        assert_eq!(
+            chain.validate_next(&early),
+            Err(BlockchainError::UnauthorizedSigner)
+        );
+        let relay = block_at(slot * turn as i32);
+        assert_eq!(chain.validate_next(&relay), Ok(()));
+
+        // Un horodatage antérieur au parent ne désigne personne
+        let backdated = block_at(-slot);
+        assert_eq!(
+            chain.validate_next(&backdated),
+            Err(BlockchainError::UnauthorizedSigner)
+        );
+
+        // Un bloc daté dans le futur est refusé à sa réception, mais la
+        // validité de la chaîne ne dépend pas de l'horloge
+        let ahead = block_at(chrono::Duration::hours(2));
+        assert_eq!(
+            chain.validate_next(&ahead),
+            Err(BlockchainError::FutureBlock)
+        );
+        let observer = engine(&genesis, None);
+        let attempt = attempt(&parent, &ahead).unwrap();
+        let expected = if genesis.leader(&parent.hash, attempt, None) == absent {
+            Err(BlockchainError::UnauthorizedSigner)
+        } else {
+            Ok(())
+        };
+        assert_eq!(observer.verify_authority(&chain.chain, 1, &ahead), expected);
+    }
+
+    #[test]
+    fn test_double_signing_is_slashed() {
+        let wallets = [Wallet::generate(), Wallet::generate()];
+        let genesis = registry(&[(&wallets[0], 5), (&wallets[1], 5)]);
+        let mut chain = Blockchain::new(0).with_consensus(engine(&genesis, None));
+        propose(&mut chain, &genesis, &wallets, "Block 1");
+
+        // Le proposant du bloc 1 signe un second bloc à la même hauteur
+        let first = chain.chain[1].clone();
+        let wallet = signer(&wallets, &first);
+        let offender = wallet.public_key_hex();
+        let mut second = first.clone();
+        second.data = "Conflicting block 1".to_string();
+        sign_block(wallet, &mut second);
+
+        let evidence = DoubleSignEvidence {
+            validator: offender.clone(),
+            first: first.clone(),
+            second,
+        };
+        assert!(evidence.verify());
+        assert_eq!(
+            DoubleSignEvidence::from_data(&evidence.to_data()),
+            Some(evidence.clone())
+        );
+        let replayed = DoubleSignEvidence {
+            second: first,
+            ..evidence.clone()
+        };
+        assert!(!replayed.verify());
+
+        // Une fois la preuve dans la chaîne, le fautif n'est plus jamais tiré
+        propose(&mut chain, &genesis, &wallets, &evidence.to_data());
+        let observer = engine(&genesis, None);
+        let stakes = observer.stakes_at(&chain.chain, chain.chain.len());
+        assert!(stakes.is_slashed(&offender));
+        assert_eq!(stakes.stake_of(&offender), 0);
+        for i in 3..=8 {
+            propose(&mut chain, &genesis, &wallets, &format!("Block {}", i));
+            assert!(!signed_by(chain.latest_block(), &offender));
+        }
+        assert!(chain.validate_detailed().is_empty());
+
+        // Ni deux fois, ni quand c'est la dernière mise
+        assert!(!StakeRegistry::clone(&stakes).slash(&evidence));
+        assert!(!registry(&[(wallet, 5)]).slash(&evidence));
+    }
+
+    #[test]
+    fn test_stake_changes_count_after_the_delay() {
+        let wallets = [Wallet::generate(), Wallet::generate(), Wallet::generate()];
+        let newcomer = wallets[2].public_key_hex();
+        let genesis = registry(&[(&wallets[0], 5), (&wallets[1], 5)]);
+        let mut chain = Blockchain::new(0).with_consensus(engine(&genesis, None));
+
+        let delegate = StakeChange::new(
+            &wallets[0],
+            StakeAction::Delegate {
+                to: newcomer.clone(),
+                amount: 4,
+            },
+            0,
+        );
+        assert_eq!(
+            StakeChange::from_data(&delegate.to_data()),
+            Some(delegate.clone())
+        );
+        propose(&mut chain, &genesis, &wallets, &delegate.to_data());
+        // Rejouée, l'opération est ignorée
+        propose(&mut chain, &genesis, &wallets, &delegate.to_data());
+
+        let observer = engine(&genesis, None);
+        let stakes = observer.stakes_at(&chain.chain, chain.chain.len());
+        assert_eq!(stakes.stake_of(&wallets[0].public_key_hex()), 1);
+        assert_eq!(stakes.stake_of(&newcomer), 4);
+        assert_eq!(stakes.next_nonce(&wallets[0].public_key_hex()), 1);
+
+        // La nouvelle mise ne compte pour le tirage qu'après STAKE_DELAY blocs
+        while chain.chain.len() <= STAKE_DELAY + 1 {
+            let position = chain.chain.len();
+            assert_eq!(
+                observer
+                    .draw_stakes(&chain.chain, position)
+                    .stake_of(&newcomer),
+                0
+            );
+            propose(
+                &mut chain,
+                &genesis,
+                &wallets,
+                &format!("Block {}", position),
+            );
+            assert!(!signed_by(chain.latest_block(), &newcomer));
+        }
+        let position = chain.chain.len();
+        assert_eq!(
+            observer
+                .draw_stakes(&chain.chain, position)
+                .stake_of(&newcomer),
+            4
+        );
+        while !signed_by(chain.latest_block(), &newcomer) {
+            assert!(chain.chain.len() < 200, "the newcomer is never drawn");
+            let position = chain.chain.len();
+            propose(
+                &mut chain,
+                &genesis,
+                &wallets,
+                &format!("Block {}", position),
+            );
+        }
+        assert!(chain.validate_detailed().is_empty());
+    }
+
+    #[test]
+    fn test_forged_block_reusing_a_hash_keeps_the_drawn_leaders() {
+        let wallets = [Wallet::generate(), Wallet::generate(), Wallet::generate()];
+        let newcomer = wallets[2].public_key_hex();
+        let genesis = registry(&[(&wallets[0], 5), (&wallets[1], 5)]);
+        let observer = engine(&genesis, None);
+        let mut honest = Blockchain::new(0).with_consensus(observer.clone());
+        for i in 1..=STAKE_DELAY + 1 {
+            propose(&mut honest, &genesis, &wallets, &format!("Block {}", i));
+        }
+
+        // Même hash annoncé que le bloc 1, mais une délégation au nouveau venu
+        let mut forged = honest.clone();
+        forged.chain[1].data = StakeChange::new(
+            &wallets[0],
+            StakeAction::Delegate {
+                to: newcomer.clone(),
+                amount: 4,
+            },
+            0,
+        )
+        .to_data();
+        assert!(!forged.validate_detailed().is_empty());
+        let position = forged.chain.len();
+        assert_eq!(
+            observer
+                .draw_stakes(&forged.chain, position)
+                .stake_of(&newcomer),
+            4
+        );
+
+        // La chaîne honnête tire toujours ses proposants parmi ses mises
+        assert_eq!(
+            observer
+                .draw_stakes(&honest.chain, position)
+                .stake_of(&newcomer),
+            0
+        );
+        assert!(honest.validate_detailed().is_empty());
+        for i in 0..4 {
+            propose(&mut honest, &genesis, &wallets[..2], &format!("Next {}", i));
+        }
+    }
+
+    #[test]
+    fn test_invalid_stake_changes_are_ignored() {
+        let (owner, other) = (Wallet::generate(), Wallet::generate());
+        let mut stakes = registry(&[(&owner, 5), (&other, 5)]);
+        let unstake = |wallet: &Wallet, amount, nonce| {
+            StakeChange::new(wallet, StakeAction::Unstake { amount }, nonce)
+        };
+
+        // Signature d'un autre détenteur, nonce hors séquence, mise insuffisante
+        let mut forged = unstake(&other, 2, 0);
+        forged.owner = owner.public_key_hex();
+        assert!(!stakes.apply(&forged));
+        assert!(!stakes.apply(&unstake(&owner, 2, 1)));
+        assert!(!stakes.apply(&unstake(&owner, 6, 0)));
+        let to_self = StakeChange::new(
+            &owner,
+            StakeAction::Delegate {
+                to: owner.public_key_hex(),
+                amount: 1,
+            },
+            0,
+        );
+        assert!(!stakes.apply(&to_self));
+
+        assert!(stakes.apply(&unstake(&owner, 5, 0)));
+        assert_eq!(stakes.stake_of(&owner.public_key_hex()), 0);
+        assert_eq!(stakes.total_stake(), 5);
+        // La dernière mise ne peut pas être retirée
+        assert!(!stakes.apply(&unstake(&other, 5, 0)));
+        assert!(stakes.apply(&unstake(&other, 4, 0)));
+    }
+}
@@ -0,0 +1,129 @@
+use super::ConsensusEngine;
+use crate::block::Block;
+use crate::block_store::BlockStore;
+use crate::blockchain::BlockchainError;
+use crate::proof_of_work::{self, PowAlgorithm};
+use std::sync::atomic::AtomicBool;
+
+/// Proof of Work : le hash de travail doit commencer par `difficulty` zéros
+/// hexadécimaux
+///
+/// L'algorithme (SHA-256 ou Argon2id) est un paramètre de la chaîne : chaque
+/// bloc l'inscrit dans son en-tête et un bloc miné avec un autre algorithme
+/// est rejeté.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProofOfWork {
+    algorithm: PowAlgorithm,
+}
+
+impl ProofOfWork {
+    pub fn new(algorithm: PowAlgorithm) -> Self {
+        Self { algorithm }
+    }
+
+    pub fn algorithm(&self) -> PowAlgorithm {
+        self.algorithm
+    }
+}
+
+impl ConsensusEngine for ProofOfWork {
+    fn name(&self) -> &'static str {
+        "pow"
+    }
+
+    /// Refuser avant le mining des données qu'un en-tête Bitcoin ne peut
+    /// pas engager sans ambiguïté : le bloc serait rejeté une fois miné
+    fn authorize_seal(&self, _chain: &BlockStore, block: &Block) -> Result<(), BlockchainError> {
+        if self.algorithm == PowAlgorithm::Sha256d && !block.has_canonical_transactions() {
+            return Err(BlockchainError::InvalidTransactions);
+        }
+        Ok(())
+    }
+
+    fn seal(&self, block: &mut Block, difficulty: usize, cancel: &AtomicBool) -> bool {
+        block.algorithm = self.algorithm;
+        proof_of_work::mine_block_until(block, difficulty, cancel)
+    }
+
+    fn verify_seal(&self, block: &Block, difficulty: usize) -> bool {
+        block.algorithm == self.algorithm && proof_of_work::verify_block_work(block, difficulty)
+    }
+
+    /// Nombre moyen de hashes nécessaires pour miner le bloc (16^difficulté)
+    fn block_weight(&self, _block: &Block, difficulty: usize) -> u128 {
+        u32::try_from(difficulty)
+            .ok()
+            .and_then(|difficulty| 16u128.checked_pow(difficulty))
+            .unwrap_or(u128::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::Blockchain;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_seal_verify_and_weight() {
+        let engine = ProofOfWork::default();
+        let mut block = Block::new(1, "Sealed".to_string(), "prev_hash".to_string());
+
+        assert!(engine.seal(&mut block, 2, &AtomicBool::new(false)));
+        assert_eq!(block.hash, block.calculate_hash());
+        assert!(engine.verify_seal(&block, 2));
+        assert!(!engine.verify_seal(&block, 64));
+
+        assert_eq!(engine.block_weight(&block, 0), 1);
+        assert_eq!(engine.block_weight(&block, 2), 256);
+        assert_eq!(engine.block_weight(&block, 64), u128::MAX);
+    }
+
+    #[test]
+    fn test_blocks_of_another_algorithm_are_rejected() {
+        let argon2 = ProofOfWork::new(PowAlgorithm::Argon2id);
+        let mut block = Block::new(1, "Memory hard".to_string(), "prev_hash".to_string());
+
+        assert!(argon2.seal(&mut block, 1, &AtomicBool::new(false)));
+        assert_eq!(block.algorithm, PowAlgorithm::Argon2id);
+        assert!(argon2.verify_seal(&block, 1));
+        assert!(!ProofOfWork::default().verify_seal(&block, 0));
+    }
+
+    #[test]
+    fn test_bitcoin_style_chain() {
+        let engine = Arc::new(ProofOfWork::new(PowAlgorithm::Sha256d));
+        let mut chain = Blockchain::new(1).with_consensus(engine.clone());
+        chain.add_block("Block 1".to_string()).unwrap();
+        chain.add_block("Block 2".to_string()).unwrap();
+
+        let tip = chain.latest_block();
+        assert_eq!(tip.algorithm, PowAlgorithm::Sha256d);
+        assert_eq!(tip.hash, tip.bitcoin_header().block_hash());
+        assert!(chain.validate_detailed().is_empty());
+
+        // Les données sont engagées par la racine de Merkle de l'en-tête
+        chain.chain[1].data = "Tampered".to_string();
+        assert_eq!(
+            chain.validate_detailed()[0].error,
+            BlockchainError::InvalidHash
+        );
+
+        // Dupliquer la dernière transaction garde la racine, donc le hash
+        // Bitcoin : le bloc est refusé quand même
+        let mut fees = Blockchain::new(1).with_consensus(engine);
+        fees.add_block("Block 1\nFee 1".to_string()).unwrap();
+        let mut duplicated = fees.latest_block().clone();
+        assert!(fees.verify_block(&duplicated));
+        duplicated.data.push_str("\nFee 1");
+        assert_eq!(duplicated.calculate_hash(), duplicated.hash);
+        assert!(!fees.verify_block(&duplicated));
+
+        // Des données ambiguës pour la racine de Merkle ne sont pas minées
+        assert_eq!(
+            chain.add_block("Block 3\n".to_string()).unwrap_err(),
+            BlockchainError::InvalidTransactions
+        );
+        assert_eq!(chain.len(), 3);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::block::Block;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+/// Capacité par défaut du bus d'événements (événements conservés pour un abonné lent)
+pub const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// Événements publiés par le nœud
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEvent {
+    /// Un nouveau bloc a été ajouté au sommet de la chaîne
+    BlockAdded { block: Block },
+
+    /// La chaîne a été réorganisée à partir de `fork_height`
+    Reorg {
+        fork_height: u64,
+        old_tip: String,
+        new_tip: String,
+    },
+
+    /// Une transaction a été acceptée et attend d'être minée
+    TxAccepted { txid: String },
+
+    /// Une transaction acceptée ne sera pas minée (mining annulé, bloc refusé)
+    TxDropped { txid: String, reason: String },
+
+    /// Une validation (bloc ou chaîne) a échoué
+    ValidationFailed {
+        block_index: Option<u64>,
+        reason: String,
+    },
+}
+
+/// Sujets auxquels un client peut s'abonner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EventTopic {
+    Blocks,
+    Reorgs,
+    Mempool,
+    Validation,
+}
+
+impl EventTopic {
+    /// Tous les sujets disponibles
+    pub const ALL: [EventTopic; 4] = [
+        EventTopic::Blocks,
+        EventTopic::Reorgs,
+        EventTopic::Mempool,
+        EventTopic::Validation,
+    ];
+
+    /// Parser une liste de sujets séparés par des virgules (ex: "blocks,reorgs")
+    ///
+    /// # Returns
+    /// La liste des sujets, ou le premier nom inconnu en erreur
+    pub fn parse_list(list: &str) -> Result<Vec<EventTopic>, String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(EventTopic::from_str)
+            .collect()
+    }
+}
+
+impl FromStr for EventTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocks" => Ok(EventTopic::Blocks),
+            "reorgs" => Ok(EventTopic::Reorgs),
+            "mempool" => Ok(EventTopic::Mempool),
+            "validation" => Ok(EventTopic::Validation),
+            other => Err(format!("Unknown topic '{}'", other)),
+        }
+    }
+}
+
+impl NodeEvent {
+    /// Échec de validation du bloc `block_index`, refusé pour `error`
+    pub fn block_rejected(block_index: u64, error: &impl std::fmt::Display) -> Self {
+        NodeEvent::ValidationFailed {
+            block_index: Some(block_index),
+            reason: error.to_string(),
+        }
+    }
+
+    /// Sujet auquel appartient l'événement
+    pub fn topic(&self) -> EventTopic {
+        match self {
+            NodeEvent::BlockAdded { .. } => EventTopic::Blocks,
+            NodeEvent::Reorg { .. } => EventTopic::Reorgs,
+            NodeEvent::TxAccepted { .. } | NodeEvent::TxDropped { .. } => EventTopic::Mempool,
+            NodeEvent::ValidationFailed { .. } => EventTopic::Validation,
+        }
+    }
+
+    /// Nom de l'événement (utilisé comme champ `event` en SSE)
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeEvent::BlockAdded { .. } => "block_added",
+            NodeEvent::Reorg { .. } => "reorg",
+            NodeEvent::TxAccepted { .. } => "tx_accepted",
+            NodeEvent::TxDropped { .. } => "tx_dropped",
+            NodeEvent::ValidationFailed { .. } => "validation_failed",
+        }
+    }
+}
+
+/// Bus d'événements interne du nœud (diffusion vers tous les abonnés)
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<NodeEvent>,
+}
+
+impl EventBus {
+    /// Créer un bus pouvant conserver `capacity` événements non lus par abonné
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publier un événement
+    ///
+    /// # Returns
+    /// Nombre d'abonnés ayant reçu l'événement (0 si personne n'écoute)
+    pub fn publish(&self, event: NodeEvent) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// S'abonner aux événements publiés à partir de maintenant
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Nombre d'abonnés actuellement connectés
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_and_subscribe() {
+        let bus = EventBus::default();
+
+        // Sans abonné, la publication ne doit pas échouer
+        assert_eq!(bus.publish(NodeEvent::TxAccepted { txid: "a".to_string() }), 0);
+
+        let mut rx = bus.subscribe();
+        let event = NodeEvent::BlockAdded { block: Block::genesis() };
+        assert_eq!(bus.publish(event.clone()), 1);
+        assert_eq!(rx.recv().await.unwrap(), event);
+    }
+
+    #[test]
+    fn test_topic_parsing() {
+        let topics = EventTopic::parse_list("blocks, reorgs").unwrap();
+        assert_eq!(topics, vec![EventTopic::Blocks, EventTopic::Reorgs]);
+        assert!(EventTopic::parse_list("blocks,unknown").is_err());
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = NodeEvent::ValidationFailed {
+            block_index: Some(3),
+            reason: "Invalid block hash".to_string(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "validation_failed");
+        assert_eq!(json["block_index"], 3);
+        assert_eq!(event.topic(), EventTopic::Validation);
+    }
+}
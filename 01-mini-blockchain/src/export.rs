@@ -0,0 +1,383 @@
+use crate::block::Block;
+use crate::blockchain::{Blockchain, BlockchainError};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+/// En-tête du format binaire (version incluse)
+pub const BINARY_MAGIC: &[u8; 4] = b"MBC1";
+
+/// Taille maximale d'un enregistrement binaire (protège contre un préfixe corrompu)
+const MAX_RECORD_BYTES: u32 = 16 * 1024 * 1024;
+
+/// Nombre de blocs dont les hashes sont vérifiés ensemble, en parallèle
+const IMPORT_BATCH: usize = 1024;
+
+/// Formats d'export : un bloc par enregistrement
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainFormat {
+    /// JSON Lines : un bloc JSON par ligne
+    #[default]
+    Jsonl,
+    /// En-tête `MBC1` puis, pour chaque bloc, sa taille (u32 big-endian)
+    /// suivie du bloc encodé en bincode
+    Binary,
+}
+
+impl ChainFormat {
+    /// Type MIME utilisé par l'API
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ChainFormat::Jsonl => "application/x-ndjson",
+            ChainFormat::Binary => "application/octet-stream",
+        }
+    }
+}
+
+impl fmt::Display for ChainFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainFormat::Jsonl => f.write_str("jsonl"),
+            ChainFormat::Binary => f.write_str("binary"),
+        }
+    }
+}
+
+impl FromStr for ChainFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jsonl" => Ok(ChainFormat::Jsonl),
+            "binary" => Ok(ChainFormat::Binary),
+            other => Err(format!(
+                "Unknown format '{}', expected jsonl or binary",
+                other
+            )),
+        }
+    }
+}
+
+/// Erreurs possibles lors d'un export ou d'un import
+#[derive(Error, Debug)]
+pub enum TransferError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("Cannot decode record {record}: {reason}")]
+    Decode { record: usize, reason: String },
+
+    #[error("Record {record} (block {index}) rejected: {error}")]
+    Invalid {
+        record: usize,
+        index: u64,
+        error: BlockchainError,
+    },
+
+    #[error("Record {record} conflicts with block {index} already in the chain")]
+    Conflict { record: usize, index: u64 },
+}
+
+/// Résultat d'un import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Blocs ajoutés à la chaîne
+    pub imported: usize,
+    /// Blocs déjà présents (import repris après une interruption)
+    pub skipped: usize,
+}
+
+impl Blockchain {
+    /// Exporter la chaîne, un bloc par enregistrement
+    ///
+    /// # Arguments
+    /// * `writer` - Destination de l'export
+    /// * `format` - Format des enregistrements
+    pub fn export<W: Write>(&self, writer: W, format: ChainFormat) -> Result<(), TransferError> {
+        export_blocks(&self.chain, writer, format)
+    }
+
+    /// Importer des blocs à la suite de la chaîne, en validant chacun
+    ///
+    /// Les blocs déjà présents (même index et même hash) sont ignorés, ce qui
+    /// permet de reprendre un import interrompu avec le même fichier. En cas
+    /// d'erreur, les blocs importés avant l'enregistrement fautif sont conservés.
+    ///
+    /// Si la chaîne ne contient que son bloc genesis, le genesis de l'import le
+    /// remplace (initialisation d'un nouvel environnement).
+    ///
+    /// Les blocs sont lus par lots de `IMPORT_BATCH` dont les hashes et le
+    /// Proof of Work sont vérifiés en parallèle ; les liens restent vérifiés
+    /// dans l'ordre, bloc par bloc.
+    pub fn import<R: Read>(
+        &mut self,
+        reader: R,
+        format: ChainFormat,
+    ) -> Result<ImportReport, TransferError> {
+        let mut report = ImportReport::default();
+        let mut batch = Vec::with_capacity(IMPORT_BATCH);
+        let result = read_blocks(reader, format, |record, block| {
+            batch.push((record, block));
+            if batch.len() < IMPORT_BATCH {
+                return Ok(());
+            }
+            self.import_batch(std::mem::take(&mut batch), &mut report)
+        });
+        // Les blocs lus avant une erreur de lecture sont importés : la
+        // première erreur rencontrée dans le fichier l'emporte
+        let flushed = self.import_batch(batch, &mut report);
+        if report.imported > 0 {
+            self.record_metrics();
+        }
+        flushed.and(result).map(|_| report)
+    }
+
+    fn import_batch(
+        &mut self,
+        batch: Vec<(usize, Block)>,
+        report: &mut ImportReport,
+    ) -> Result<(), TransferError> {
+        let verified: Vec<bool> = batch
+            .par_iter()
+            .map(|(_, block)| self.verify_block(block))
+            .collect();
+        for ((record, block), verified) in batch.into_iter().zip(verified) {
+            self.import_block(record, block, verified, report)?;
+        }
+        Ok(())
+    }
+
+    fn import_block(
+        &mut self,
+        record: usize,
+        block: Block,
+        verified: bool,
+        report: &mut ImportReport,
+    ) -> Result<(), TransferError> {
+        let index = block.index;
+
+        match self.get_block(index) {
+            // Bloc déjà présent : import repris
+            Some(existing) if existing.hash == block.hash => {
+                report.skipped += 1;
+                Ok(())
+            }
+            // Genesis différent sur une chaîne vierge : l'adopter
+            Some(_) if index == 0 && self.len() == 1 => {
+                self.adopt_genesis(block)
+                    .map_err(|error| TransferError::Invalid {
+                        record,
+                        index,
+                        error,
+                    })?;
+                report.imported += 1;
+                Ok(())
+            }
+            Some(_) => Err(TransferError::Conflict { record, index }),
+            None => {
+                self.append_verified(block, verified)
+                    .map_err(|error| TransferError::Invalid {
+                        record,
+                        index,
+                        error,
+                    })?;
+                report.imported += 1;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Écrire une suite de blocs dans le format demandé
+pub fn export_blocks<'a, W: Write>(
+    blocks: impl IntoIterator<Item = &'a Block>,
+    writer: W,
+    format: ChainFormat,
+) -> Result<(), TransferError> {
+    let mut writer = io::BufWriter::new(writer);
+
+    match format {
+        ChainFormat::Jsonl => {
+            for block in blocks {
+                serde_json::to_writer(&mut writer, block).map_err(io::Error::from)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        ChainFormat::Binary => {
+            writer.write_all(BINARY_MAGIC)?;
+            for block in blocks {
+                let bytes = bincode::serialize(block)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                writer.write_all(&(bytes.len() as u32).to_be_bytes())?;
+                writer.write_all(&bytes)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Lire les blocs un par un et les passer à `on_block` (arrêt à la première erreur)
+fn read_blocks<R: Read>(
+    reader: R,
+    format: ChainFormat,
+    mut on_block: impl FnMut(usize, Block) -> Result<(), TransferError>,
+) -> Result<(), TransferError> {
+    let mut reader = BufReader::new(reader);
+
+    match format {
+        ChainFormat::Jsonl => {
+            for (record, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let block = serde_json::from_str(&line).map_err(|e| TransferError::Decode {
+                    record,
+                    reason: e.to_string(),
+                })?;
+                on_block(record, block)?;
+            }
+        }
+        ChainFormat::Binary => {
+            let mut magic = [0u8; 4];
+            reader.read_exact(&mut magic)?;
+            if &magic != BINARY_MAGIC {
+                return Err(TransferError::Decode {
+                    record: 0,
+                    reason: "missing MBC1 header".to_string(),
+                });
+            }
+
+            let mut record = 0;
+            while !reader.fill_buf()?.is_empty() {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                let len = u32::from_be_bytes(len);
+                if len > MAX_RECORD_BYTES {
+                    return Err(TransferError::Decode {
+                        record,
+                        reason: format!("record of {} bytes exceeds the limit", len),
+                    });
+                }
+
+                let mut bytes = vec![0u8; len as usize];
+                reader.read_exact(&mut bytes)?;
+                let block = bincode::deserialize(&bytes).map_err(|e| TransferError::Decode {
+                    record,
+                    reason: e.to_string(),
+                })?;
+                on_block(record, block)?;
+                record += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_chain() -> Blockchain {
+        let mut blockchain = Blockchain::new(1);
+        for i in 1..=3 {
+            blockchain.add_block(format!("Block {}", i)).unwrap();
+        }
+        blockchain
+    }
+
+    #[test]
+    fn test_roundtrip_both_formats() {
+        let source = sample_chain();
+
+        for format in [ChainFormat::Jsonl, ChainFormat::Binary] {
+            let mut buffer = Vec::new();
+            source.export(&mut buffer, format).unwrap();
+
+            // Un nœud neuf adopte le genesis exporté puis tous les blocs
+            let mut target = Blockchain::new(1);
+            let report = target.import(buffer.as_slice(), format).unwrap();
+
+            assert_eq!(report.imported + report.skipped, 4);
+            assert_eq!(target.chain, source.chain);
+        }
+    }
+
+    #[test]
+    fn test_resume_partial_import() {
+        let source = sample_chain();
+        let mut buffer = Vec::new();
+        source.export(&mut buffer, ChainFormat::Jsonl).unwrap();
+
+        // Import interrompu après deux lignes
+        let partial: Vec<&[u8]> = buffer.split_inclusive(|b| *b == b'\n').collect();
+        let mut target = Blockchain::new(1);
+        target
+            .import(partial[..2].concat().as_slice(), ChainFormat::Jsonl)
+            .unwrap();
+        assert_eq!(target.len(), 2);
+
+        // Reprise avec le fichier complet
+        let report = target
+            .import(buffer.as_slice(), ChainFormat::Jsonl)
+            .unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                imported: 2,
+                skipped: 2
+            }
+        );
+        assert_eq!(target.chain, source.chain);
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_block() {
+        let mut source = sample_chain();
+        source.chain[2].data = "Tampered".to_string();
+        let mut buffer = Vec::new();
+        source.export(&mut buffer, ChainFormat::Binary).unwrap();
+
+        let mut target = Blockchain::new(1);
+        let error = target
+            .import(buffer.as_slice(), ChainFormat::Binary)
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            TransferError::Invalid {
+                record: 2,
+                index: 2,
+                error: BlockchainError::InvalidHash
+            }
+        ));
+        // Les blocs valides avant l'erreur sont conservés
+        assert_eq!(target.len(), 2);
+        assert!(target.is_valid());
+    }
+
+    #[test]
+    fn test_import_conflict_and_bad_header() {
+        let mut target = sample_chain();
+        let other = sample_chain();
+        let mut buffer = Vec::new();
+        other.export(&mut buffer, ChainFormat::Jsonl).unwrap();
+
+        assert!(matches!(
+            target.import(buffer.as_slice(), ChainFormat::Jsonl),
+            Err(TransferError::Conflict { record: 0, .. })
+        ));
+        assert!(matches!(
+            target.import(&b"XXXX"[..], ChainFormat::Binary),
+            Err(TransferError::Decode { .. })
+        ));
+    }
+}
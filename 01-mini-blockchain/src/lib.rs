@@ -1,8 +1,28 @@
 pub mod api;
+pub mod bitcoin;
 pub mod block;
+pub mod block_store;
 pub mod blockchain;
+pub mod chain_service;
+pub mod checkpoints;
+pub mod config;
+pub mod consensus;
+pub mod events;
+pub mod export;
+pub mod light_client;
+pub mod merkle;
+pub mod metrics;
+pub mod orphans;
+pub mod peers;
 pub mod proof_of_work;
+pub mod shutdown;
+pub mod storage;
+pub mod sync;
+pub mod wallet;
 
 // Réexporter les types principaux pour faciliter l'utilisation
 pub use block::Block;
-pub use blockchain::{Blockchain, BlockchainError, SharedBlockchain};
+pub use block_store::BlockStore;
+pub use blockchain::{BlockFault, Blockchain, BlockchainError, ValidationMode, ValidationReport};
+pub use chain_service::{ChainHandle, ServiceError};
+pub use events::{EventBus, NodeEvent};
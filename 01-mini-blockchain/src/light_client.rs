@@ -0,0 +1,237 @@
+//! Client léger (Simplified Payment Verification)
+//!
+//! Le client ne garde que les en-têtes de 80 octets des blocs (chaîne
+//! `sha256d`) : il vérifie leurs liens et leur Proof of Work, puis l'inclusion
+//! d'une transaction grâce à une preuve de Merkle fournie par un nœud complet,
+//! sans jamais télécharger le contenu des blocs.
+
+use crate::bitcoin::{self, BlockHeader};
+use crate::merkle::{self, MerkleProof};
+use crate::proof_of_work::verify_proof_of_work;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Erreurs de vérification du client léger
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LightClientError {
+    #[error("Expected header {expected}, got header {height}")]
+    UnexpectedHeight { height: u64, expected: u64 },
+
+    #[error("Header {0} does not link to the previous header")]
+    BrokenLink(u64),
+
+    #[error("Header {0} does not meet the chain difficulty")]
+    InsufficientWork(u64),
+
+    #[error("No header at height {0}")]
+    UnknownHeight(u64),
+
+    #[error("Transaction is not included in block {0}")]
+    NotIncluded(u64),
+}
+
+/// Chaîne d'en-têtes vérifiée, ancrée sur un genesis de confiance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightClient {
+    /// Hash du bloc genesis, auquel le premier en-tête doit se rattacher
+    genesis_hash: String,
+    /// Difficulté de la chaîne (zéros hexadécimaux en tête du hash)
+    difficulty: usize,
+    /// En-têtes des blocs 1, 2, ... (le genesis n'a pas d'en-tête Bitcoin)
+    headers: Vec<BlockHeader>,
+}
+
+impl LightClient {
+    /// Créer un client sans en-tête
+    ///
+    /// # Arguments
+    /// * `genesis_hash` - Hash du genesis de la chaîne suivie
+    /// * `difficulty` - Difficulté exigée de chaque en-tête
+    pub fn new(genesis_hash: String, difficulty: usize) -> Self {
+        Self {
+            genesis_hash,
+            difficulty,
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn genesis_hash(&self) -> &str {
+        &self.genesis_hash
+    }
+
+    pub fn difficulty(&self) -> usize {
+        self.difficulty
+    }
+
+    /// Hauteur du dernier en-tête vérifié (0 : seul le genesis est connu)
+    pub fn height(&self) -> u64 {
+        self.headers.len() as u64
+    }
+
+    /// Hash du dernier bloc connu
+    pub fn tip_hash(&self) -> String {
+        self.headers
+            .last()
+            .map(BlockHeader::block_hash)
+            .unwrap_or_else(|| self.genesis_hash.clone())
+    }
+
+    /// En-tête du bloc `height`
+    pub fn header(&self, height: u64) -> Option<&BlockHeader> {
+        let position = usize::try_from(height.checked_sub(1)?).ok()?;
+        self.headers.get(position)
+    }
+
+    /// Vérifier puis ajouter les en-têtes suivants
+    ///
+    /// Chaque en-tête doit pointer sur le hash du précédent et respecter la
+    /// difficulté. Rien n'est ajouté si l'un d'eux est refusé.
+    ///
+    /// # Arguments
+    /// * `from` - Hauteur du premier en-tête (`height() + 1`)
+    /// * `headers` - En-têtes consécutifs
+    ///
+    /// # Returns
+    /// La nouvelle hauteur
+    pub fn extend(&mut self, from: u64, headers: &[BlockHeader]) -> Result<u64, LightClientError> {
+        let expected = self.height() + 1;
+        if !headers.is_empty() && from != expected {
+            return Err(LightClientError::UnexpectedHeight {
+                height: from,
+                expected,
+            });
+        }
+
+        let mut tip = self.tip_hash();
+        for (height, header) in (from..).zip(headers) {
+            if bitcoin::to_display_hex(&header.prev_blockhash) != tip {
+                return Err(LightClientError::BrokenLink(height));
+            }
+            tip = header.block_hash();
+            if !verify_proof_of_work(&tip, self.difficulty) {
+                return Err(LightClientError::InsufficientWork(height));
+            }
+        }
+
+        self.headers.extend_from_slice(headers);
+        Ok(self.height())
+    }
+
+    /// Vérifier qu'une transaction est incluse dans le bloc `height`
+    ///
+    /// # Arguments
+    /// * `height` - Bloc dont l'en-tête a déjà été vérifié
+    /// * `transaction` - Contenu exact de la transaction (une ligne de données)
+    /// * `proof` - Preuve de Merkle fournie par un nœud complet
+    pub fn verify_transaction(
+        &self,
+        height: u64,
+        transaction: &str,
+        proof: &MerkleProof,
+    ) -> Result<(), LightClientError> {
+        let header = self
+            .header(height)
+            .ok_or(LightClientError::UnknownHeight(height))?;
+
+        // La feuille 0 engage la hauteur du bloc, ce n'est pas une transaction
+        if proof.leaf_index == 0 || proof.root(merkle::txid(transaction)) != header.merkle_root {
+            return Err(LightClientError::NotIncluded(height));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::Blockchain;
+    use crate::consensus::ProofOfWork;
+    use crate::proof_of_work::PowAlgorithm;
+    use std::sync::Arc;
+
+    fn bitcoin_chain(blocks: &[&str]) -> Blockchain {
+        let engine = Arc::new(ProofOfWork::new(PowAlgorithm::Sha256d));
+        let mut chain = Blockchain::new(1).with_consensus(engine);
+        for data in blocks {
+            chain.add_block(data.to_string()).unwrap();
+        }
+        chain
+    }
+
+    fn headers(chain: &Blockchain) -> Vec<BlockHeader> {
+        chain
+            .chain
+            .iter()
+            .skip(1)
+            .map(|b| b.bitcoin_header())
+            .collect()
+    }
+
+    #[test]
+    fn test_sync_headers_and_verify_payment() {
+        let chain = bitcoin_chain(&["Coffee", "Alice pays Bob 5\nBob pays Carol 2", "Rent"]);
+        let headers = headers(&chain);
+
+        let mut client = LightClient::new(chain.chain[0].hash.clone(), 1);
+        assert_eq!(client.extend(1, &headers[..1]).unwrap(), 1);
+        assert_eq!(client.extend(2, &headers[1..]).unwrap(), 3);
+        assert_eq!(client.tip_hash(), chain.latest_block().hash);
+
+        let proof = chain.chain[2].transaction_proof(1).unwrap();
+        assert_eq!(
+            client.verify_transaction(2, "Bob pays Carol 2", &proof),
+            Ok(())
+        );
+        assert_eq!(
+            client.verify_transaction(2, "Bob pays Carol 200", &proof),
+            Err(LightClientError::NotIncluded(2))
+        );
+        assert_eq!(
+            client.verify_transaction(3, "Bob pays Carol 2", &proof),
+            Err(LightClientError::NotIncluded(3))
+        );
+        assert_eq!(
+            client.verify_transaction(4, "Rent", &proof),
+            Err(LightClientError::UnknownHeight(4))
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_header_chains() {
+        let chain = bitcoin_chain(&["Block 1", "Block 2"]);
+        let headers = headers(&chain);
+        let genesis = chain.chain[0].hash.clone();
+
+        // En-têtes dans le désordre ou qui ne suivent pas la hauteur connue
+        let mut client = LightClient::new(genesis.clone(), 1);
+        assert_eq!(
+            client.extend(1, &[headers[1]]),
+            Err(LightClientError::BrokenLink(1))
+        );
+        assert_eq!(
+            client.extend(2, &headers),
+            Err(LightClientError::UnexpectedHeight {
+                height: 2,
+                expected: 1
+            })
+        );
+
+        // En-tête modifié : son hash ne respecte plus la difficulté (ou casse le lien)
+        let mut forged = headers.clone();
+        forged[0].time += 1;
+        assert!(client.extend(1, &forged).is_err());
+        assert_eq!(client.height(), 0);
+
+        // Difficulté plus forte que celle de la chaîne
+        let mut strict = LightClient::new(genesis, 64);
+        assert_eq!(
+            strict.extend(1, &headers),
+            Err(LightClientError::InsufficientWork(1))
+        );
+
+        // Le client se sauvegarde et se recharge tel quel
+        client.extend(1, &headers).unwrap();
+        let json = serde_json::to_string(&client).unwrap();
+        assert_eq!(serde_json::from_str::<LightClient>(&json).unwrap(), client);
+    }
+}
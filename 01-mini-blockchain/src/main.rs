@@ -1,44 +1,176 @@
-use axum::{
-    routing::{get, post},
-    Router,
-};
-use mini_blockchain::{api, Blockchain};
-use std::sync::{Arc, RwLock};
-use tower_http::cors::CorsLayer;
+use mini_blockchain::api::{self, auth::ApiSecurity};
+use mini_blockchain::config::NodeConfig;
+use mini_blockchain::consensus::ConsensusKind;
+use mini_blockchain::orphans::OrphanPool;
+use mini_blockchain::peers::PeerManager;
+use mini_blockchain::shutdown::wait_for_signal;
+use mini_blockchain::storage::{self, DataDir};
+use mini_blockchain::sync::HeadersFirstSync;
+use mini_blockchain::{Blockchain, ValidationMode};
 
 #[tokio::main]
 async fn main() {
     // 1. Initialiser le système de logging
     tracing_subscriber::fmt::init();
-    //
-    // 2. Créer une blockchain avec difficulté 2 (rapide pour développement)
+
+    // 2. Charger la configuration depuis l'environnement
+    let config = NodeConfig::from_env().unwrap_or_else(|e| {
+        eprintln!("❌ Invalid configuration: {}", e);
+        std::process::exit(1);
+    });
+
+    // 3. Créer la politique d'accès (clés d'API, scopes, limites de débit)
+    let security = ApiSecurity::from_config(&config);
+    if security.allows_anonymous_writes() {
+        tracing::warn!(
+            "BLOCKCHAIN_ALLOW_ANONYMOUS_WRITES is set: anyone can mine, import or manage peers without an API key"
+        );
+    } else if !security.is_enabled() {
+        tracing::warn!(
+            "No API keys configured (BLOCKCHAIN_API_KEYS): writes are rejected, set BLOCKCHAIN_ALLOW_ANONYMOUS_WRITES=true to accept them without a key"
+        );
+    }
+
+    // 4. Créer une blockchain avec la difficulté configurée (2 par défaut, rapide pour développement)
     // Difficulté 1-2 = < 1 seconde | Difficulté 3 = quelques secondes | Difficulté 4+ = très long
-    let blockchain = Arc::new(RwLock::new(Blockchain::new(2)));
-    //
-    // 3. Créer le routeur Axum avec toutes les routes :
-       let app = Router::new()
-           .route("/", get(root))
-           .route("/chain", get(api::get_chain))
-           .route("/blocks", post(api::add_block))
-           .route("/blocks/:index", get(api::get_block))
-           .route("/validate", get(api::validate_chain))
-           .with_state(blockchain)
-           .layer(CorsLayer::permissive());
-    
-    // 4. Créer le listener TCP sur 127.0.0.1:8080
-       let listener = tokio::net::TcpListener::bind("localhost:8090")
-           .await
-           .unwrap();
-    
-    // 5. Afficher un message de démarrage
-    println!("🚀 Blockchain API server running on http://localhost:8090");
-    //
-    // 6. Démarrer le serveur
-    axum::serve(listener, app).await.unwrap();
-}
+    // Si un répertoire de données est configuré, reprendre la chaîne sauvegardée
+    let data_dir = config.data_dir.clone().map(DataDir::new);
+    let blockchain = match &data_dir {
+        Some(data_dir) => data_dir
+            .load_or_create(config.difficulty)
+            .unwrap_or_else(|e| {
+                eprintln!("❌ Cannot open data directory: {}", e);
+                std::process::exit(1);
+            }),
+        None => Blockchain::new(config.difficulty),
+    };
+    let consensus = config.consensus_engine().unwrap_or_else(|e| {
+        eprintln!("❌ Invalid configuration: {}", e);
+        std::process::exit(1);
+    });
+    tracing::info!("Consensus engine: {}", consensus.name());
+    if config.consensus == ConsensusKind::Pow {
+        tracing::info!("Proof-of-work algorithm: {}", config.pow_algorithm);
+    }
+    if let Some(key) = &config.validator_key {
+        tracing::info!("Signing blocks as validator {}", key.public_key_hex());
+    }
+    let blockchain = blockchain
+        .with_checkpoints(config.checkpoints.clone())
+        .with_consensus(consensus);
+    if !config.checkpoints.is_empty() {
+        tracing::info!("{} checkpoints configured", config.checkpoints.len());
+    }
+
+    // Refuser de démarrer sur une chaîne sauvegardée invalide plutôt que de
+    // la tronquer sans prévenir : l'opérateur choisit la hauteur à garder
+    let (blockchain, report) = tokio::task::spawn_blocking(move || {
+        let report = blockchain.validate_parallel(ValidationMode::Incremental);
+        (blockchain, report)
+    })
+    .await
+    .expect("chain validation panicked");
+    if let Some(fault) = report.faults.first() {
+        eprintln!("❌ Saved chain is invalid: {}", fault);
+        eprintln!(
+            "   Repair it with `bc-tool -d <data dir> truncate --to {}`",
+            fault.position.saturating_sub(1)
+        );
+        std::process::exit(1);
+    }
+    blockchain.record_metrics();
+    let peers = PeerManager::new(config.peer_config());
+    if let Some(url) = &config.public_url {
+        tracing::info!("Announcing {} to peers", url);
+    }
+    let state = api::AppState::new(blockchain)
+        .with_security(security)
+        .with_peers(peers.clone())
+        .with_orphans(OrphanPool::new(config.max_orphans, config.orphan_ttl));
+
+    // Ajouter les blocs orphelins dès que leur parent rejoint la chaîne
+    tokio::spawn(
+        state
+            .orphans
+            .clone()
+            .run(state.chain.clone(), state.shutdown.clone()),
+    );
+
+    // Sauvegarder la chaîne à chaque nouveau bloc
+    let persistence = data_dir.clone().map(|data_dir| {
+        tracing::info!("Persisting chain to {}", data_dir.root().display());
+        tokio::spawn(storage::persist_on_events(
+            data_dir,
+            state.chain.clone(),
+            state.events.subscribe(),
+            state.shutdown.clone(),
+        ))
+    });
+
+    // Se connecter aux pairs du carnet d'adresses, rattraper les pairs
+    // connectés (en-têtes d'abord) pendant que l'API répond, puis maintenir
+    // les connexions
+    {
+        let chain = state.chain.clone();
+        let tracker = state.sync.clone();
+        let shutdown = state.shutdown.clone();
+        tokio::spawn(async move {
+            let catch_up = async {
+                peers.maintain().await;
+                let urls = peers.outbound_urls();
+                if !urls.is_empty() {
+                    let sync = HeadersFirstSync::new(chain, &urls, tracker)
+                        .with_peer_manager(peers.clone());
+                    let _ = sync.run().await;
+                }
+            };
+            tokio::select! {
+                _ = catch_up => {}
+                _ = shutdown.wait() => return,
+            }
+            peers.run(shutdown).await;
+        });
+    }
+
+    // 5. Déclencher l'arrêt sur SIGINT (Ctrl-C) ou SIGTERM
+    let shutdown = state.shutdown.clone();
+    tokio::spawn(async move {
+        let signal = wait_for_signal().await;
+        tracing::info!("{} received, shutting down", signal);
+        shutdown.trigger();
+    });
+
+    // 6. Créer le listener TCP sur l'adresse configurée
+    let listener = tokio::net::TcpListener::bind(&config.listen_addr)
+        .await
+        .unwrap();
+
+    // 7. Afficher un message de démarrage
+    println!(
+        "🚀 Blockchain API server running on http://{}",
+        config.listen_addr
+    );
+
+    // 8. Servir l'API jusqu'à l'arrêt (l'adresse du client sert à la limitation par IP)
+    if let Err(e) = api::serve(listener, state.clone(), &config).await {
+        tracing::error!("Server error: {}", e);
+    }
+
+    // 9. Écrire l'état final une fois la tâche de persistance terminée
+    if let (Some(data_dir), Some(persistence)) = (data_dir, persistence) {
+        let _ = persistence.await;
+        let blockchain = state.chain.snapshot();
+        match data_dir.save(&blockchain).map(|_| blockchain.len()) {
+            Ok(blocks) => {
+                tracing::info!("Saved {} blocks to {}", blocks, data_dir.root().display())
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to save chain: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
-/// Handler pour GET /
-/// Retourne un message de bienvenue
-async fn root() -> &'static str {
-    "Mini Blockchain API - See /chain for the blockchain"
+    state.peers.save();
+    println!("👋 Node stopped cleanly");
 }
@@ -0,0 +1,125 @@
+use crate::bitcoin::sha256d;
+
+/// Identifiant d'une transaction : double SHA-256 de son contenu
+pub fn txid(transaction: &str) -> [u8; 32] {
+    sha256d(transaction.as_bytes())
+}
+
+/// Racine de Merkle façon Bitcoin
+///
+/// Les nœuds sont `sha256d(gauche ‖ droite)` ; à chaque niveau de taille
+/// impaire, le dernier nœud est apparié avec lui-même. Une seule feuille est
+/// sa propre racine, aucune donne 32 octets nuls.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    if level.is_empty() {
+        return [0u8; 32];
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+    }
+    level[0]
+}
+
+/// Preuve d'inclusion d'une feuille : ses voisins du bas vers le haut
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// Position de la feuille : ses bits donnent le côté à chaque niveau
+    pub leaf_index: usize,
+    /// Nœud voisin à chaque niveau, dans l'ordre interne
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleProof {
+    /// Construire la preuve de la feuille `leaf_index`
+    ///
+    /// # Returns
+    /// None si la feuille n'existe pas
+    pub fn build(leaves: &[[u8; 32]], leaf_index: usize) -> Option<Self> {
+        if leaf_index >= leaves.len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        let mut level = leaves.to_vec();
+        let mut position = leaf_index;
+        while level.len() > 1 {
+            let sibling = position ^ 1;
+            siblings.push(*level.get(sibling).unwrap_or(&level[position]));
+            level = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            position /= 2;
+        }
+        Some(Self {
+            leaf_index,
+            siblings,
+        })
+    }
+
+    /// Racine obtenue en remontant de `leaf` avec les voisins de la preuve
+    pub fn root(&self, leaf: [u8; 32]) -> [u8; 32] {
+        let mut position = self.leaf_index;
+        let mut node = leaf;
+        for sibling in &self.siblings {
+            node = if position.is_multiple_of(2) {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            };
+            position /= 2;
+        }
+        node
+    }
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut pair = [0u8; 64];
+    pair[..32].copy_from_slice(left);
+    pair[32..].copy_from_slice(right);
+    sha256d(&pair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{from_display_hex, to_display_hex};
+
+    #[test]
+    fn test_bitcoin_block_170_merkle_root() {
+        // Bloc 170 : la coinbase puis la première transaction entre personnes
+        let leaves = [
+            "b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082",
+            "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16",
+        ]
+        .map(|txid| from_display_hex(txid).unwrap());
+
+        let root = merkle_root(&leaves);
+        assert_eq!(
+            to_display_hex(&root),
+            "7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff"
+        );
+        let proof = MerkleProof::build(&leaves, 1).unwrap();
+        assert_eq!(proof.siblings, vec![leaves[0]]);
+        assert_eq!(proof.root(leaves[1]), root);
+    }
+
+    #[test]
+    fn test_proofs_for_every_leaf() {
+        for count in 1..=7 {
+            let leaves: Vec<[u8; 32]> = (0..count).map(|i| txid(&format!("tx {}", i))).collect();
+            let root = merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = MerkleProof::build(&leaves, index).unwrap();
+                assert_eq!(proof.root(*leaf), root, "{} leaves, leaf {}", count, index);
+                assert_ne!(proof.root(txid("forged")), root);
+            }
+            assert!(MerkleProof::build(&leaves, count).is_none());
+        }
+        assert_eq!(merkle_root(&[]), [0u8; 32]);
+    }
+}
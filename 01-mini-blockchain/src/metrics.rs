@@ -0,0 +1,166 @@
+use prometheus::{
+    Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntGauge, Registry,
+    TextEncoder,
+};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Nombre de blocs utilisés pour calculer l'intervalle moyen entre blocs
+pub const BLOCK_INTERVAL_WINDOW: usize = 10;
+
+/// Métriques Prometheus du nœud
+pub struct Metrics {
+    registry: Registry,
+    pub chain_height: IntGauge,
+    pub difficulty: IntGauge,
+    pub mining_hashrate: Gauge,
+    pub mining_hashes_total: IntCounter,
+    pub mining_duration_seconds: Histogram,
+    pub blocks_mined_total: IntCounter,
+    pub block_interval_seconds: Gauge,
+    pub mempool_size: IntGauge,
+    pub peer_count: IntGauge,
+    pub orphan_count: IntGauge,
+    pub orphans_connected_total: IntCounter,
+    pub orphans_evicted_total: IntCounter,
+    pub validation_failures_total: IntCounter,
+    pub http_request_duration_seconds: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("blockchain".to_string()), None)
+            .expect("valid metrics prefix");
+
+        let metrics = Self {
+            chain_height: IntGauge::new("chain_height", "Height of the chain tip").unwrap(),
+            difficulty: IntGauge::new("difficulty", "Current mining difficulty").unwrap(),
+            mining_hashrate: Gauge::new(
+                "mining_hashrate",
+                "Hashes per second measured on the last mined block",
+            )
+            .unwrap(),
+            mining_hashes_total: IntCounter::new(
+                "mining_hashes_total",
+                "Total number of hashes computed while mining",
+            )
+            .unwrap(),
+            mining_duration_seconds: Histogram::with_opts(
+                HistogramOpts::new("mining_duration_seconds", "Time spent mining a block")
+                    .buckets(vec![0.001, 0.01, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0]),
+            )
+            .unwrap(),
+            blocks_mined_total: IntCounter::new("blocks_mined_total", "Number of blocks mined")
+                .unwrap(),
+            block_interval_seconds: Gauge::new(
+                "block_interval_seconds",
+                "Average interval between the last blocks",
+            )
+            .unwrap(),
+            mempool_size: IntGauge::new("mempool_size", "Transactions waiting in the mempool")
+                .unwrap(),
+            peer_count: IntGauge::new("peer_count", "Number of connected peers").unwrap(),
+            orphan_count: IntGauge::new("orphan_count", "Blocks waiting for their parent").unwrap(),
+            orphans_connected_total: IntCounter::new(
+                "orphans_connected_total",
+                "Orphan blocks appended once their parent arrived",
+            )
+            .unwrap(),
+            orphans_evicted_total: IntCounter::new(
+                "orphans_evicted_total",
+                "Orphan blocks dropped because the pool was full or they expired",
+            )
+            .unwrap(),
+            validation_failures_total: IntCounter::new(
+                "validation_failures_total",
+                "Number of failed chain validations",
+            )
+            .unwrap(),
+            http_request_duration_seconds: HistogramVec::new(
+                HistogramOpts::new("http_request_duration_seconds", "API request latency"),
+                &["method", "route", "status"],
+            )
+            .unwrap(),
+            registry,
+        };
+
+        metrics.register_all();
+        metrics
+    }
+
+    fn register_all(&self) {
+        let collectors: Vec<Box<dyn prometheus::core::Collector>> = vec![
+            Box::new(self.chain_height.clone()),
+            Box::new(self.difficulty.clone()),
+            Box::new(self.mining_hashrate.clone()),
+            Box::new(self.mining_hashes_total.clone()),
+            Box::new(self.mining_duration_seconds.clone()),
+            Box::new(self.blocks_mined_total.clone()),
+            Box::new(self.block_interval_seconds.clone()),
+            Box::new(self.mempool_size.clone()),
+            Box::new(self.peer_count.clone()),
+            Box::new(self.orphan_count.clone()),
+            Box::new(self.orphans_connected_total.clone()),
+            Box::new(self.orphans_evicted_total.clone()),
+            Box::new(self.validation_failures_total.clone()),
+            Box::new(self.http_request_duration_seconds.clone()),
+        ];
+        for collector in collectors {
+            self.registry
+                .register(collector)
+                .expect("metric registered once");
+        }
+    }
+
+    /// Enregistrer le résultat d'une session de mining
+    ///
+    /// # Arguments
+    /// * `hashes` - Nombre de hashes calculés
+    /// * `elapsed` - Durée du mining
+    pub fn record_mining(&self, hashes: u64, elapsed: Duration) {
+        self.mining_hashes_total.inc_by(hashes);
+        self.mining_duration_seconds.observe(elapsed.as_secs_f64());
+        self.blocks_mined_total.inc();
+
+        let seconds = elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.mining_hashrate.set(hashes as f64 / seconds);
+        }
+    }
+
+    /// Encoder toutes les métriques au format texte de Prometheus
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding cannot fail");
+        String::from_utf8(buffer).expect("Prometheus text format is UTF-8")
+    }
+}
+
+/// Accéder aux métriques globales du processus
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_mining_and_encode() {
+        let metrics = metrics();
+        let before = metrics.mining_hashes_total.get();
+
+        metrics.record_mining(1_000, Duration::from_millis(500));
+
+        assert!(metrics.mining_hashes_total.get() >= before + 1_000);
+        assert!(metrics.mining_hashrate.get() > 0.0);
+
+        let text = metrics.encode();
+        assert!(text.contains("blockchain_chain_height"));
+        assert!(text.contains("blockchain_mining_hashes_total"));
+        assert!(text.contains("blockchain_peer_count"));
+    }
+}
@@ -0,0 +1,641 @@
+//! Réserve de blocs orphelins
+//!
+//! Un bloc relayé par un pair (`POST /peers/blocks`) dont le parent est inconnu
+//! n'est pas refusé : s'il porte un hash et un sceau valides, il attend dans
+//! la réserve pendant que son parent est demandé aux pairs sortants (jamais à
+//! une URL fournie par le corps de la requête). Dès
+//! que le parent rejoint la chaîne (relais, mining ou synchronisation), les
+//! orphelins qui en descendent sont ajoutés à leur tour. La réserve est bornée
+//! en taille (le plus ancien est évincé) et en âge.
+
+use crate::block::Block;
+use crate::blockchain::{Blockchain, BlockchainError};
+use crate::chain_service::{ChainHandle, ServiceError};
+use crate::events::NodeEvent;
+use crate::metrics::metrics;
+use crate::peers::{PeerManager, INVALID_BLOCK_PENALTY};
+use crate::shutdown::Shutdown;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::ToSchema;
+
+/// Nombre maximal d'orphelins conservés
+pub const MAX_ORPHANS: usize = 100;
+
+/// Durée de conservation d'un orphelin
+pub const ORPHAN_TTL: Duration = Duration::from_secs(20 * 60);
+
+/// Orphelin en attente de son parent (GET /orphans)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OrphanInfo {
+    pub hash: String,
+    pub index: u64,
+    pub previous_hash: String,
+    pub received_at: DateTime<Utc>,
+    /// Pair qui a relayé le bloc
+    pub source: Option<String>,
+}
+
+/// Sort d'un bloc relayé (POST /peers/blocks)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum BlockStatus {
+    /// Ajouté au sommet, suivi de `orphans_connected` orphelins
+    Connected {
+        height: u64,
+        orphans_connected: usize,
+    },
+    /// Gardé dans la réserve ; `missing_parent` est le premier ancêtre manquant
+    Orphaned { missing_parent: String },
+    /// Déjà dans la chaîne ou dans la réserve
+    Duplicate,
+}
+
+struct Orphan {
+    block: Block,
+    received_at: DateTime<Utc>,
+    /// Ordre d'arrivée (départage les réceptions simultanées)
+    sequence: u64,
+    source: Option<String>,
+}
+
+/// Résultat d'un passage dans le service de chaîne
+struct Accepted {
+    status: BlockStatus,
+    /// Blocs ajoutés, dans l'ordre
+    added: Vec<Block>,
+    /// Orphelins refusés à la connexion (hauteur, pair qui les a relayés)
+    dropped: Vec<(u64, Option<String>, BlockchainError)>,
+}
+
+/// Réserve d'orphelins, clonable et partagée entre l'API et les tâches
+#[derive(Clone)]
+pub struct OrphanPool {
+    orphans: Arc<Mutex<HashMap<String, Orphan>>>,
+    received: Arc<AtomicU64>,
+    max_orphans: usize,
+    max_age: Duration,
+}
+
+impl Default for OrphanPool {
+    fn default() -> Self {
+        Self::new(MAX_ORPHANS, ORPHAN_TTL)
+    }
+}
+
+impl OrphanPool {
+    /// Créer une réserve vide
+    ///
+    /// # Arguments
+    /// * `max_orphans` - Taille maximale ; au-delà, le plus ancien est évincé
+    /// * `max_age` - Durée après laquelle un orphelin est abandonné
+    pub fn new(max_orphans: usize, max_age: Duration) -> Self {
+        Self {
+            orphans: Arc::new(Mutex::new(HashMap::new())),
+            received: Arc::new(AtomicU64::new(0)),
+            max_orphans,
+            max_age,
+        }
+    }
+
+    pub fn max_orphans(&self) -> usize {
+        self.max_orphans
+    }
+
+    /// Nombre d'orphelins (après éviction des orphelins expirés)
+    pub fn len(&self) -> usize {
+        let mut orphans = self.orphans.lock().unwrap();
+        self.expire(&mut orphans);
+        orphans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, hash: &str) -> bool {
+        self.orphans.lock().unwrap().contains_key(hash)
+    }
+
+    /// Orphelins actuels, par hauteur puis date de réception
+    pub fn list(&self) -> Vec<OrphanInfo> {
+        let mut orphans = self.orphans.lock().unwrap();
+        self.expire(&mut orphans);
+        let mut list: Vec<OrphanInfo> = orphans
+            .values()
+            .map(|orphan| OrphanInfo {
+                hash: orphan.block.hash.clone(),
+                index: orphan.block.index,
+                previous_hash: orphan.block.previous_hash.clone(),
+                received_at: orphan.received_at,
+                source: orphan.source.clone(),
+            })
+            .collect();
+        list.sort_by_key(|orphan| (orphan.index, orphan.received_at));
+        list
+    }
+
+    /// Premier ancêtre manquant de l'orphelin `hash`, en remontant les
+    /// orphelins qui le précèdent
+    ///
+    /// # Returns
+    /// None si `hash` n'est pas (ou plus) dans la réserve
+    pub fn missing_parent(&self, hash: &str) -> Option<String> {
+        let orphans = self.orphans.lock().unwrap();
+        let mut parent = &orphans.get(hash)?.block.previous_hash;
+        // La réserve est bornée : la remontée aussi, même en cas de cycle
+        for _ in 0..orphans.len() {
+            match orphans.get(parent) {
+                Some(orphan) => parent = &orphan.block.previous_hash,
+                None => break,
+            }
+        }
+        Some(parent.clone())
+    }
+
+    /// Ranger un bloc dont le parent est inconnu
+    ///
+    /// # Returns
+    /// false si le bloc est déjà dans la réserve
+    fn insert(&self, block: Block, source: Option<String>) -> bool {
+        let mut orphans = self.orphans.lock().unwrap();
+        self.expire(&mut orphans);
+        if orphans.contains_key(&block.hash) || self.max_orphans == 0 {
+            return false;
+        }
+        if orphans.len() >= self.max_orphans {
+            let oldest = orphans
+                .values()
+                .min_by_key(|orphan| orphan.sequence)
+                .map(|orphan| orphan.block.hash.clone());
+            if let Some(oldest) = oldest {
+                orphans.remove(&oldest);
+                metrics().orphans_evicted_total.inc();
+                tracing::debug!("Orphan pool full, evicted {}", oldest);
+            }
+        }
+        tracing::info!(
+            "Block {} ({}) is an orphan, parent {} unknown",
+            block.index,
+            block.hash,
+            block.previous_hash
+        );
+        orphans.insert(
+            block.hash.clone(),
+            Orphan {
+                block,
+                received_at: Utc::now(),
+                sequence: self.received.fetch_add(1, Ordering::Relaxed),
+                source,
+            },
+        );
+        metrics().orphan_count.set(orphans.len() as i64);
+        true
+    }
+
+    /// Retirer les orphelins dont le parent est `parent`, du plus ancien au
+    /// plus récent
+    fn take_children(&self, parent: &str) -> Vec<Orphan> {
+        let mut orphans = self.orphans.lock().unwrap();
+        let hashes: Vec<String> = orphans
+            .values()
+            .filter(|orphan| orphan.block.previous_hash == parent)
+            .map(|orphan| orphan.block.hash.clone())
+            .collect();
+        let mut children: Vec<Orphan> = hashes
+            .iter()
+            .filter_map(|hash| orphans.remove(hash))
+            .collect();
+        children.sort_by_key(|orphan| orphan.sequence);
+        metrics().orphan_count.set(orphans.len() as i64);
+        children
+    }
+
+    fn expire(&self, orphans: &mut HashMap<String, Orphan>) {
+        let max_age = chrono::Duration::from_std(self.max_age).unwrap_or(chrono::Duration::MAX);
+        let Some(deadline) = Utc::now().checked_sub_signed(max_age) else {
+            return;
+        };
+        let before = orphans.len();
+        orphans.retain(|_, orphan| orphan.received_at > deadline);
+        let expired = before - orphans.len();
+        if expired > 0 {
+            metrics().orphans_evicted_total.inc_by(expired as u64);
+            tracing::debug!("{} orphans expired", expired);
+        }
+        metrics().orphan_count.set(orphans.len() as i64);
+    }
+
+    /// Ajouter un bloc relayé à la chaîne, ou le garder comme orphelin
+    ///
+    /// Un orphelin doit tout de même avoir un hash et un sceau valides : la
+    /// réserve ne retient que des blocs qui ont coûté un travail. Une réserve
+    /// désactivée (`max_orphans == 0`) refuse l'orphelin avec
+    /// `InvalidPreviousHash`.
+    fn accept(
+        &self,
+        bc: &mut Blockchain,
+        block: Block,
+        source: Option<String>,
+    ) -> Result<Accepted, BlockchainError> {
+        let mut accepted = Accepted {
+            status: BlockStatus::Duplicate,
+            added: Vec::new(),
+            dropped: Vec::new(),
+        };
+        if bc.get_block_by_hash(&block.hash).is_some() || self.contains(&block.hash) {
+            return Ok(accepted);
+        }
+        if !bc.verify_block(&block) {
+            return Err(BlockchainError::InvalidHash);
+        }
+
+        if bc.get_block_by_hash(&block.previous_hash).is_none() {
+            let hash = block.hash.clone();
+            if !self.insert(block, source) {
+                return Err(BlockchainError::InvalidPreviousHash);
+            }
+            accepted.status = BlockStatus::Orphaned {
+                missing_parent: self.missing_parent(&hash).unwrap_or_default(),
+            };
+            return Ok(accepted);
+        }
+
+        let block = bc.append_block(block)?.clone();
+        let hash = block.hash.clone();
+        accepted.added.push(block);
+        self.connect(bc, &hash, &mut accepted);
+        accepted.status = BlockStatus::Connected {
+            height: bc.latest_block().index,
+            orphans_connected: accepted.added.len() - 1,
+        };
+        Ok(accepted)
+    }
+
+    /// Ajouter les orphelins qui descendent du bloc `parent`
+    fn connect(&self, bc: &mut Blockchain, parent: &str, accepted: &mut Accepted) {
+        let mut parents = vec![parent.to_string()];
+        while let Some(parent) = parents.pop() {
+            for orphan in self.take_children(&parent) {
+                let index = orphan.block.index;
+                match bc.append_block(orphan.block) {
+                    Ok(block) => {
+                        tracing::info!("Connected orphan block {} ({})", block.index, block.hash);
+                        metrics().orphans_connected_total.inc();
+                        parents.push(block.hash.clone());
+                        accepted.added.push(block.clone());
+                    }
+                    Err(error) => {
+                        tracing::warn!("Dropping orphan block {}: {}", index, error);
+                        accepted.dropped.push((index, orphan.source, error));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Ajouter les orphelins dont le parent est déjà dans la chaîne
+    fn connect_known(&self, bc: &mut Blockchain, accepted: &mut Accepted) {
+        let parents: HashSet<String> = self
+            .orphans
+            .lock()
+            .unwrap()
+            .values()
+            .map(|orphan| orphan.block.previous_hash.clone())
+            .filter(|parent| bc.get_block_by_hash(parent).is_some())
+            .collect();
+        for parent in parents {
+            self.connect(bc, &parent, accepted);
+        }
+    }
+
+    /// Traiter un bloc relayé par un pair
+    ///
+    /// Les blocs ajoutés sont publiés (`BlockAdded`) et le pair qui a relayé
+    /// un bloc invalide est pénalisé. Pour un orphelin, ses ancêtres manquants
+    /// sont demandés en tâche de fond.
+    ///
+    /// # Arguments
+    /// * `source` - URL vérifiée du pair émetteur, interrogé en premier s'il
+    ///   fait partie des pairs sortants
+    pub async fn receive(
+        &self,
+        chain: &ChainHandle,
+        peers: &PeerManager,
+        block: Block,
+        source: Option<String>,
+    ) -> Result<BlockStatus, ServiceError> {
+        let hash = block.hash.clone();
+        let status = self.process(chain, peers, block, source.clone()).await?;
+        if matches!(status, BlockStatus::Orphaned { .. }) {
+            let (pool, chain, peers) = (self.clone(), chain.clone(), peers.clone());
+            tokio::spawn(async move { pool.request_parents(&chain, &peers, &hash, source).await });
+        }
+        Ok(status)
+    }
+
+    async fn process(
+        &self,
+        chain: &ChainHandle,
+        peers: &PeerManager,
+        block: Block,
+        source: Option<String>,
+    ) -> Result<BlockStatus, ServiceError> {
+        let pool = self.clone();
+        let from = source.clone();
+        let index = block.index;
+        let result = chain.write(move |bc| pool.accept(bc, block, from)).await?;
+        let accepted = match result {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                chain
+                    .events()
+                    .publish(NodeEvent::block_rejected(index, &error));
+                if let Some(source) = &source {
+                    penalize(peers, source, &error);
+                }
+                return Err(error.into());
+            }
+        };
+
+        for (_, source, error) in &accepted.dropped {
+            if let Some(source) = source {
+                penalize(peers, source, error);
+            }
+        }
+        publish(chain, accepted.added, accepted.dropped);
+        Ok(accepted.status)
+    }
+
+    /// Demander les ancêtres manquants de l'orphelin `hash`, un par un, aux
+    /// pairs sortants, en commençant par `source` s'il en fait partie
+    ///
+    /// Seuls les pairs auxquels le nœud s'est connecté lui-même sont
+    /// interrogés : une URL reçue d'un émetteur n'est jamais contactée.
+    pub async fn request_parents(
+        &self,
+        chain: &ChainHandle,
+        peers: &PeerManager,
+        hash: &str,
+        source: Option<String>,
+    ) {
+        for _ in 0..self.max_orphans {
+            let Some(missing) = self.missing_parent(hash) else {
+                return;
+            };
+            let mut candidates = peers.outbound_urls();
+            if let Some(position) = candidates
+                .iter()
+                .position(|url| Some(url) == source.as_ref())
+            {
+                candidates[..=position].rotate_right(1);
+            }
+
+            let mut parent = None;
+            for url in candidates {
+                match peers.request_block(&url, &missing).await {
+                    Ok(block) => {
+                        parent = Some((block, url));
+                        break;
+                    }
+                    Err(e) => tracing::debug!("Parent {} not served: {}", missing, e),
+                }
+            }
+            let Some((block, url)) = parent else {
+                tracing::warn!("No peer served missing parent {}", missing);
+                return;
+            };
+            match self.process(chain, peers, block, Some(url)).await {
+                Ok(BlockStatus::Orphaned { .. }) => continue,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::warn!("Missing parent {} rejected: {}", missing, e);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Ajouter les orphelins dont le parent arrive par une autre voie
+    /// (mining, synchronisation, import), jusqu'à l'arrêt du nœud
+    ///
+    /// Si des événements ont été perdus, toute la réserve est comparée à la
+    /// chaîne.
+    pub async fn run(self, chain: ChainHandle, shutdown: Shutdown) {
+        let mut events = chain.subscribe();
+        loop {
+            let parent = tokio::select! {
+                event = events.recv() => match event {
+                    Ok(NodeEvent::BlockAdded { block }) => Some(block.hash),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Orphan pool missed {} events, rescanning", skipped);
+                        None
+                    }
+                    Err(RecvError::Closed) => return,
+                },
+                _ = shutdown.wait() => return,
+            };
+            if self.is_empty() {
+                continue;
+            }
+
+            let pool = self.clone();
+            let added = chain
+                .write(move |bc| {
+                    let mut accepted = Accepted {
+                        status: BlockStatus::Duplicate,
+                        added: Vec::new(),
+                        dropped: Vec::new(),
+                    };
+                    match parent {
+                        Some(parent) => pool.connect(bc, &parent, &mut accepted),
+                        None => pool.connect_known(bc, &mut accepted),
+                    }
+                    (accepted.added, accepted.dropped)
+                })
+                .await;
+            let Ok((added, dropped)) = added else {
+                return;
+            };
+            publish(&chain, added, dropped);
+        }
+    }
+}
+
+/// Publier `BlockAdded` pour les blocs ajoutés puis `ValidationFailed` pour
+/// les orphelins refusés
+fn publish(
+    chain: &ChainHandle,
+    added: Vec<Block>,
+    dropped: Vec<(u64, Option<String>, BlockchainError)>,
+) {
+    for block in added {
+        chain.events().publish(NodeEvent::BlockAdded { block });
+    }
+    for (index, _, error) in dropped {
+        chain
+            .events()
+            .publish(NodeEvent::block_rejected(index, &error));
+    }
+}
+
+/// Pénaliser le pair qui a relayé un bloc invalide ; un bloc qui ne prolonge
+/// pas le sommet (fork, bloc en retard) ou daté d'après l'horloge locale n'est
+/// pas une faute
+fn penalize(peers: &PeerManager, source: &str, error: &BlockchainError) {
+    if !matches!(
+        error,
+        BlockchainError::InvalidIndex
+            | BlockchainError::InvalidPreviousHash
+            | BlockchainError::FutureBlock
+    ) {
+        peers.misbehaving(source, INVALID_BLOCK_PENALTY, &error.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(blocks: usize) -> Blockchain {
+        let mut bc = Blockchain::new(1);
+        for i in 1..=blocks {
+            bc.add_block(format!("Block {}", i)).unwrap();
+        }
+        bc
+    }
+
+    #[test]
+    fn test_orphans_connect_once_parent_arrives() {
+        let source = chain(4);
+        let mut local = source.clone();
+        local.truncate(1);
+        let pool = OrphanPool::default();
+
+        // Blocs 4 puis 3 avant 2 : ils attendent leur parent
+        let accepted = pool.accept(&mut local, source.chain[4].clone(), None);
+        assert_eq!(
+            accepted.unwrap().status,
+            BlockStatus::Orphaned {
+                missing_parent: source.chain[3].hash.clone()
+            }
+        );
+        let accepted = pool.accept(&mut local, source.chain[3].clone(), None);
+        assert_eq!(
+            accepted.unwrap().status,
+            BlockStatus::Orphaned {
+                missing_parent: source.chain[2].hash.clone()
+            }
+        );
+        assert_eq!(
+            pool.missing_parent(&source.chain[4].hash),
+            Some(source.chain[2].hash.clone())
+        );
+        let accepted = pool.accept(&mut local, source.chain[3].clone(), None);
+        assert_eq!(accepted.unwrap().status, BlockStatus::Duplicate);
+        assert_eq!(pool.list().len(), 2);
+        assert_eq!(pool.list()[0].index, 3);
+
+        let accepted = pool
+            .accept(&mut local, source.chain[2].clone(), None)
+            .unwrap();
+        assert_eq!(
+            accepted.status,
+            BlockStatus::Connected {
+                height: 4,
+                orphans_connected: 2
+            }
+        );
+        assert_eq!(accepted.added.len(), 3);
+        assert_eq!(local.chain, source.chain);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unsealed_blocks_and_forks() {
+        let source = chain(2);
+        let mut local = source.clone();
+        local.truncate(0);
+        let pool = OrphanPool::default();
+
+        // Un orphelin sans Proof of Work n'entre pas dans la réserve
+        let mut forged = source.chain[2].clone();
+        forged.data = "Forged".to_string();
+        let result = pool.accept(&mut local, forged, None);
+        assert_eq!(result.err(), Some(BlockchainError::InvalidHash));
+        assert!(pool.is_empty());
+
+        // Un bloc dont le parent est connu mais n'est pas le sommet est refusé
+        let mut tip = source.clone();
+        let result = pool.accept(&mut tip, source.chain[1].clone(), None);
+        assert!(matches!(
+            result,
+            Ok(Accepted {
+                status: BlockStatus::Duplicate,
+                ..
+            })
+        ));
+        let mut fork = source.clone();
+        fork.truncate(0);
+        fork.add_block("Fork".to_string()).unwrap();
+        let result = pool.accept(&mut tip, fork.chain[1].clone(), None);
+        assert_eq!(result.err(), Some(BlockchainError::InvalidIndex));
+    }
+
+    #[test]
+    fn test_pool_is_bounded_by_size_and_age() {
+        let source = chain(4);
+        let mut local = source.clone();
+        local.truncate(0);
+
+        let pool = OrphanPool::new(2, ORPHAN_TTL);
+        for block in source.chain.iter().skip(2) {
+            pool.accept(&mut local, block.clone(), None).unwrap();
+        }
+        let hashes: Vec<String> = pool.list().into_iter().map(|o| o.hash).collect();
+        assert_eq!(
+            hashes,
+            vec![source.chain[3].hash.clone(), source.chain[4].hash.clone()]
+        );
+
+        let pool = OrphanPool::new(10, Duration::ZERO);
+        pool.accept(&mut local, source.chain[3].clone(), None)
+            .unwrap();
+        assert!(pool.list().is_empty());
+
+        // Réserve désactivée : l'orphelin est refusé, pas annoncé comme gardé
+        let pool = OrphanPool::new(0, ORPHAN_TTL);
+        let result = pool.accept(&mut local, source.chain[3].clone(), None);
+        assert_eq!(result.err(), Some(BlockchainError::InvalidPreviousHash));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_rescan_connects_orphans_of_known_parents() {
+        let source = chain(4);
+        let mut local = source.clone();
+        local.truncate(1);
+        let pool = OrphanPool::default();
+        for block in source.chain.iter().skip(3) {
+            pool.accept(&mut local, block.clone(), None).unwrap();
+        }
+
+        // Le bloc 2 arrive sans que la réserve le voie passer (événement perdu)
+        local.append_block(source.chain[2].clone()).unwrap();
+        let mut accepted = Accepted {
+            status: BlockStatus::Duplicate,
+            added: Vec::new(),
+            dropped: Vec::new(),
+        };
+        pool.connect_known(&mut local, &mut accepted);
+        assert_eq!(accepted.added.len(), 2);
+        assert_eq!(local.chain, source.chain);
+        assert!(pool.is_empty());
+    }
+}
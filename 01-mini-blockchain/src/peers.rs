@@ -0,0 +1,1161 @@
+//! Gestion des pairs : carnet d'adresses, connexions et bannissements
+//!
+//! Les nœuds se parlent par leur API REST. Une connexion sortante commence
+//! par un échange `POST /peers/addr` : chacun envoie son URL publique et les
+//! adresses qu'il connaît, et l'appelé enregistre l'appelant comme connexion
+//! entrante. L'échange est répété à chaque tour de maintenance (`maintain`),
+//! ce qui propage les adresses de proche en proche (gossip `addr`).
+//!
+//! L'URL `from` d'un message n'est crue que si son hôte désigne l'adresse IP
+//! de la connexion (`verify_sender`) : un nœud ne peut ni occuper de place
+//! entrante ni faire pénaliser un pair sous l'URL d'un autre.
+//!
+//! Scores et bannissements valent pour l'hôte (`host_key`) : un pair banni ne
+//! revient pas sous un autre port, et un émetteur est refusé si l'IP de sa
+//! connexion est bannie. Les adresses de boucle locale ou link-local reçues
+//! par gossip ne sont jamais contactées, sauf sur un réseau local de test
+//! (URL publique locale) ; seeds et ajouts manuels le sont toujours.
+
+use crate::block::Block;
+use crate::metrics::metrics;
+use crate::storage::{self, StorageError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use utoipa::ToSchema;
+
+/// Adresses envoyées au plus par message `addr`
+pub const MAX_ADDR_PER_MESSAGE: usize = 1000;
+
+/// Taille maximale du carnet d'adresses
+pub const MAX_KNOWN_ADDRESSES: usize = 10_000;
+
+/// Connexions entrantes au plus depuis un même hôte
+pub const MAX_INBOUND_PER_HOST: usize = 4;
+
+/// Score de mauvaise conduite à partir duquel un pair est banni
+pub const BAN_THRESHOLD: u32 = 100;
+
+/// Pénalité d'un bloc ou d'un en-tête refusé par la validation
+pub const INVALID_BLOCK_PENALTY: u32 = 100;
+
+/// Pénalité d'un corps de bloc qui ne correspond pas à l'en-tête annoncé
+pub const MISMATCHED_BLOCK_PENALTY: u32 = 50;
+
+/// Pénalité d'en-têtes dont aucun pair n'a servi les corps
+pub const UNRESOLVED_HEADERS_PENALTY: u32 = 50;
+
+/// Intervalle entre deux tours de maintenance des connexions
+pub const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Délai maximal d'une requête à un pair
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Erreurs de la gestion des pairs
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PeerError {
+    #[error("Invalid peer URL '{0}', expected http(s)://host:port")]
+    InvalidUrl(String),
+
+    #[error("Peer {0} is banned")]
+    Banned(String),
+
+    #[error("No inbound slot left")]
+    InboundFull,
+
+    #[error("No outbound slot left")]
+    OutboundFull,
+
+    #[error("Peer {url} is unreachable: {reason}")]
+    Unreachable { url: String, reason: String },
+
+    #[error("No connection with id {0}")]
+    NotFound(u64),
+}
+
+/// Origine d'une adresse du carnet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressSource {
+    /// Configuration (`BLOCKCHAIN_PEERS`)
+    Seed,
+    /// Reçue d'un autre pair
+    Gossip,
+    /// Ajoutée par `POST /peers`
+    Manual,
+    /// Pair qui s'est connecté à nous
+    Inbound,
+}
+
+/// Entrée du carnet d'adresses (persistée)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct KnownAddress {
+    pub url: String,
+    pub source: AddressSource,
+    /// Dernier échange réussi avec ce pair
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Score de mauvaise conduite, remis à zéro à la fin d'un bannissement
+    #[serde(default)]
+    pub score: u32,
+    pub banned_until: Option<DateTime<Utc>>,
+    pub ban_reason: Option<String>,
+}
+
+impl KnownAddress {
+    fn new(url: String, source: AddressSource) -> Self {
+        Self {
+            url,
+            source,
+            last_seen: None,
+            score: 0,
+            banned_until: None,
+            ban_reason: None,
+        }
+    }
+
+    fn is_banned(&self, now: DateTime<Utc>) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Sens d'une connexion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// Connexion avec un pair (GET /peers)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PeerInfo {
+    pub id: u64,
+    pub url: String,
+    pub direction: Direction,
+    pub connected_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Score de mauvaise conduite (banni à 100)
+    pub score: u32,
+}
+
+/// Pair banni (GET /peers)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BannedPeer {
+    pub url: String,
+    pub until: DateTime<Utc>,
+    pub reason: String,
+}
+
+/// Message `addr` échangé par `POST /peers/addr`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AddrMessage {
+    /// URL publique de l'émetteur, s'il accepte des connexions
+    pub from: Option<String>,
+    /// Adresses connues de l'émetteur (1000 au plus)
+    pub addresses: Vec<String>,
+}
+
+/// Paramètres du gestionnaire de pairs
+#[derive(Debug, Clone)]
+pub struct PeerConfig {
+    /// Adresses de départ
+    pub seeds: Vec<String>,
+    pub max_outbound: usize,
+    pub max_inbound: usize,
+    /// URL annoncée aux pairs ; sans elle, le nœud ne reçoit pas de connexion
+    pub public_url: Option<String>,
+    /// Fichier du carnet d'adresses (en mémoire seulement si absent)
+    pub book_path: Option<PathBuf>,
+    pub ban_duration: Duration,
+}
+
+impl Default for PeerConfig {
+    fn default() -> Self {
+        Self {
+            seeds: Vec::new(),
+            max_outbound: 8,
+            max_inbound: 32,
+            public_url: None,
+            book_path: None,
+            ban_duration: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// Normaliser l'URL d'un pair (schéma http(s), sans `/` final)
+pub fn normalize_url(url: &str) -> Result<String, PeerError> {
+    let url = url.trim().trim_end_matches('/');
+    let host = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"));
+    match host {
+        Some(host) if !host.is_empty() && !host.contains(['/', ' ']) => Ok(url.to_string()),
+        _ => Err(PeerError::InvalidUrl(url.to_string())),
+    }
+}
+
+/// Hôte d'une URL normalisée, sans schéma ni port
+fn url_host(url: &str) -> &str {
+    let authority = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .unwrap_or(url);
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => host,
+        _ => authority,
+    }
+}
+
+/// Clé d'hôte des scores et bannissements : l'IP canonique si l'hôte en est
+/// une, le nom d'hôte en minuscules sinon
+fn host_key(url: &str) -> String {
+    let host = url_host(url);
+    match host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => ip_key(ip),
+        Err(_) => host.to_ascii_lowercase(),
+    }
+}
+
+fn ip_key(ip: IpAddr) -> String {
+    ip.to_canonical().to_string()
+}
+
+/// Adresse de boucle locale, link-local ou non spécifiée
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_link_local() || ip.is_unspecified(),
+        IpAddr::V6(ip) => {
+            ip.is_loopback() || ip.is_unspecified() || ip.segments()[0] & 0xffc0 == 0xfe80
+        }
+    }
+}
+
+/// L'URL désigne-t-elle la machine ou le lien local (IP littérale ou
+/// `localhost`) ?
+fn is_local(url: &str) -> bool {
+    let host = url_host(url);
+    match host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => is_local_ip(ip),
+        Err(_) => {
+            let host = host.to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost")
+        }
+    }
+}
+
+/// Comme `is_local`, en résolvant aussi les noms d'hôte
+async fn resolves_locally(url: &str) -> bool {
+    if is_local(url) {
+        return true;
+    }
+    let port = if url.starts_with("https://") { 443 } else { 80 };
+    match tokio::net::lookup_host((url_host(url), port)).await {
+        Ok(mut addresses) => addresses.any(|address| is_local_ip(address.ip())),
+        Err(_) => false,
+    }
+}
+
+/// L'hôte de `url` désigne-t-il l'adresse `ip` ?
+///
+/// Une IP littérale est comparée directement, un nom d'hôte est résolu.
+async fn resolves_to(url: &str, ip: IpAddr) -> bool {
+    let ip = ip.to_canonical();
+    let host = url_host(url);
+    if let Ok(literal) = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        return literal.to_canonical() == ip;
+    }
+    let port = if url.starts_with("https://") { 443 } else { 80 };
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(mut addresses) => addresses.any(|address| address.ip().to_canonical() == ip),
+        Err(_) => false,
+    }
+}
+
+#[derive(Default)]
+struct PeerState {
+    book: BTreeMap<String, KnownAddress>,
+    connections: BTreeMap<u64, PeerInfo>,
+    next_id: u64,
+}
+
+impl PeerState {
+    fn connection(&self, url: &str) -> Option<&PeerInfo> {
+        self.connections.values().find(|peer| peer.url == url)
+    }
+
+    fn count(&self, direction: Direction) -> usize {
+        self.connections
+            .values()
+            .filter(|peer| peer.direction == direction)
+            .count()
+    }
+
+    /// Entrées du carnet de l'hôte `key`
+    fn host_entries<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a KnownAddress> {
+        self.book
+            .values()
+            .filter(move |entry| host_key(&entry.url) == key)
+    }
+
+    /// Score de mauvaise conduite de l'hôte de `url`, tous ports confondus
+    fn score(&self, url: &str) -> u32 {
+        self.host_entries(&host_key(url))
+            .fold(0, |score, entry| score.saturating_add(entry.score))
+    }
+
+    fn is_banned(&self, url: &str, now: DateTime<Utc>) -> bool {
+        self.is_host_banned(&host_key(url), now)
+    }
+
+    fn is_host_banned(&self, key: &str, now: DateTime<Utc>) -> bool {
+        self.host_entries(key).any(|entry| entry.is_banned(now))
+    }
+
+    /// Clés des hôtes bannis, pour filtrer tout le carnet d'un coup
+    fn banned_hosts(&self, now: DateTime<Utc>) -> HashSet<String> {
+        self.book
+            .values()
+            .filter(|entry| entry.is_banned(now))
+            .map(|entry| host_key(&entry.url))
+            .collect()
+    }
+
+    fn learn(&mut self, url: String, source: AddressSource) {
+        if self.book.contains_key(&url) || (self.book.len() >= MAX_KNOWN_ADDRESSES && !self.evict())
+        {
+            return;
+        }
+        self.book
+            .insert(url.clone(), KnownAddress::new(url, source));
+    }
+
+    /// Faire de la place dans un carnet plein : oublier l'adresse reçue par
+    /// gossip ou en entrée vue le moins récemment (jamais vue d'abord), hors
+    /// pairs connectés, bannis ou pénalisés
+    ///
+    /// # Returns
+    /// false si aucune adresse ne peut être oubliée
+    fn evict(&mut self) -> bool {
+        let now = Utc::now();
+        let victim = self
+            .book
+            .values()
+            .filter(|entry| {
+                matches!(entry.source, AddressSource::Gossip | AddressSource::Inbound)
+                    && entry.score == 0
+                    && !entry.is_banned(now)
+                    && self.connection(&entry.url).is_none()
+            })
+            .min_by_key(|entry| entry.last_seen)
+            .map(|entry| entry.url.clone());
+        victim.is_some_and(|url| self.book.remove(&url).is_some())
+    }
+
+    fn connect(&mut self, url: String, direction: Direction) -> PeerInfo {
+        let now = Utc::now();
+        if let Some(entry) = self.book.get_mut(&url) {
+            entry.last_seen = Some(now);
+        }
+        self.next_id += 1;
+        let peer = PeerInfo {
+            id: self.next_id,
+            score: self.score(&url),
+            url,
+            direction,
+            connected_at: now,
+            last_seen: now,
+        };
+        self.connections.insert(peer.id, peer.clone());
+        peer
+    }
+
+    fn touch(&mut self, url: &str) {
+        let now = Utc::now();
+        if let Some(entry) = self.book.get_mut(url) {
+            entry.last_seen = Some(now);
+        }
+        for peer in self.connections.values_mut().filter(|peer| peer.url == url) {
+            peer.last_seen = now;
+        }
+    }
+
+    fn record_count(&self) {
+        metrics().peer_count.set(self.connections.len() as i64);
+    }
+}
+
+/// Gestionnaire des pairs, clonable et partagé entre l'API et les tâches
+#[derive(Clone)]
+pub struct PeerManager {
+    config: Arc<PeerConfig>,
+    state: Arc<Mutex<PeerState>>,
+    http: reqwest::Client,
+}
+
+impl PeerManager {
+    /// Créer le gestionnaire, relire le carnet d'adresses et y ajouter les seeds
+    pub fn new(config: PeerConfig) -> Self {
+        let mut state = PeerState::default();
+        if let Some(path) = &config.book_path {
+            match storage::read_json::<Vec<KnownAddress>>(path) {
+                Ok(entries) => {
+                    state.book = entries.into_iter().map(|e| (e.url.clone(), e)).collect()
+                }
+                Err(StorageError::NotFound(_)) => {}
+                Err(e) => tracing::warn!("Ignoring address book {}: {}", path.display(), e),
+            }
+        }
+        for seed in &config.seeds {
+            match normalize_url(seed) {
+                Ok(url) => state.learn(url, AddressSource::Seed),
+                Err(e) => tracing::warn!("Ignoring seed: {}", e),
+            }
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(PEER_TIMEOUT)
+            .build()
+            .expect("HTTP client");
+        Self {
+            config: Arc::new(config),
+            state: Arc::new(Mutex::new(state)),
+            http,
+        }
+    }
+
+    pub fn config(&self) -> &PeerConfig {
+        &self.config
+    }
+
+    /// Connexions ouvertes, par identifiant
+    pub fn connections(&self) -> Vec<PeerInfo> {
+        let state = self.state.lock().unwrap();
+        state
+            .connections
+            .values()
+            .map(|peer| PeerInfo {
+                score: state.score(&peer.url),
+                ..peer.clone()
+            })
+            .collect()
+    }
+
+    /// URL des pairs auxquels le nœud est connecté
+    pub fn outbound_urls(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state
+            .connections
+            .values()
+            .filter(|peer| peer.direction == Direction::Outbound)
+            .map(|peer| peer.url.clone())
+            .collect()
+    }
+
+    /// Pairs bannis en ce moment
+    pub fn banned(&self) -> Vec<BannedPeer> {
+        let now = Utc::now();
+        let state = self.state.lock().unwrap();
+        state
+            .book
+            .values()
+            .filter(|entry| entry.is_banned(now))
+            .map(|entry| BannedPeer {
+                url: entry.url.clone(),
+                until: entry.banned_until.unwrap_or(now),
+                reason: entry.ban_reason.clone().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Nombre d'adresses du carnet
+    pub fn known_addresses(&self) -> usize {
+        self.state.lock().unwrap().book.len()
+    }
+
+    pub fn is_banned(&self, url: &str) -> bool {
+        self.state.lock().unwrap().is_banned(url, Utc::now())
+    }
+
+    /// Message `addr` de ce nœud : son URL publique et des adresses non bannies
+    pub fn addr_message(&self) -> AddrMessage {
+        let state = self.state.lock().unwrap();
+        let banned = state.banned_hosts(Utc::now());
+        AddrMessage {
+            from: self.config.public_url.clone(),
+            addresses: state
+                .book
+                .values()
+                .filter(|entry| !banned.contains(&host_key(&entry.url)))
+                .map(|entry| entry.url.clone())
+                .take(MAX_ADDR_PER_MESSAGE)
+                .collect(),
+        }
+    }
+
+    /// Ajouter au carnet les adresses reçues d'un pair
+    ///
+    /// Les URL invalides, notre propre adresse et les adresses locales (hors
+    /// réseau local de test, voir `allows_local`) sont ignorées.
+    pub fn learn(&self, addresses: &[String]) {
+        let mut state = self.state.lock().unwrap();
+        for address in addresses.iter().take(MAX_ADDR_PER_MESSAGE) {
+            let Ok(url) = normalize_url(address) else {
+                continue;
+            };
+            if self.config.public_url.as_deref() != Some(url.as_str())
+                && (self.allows_local() || !is_local(&url))
+            {
+                state.learn(url, AddressSource::Gossip);
+            }
+        }
+    }
+
+    /// Le nœud s'annonce-t-il lui-même sur une adresse locale (réseau local
+    /// de test) ? Les adresses locales reçues d'autres pairs sont alors
+    /// contactées comme les autres.
+    fn allows_local(&self) -> bool {
+        self.config.public_url.as_deref().is_some_and(is_local)
+    }
+
+    /// Vérifier l'URL `from` annoncée par l'émetteur d'un message
+    ///
+    /// # Arguments
+    /// * `from` - URL annoncée dans le corps du message
+    /// * `remote` - Adresse de la connexion (None hors serveur HTTP)
+    ///
+    /// # Returns
+    /// L'URL normalisée si son hôte désigne l'IP de la connexion, None si
+    /// elle est absente ou invérifiable, `Banned` si son hôte ou l'IP de la
+    /// connexion est banni
+    pub async fn verify_sender(
+        &self,
+        from: Option<&str>,
+        remote: Option<SocketAddr>,
+    ) -> Result<Option<String>, PeerError> {
+        let Some(from) = from else {
+            return Ok(None);
+        };
+        let url = normalize_url(from)?;
+        let banned = {
+            let now = Utc::now();
+            let state = self.state.lock().unwrap();
+            state.is_banned(&url, now)
+                || remote.is_some_and(|remote| state.is_host_banned(&ip_key(remote.ip()), now))
+        };
+        if banned {
+            return Err(PeerError::Banned(url));
+        }
+        match remote {
+            Some(remote) if resolves_to(&url, remote.ip()).await => Ok(Some(url)),
+            _ => {
+                tracing::debug!("Sender {} does not match connection {:?}", url, remote);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Traiter un message `addr` reçu (POST /peers/addr)
+    ///
+    /// L'émetteur vérifié (`verify_sender`) devient une connexion entrante,
+    /// dans la limite de `max_inbound` et de `MAX_INBOUND_PER_HOST`.
+    ///
+    /// # Returns
+    /// Le message `addr` de ce nœud, en réponse
+    pub fn receive_addr(
+        &self,
+        message: &AddrMessage,
+        sender: Option<&str>,
+    ) -> Result<AddrMessage, PeerError> {
+        if let Some(url) = sender {
+            let url = url.to_string();
+            let mut state = self.state.lock().unwrap();
+            if state.connection(&url).is_some() {
+                state.touch(&url);
+            } else {
+                let same_host = state
+                    .connections
+                    .values()
+                    .filter(|peer| peer.direction == Direction::Inbound)
+                    .filter(|peer| host_key(&peer.url) == host_key(&url))
+                    .count();
+                if state.count(Direction::Inbound) >= self.config.max_inbound
+                    || same_host >= MAX_INBOUND_PER_HOST
+                {
+                    return Err(PeerError::InboundFull);
+                }
+                state.learn(url.clone(), AddressSource::Inbound);
+                let peer = state.connect(url, Direction::Inbound);
+                tracing::info!("Inbound peer {} connected (#{})", peer.url, peer.id);
+                state.record_count();
+            }
+        }
+        self.learn(&message.addresses);
+        Ok(self.addr_message())
+    }
+
+    /// Ouvrir une connexion sortante (échange `addr`)
+    pub async fn connect(&self, url: &str) -> Result<PeerInfo, PeerError> {
+        self.open(url, AddressSource::Gossip).await
+    }
+
+    /// Connexion demandée par l'opérateur (POST /peers) : lève un éventuel
+    /// bannissement de son hôte et ignore la limite de connexions sortantes
+    pub async fn add_manual(&self, url: &str) -> Result<PeerInfo, PeerError> {
+        let url = normalize_url(url)?;
+        {
+            let mut state = self.state.lock().unwrap();
+            state.learn(url.clone(), AddressSource::Manual);
+            if let Some(entry) = state.book.get_mut(&url) {
+                entry.source = AddressSource::Manual;
+            }
+            let key = host_key(&url);
+            for entry in state.book.values_mut() {
+                if host_key(&entry.url) == key {
+                    entry.score = 0;
+                    entry.banned_until = None;
+                    entry.ban_reason = None;
+                }
+            }
+        }
+        self.open(&url, AddressSource::Manual).await
+    }
+
+    async fn open(&self, url: &str, source: AddressSource) -> Result<PeerInfo, PeerError> {
+        let url = normalize_url(url)?;
+        {
+            let state = self.state.lock().unwrap();
+            if state.is_banned(&url, Utc::now()) {
+                return Err(PeerError::Banned(url));
+            }
+            if let Some(peer) = state.connection(&url) {
+                return Ok(peer.clone());
+            }
+            if source != AddressSource::Manual
+                && state.count(Direction::Outbound) >= self.config.max_outbound
+            {
+                return Err(PeerError::OutboundFull);
+            }
+        }
+
+        let reply = self.exchange_addr(&url).await?;
+        self.learn(&reply.addresses);
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(peer) = state.connection(&url) {
+            return Ok(peer.clone());
+        }
+        state.learn(url.clone(), source);
+        let peer = state.connect(url, Direction::Outbound);
+        tracing::info!("Connected to peer {} (#{})", peer.url, peer.id);
+        state.record_count();
+        Ok(peer)
+    }
+
+    async fn exchange_addr(&self, url: &str) -> Result<AddrMessage, PeerError> {
+        let unreachable = |reason: String| PeerError::Unreachable {
+            url: url.to_string(),
+            reason,
+        };
+        let response = self
+            .http
+            .post(format!("{}/peers/addr", url))
+            .json(&self.addr_message())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| unreachable(e.to_string()))?;
+        response
+            .json()
+            .await
+            .map_err(|e| unreachable(e.to_string()))
+    }
+
+    /// Demander à un pair le bloc de hash `hash` (parent d'un orphelin)
+    pub async fn request_block(&self, url: &str, hash: &str) -> Result<Block, PeerError> {
+        let unreachable = |reason: String| PeerError::Unreachable {
+            url: url.to_string(),
+            reason,
+        };
+        let block: Block = self
+            .http
+            .get(format!("{}/blocks/hash/{}", url, hash))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| unreachable(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| unreachable(e.to_string()))?;
+        if block.hash != hash {
+            return Err(unreachable(format!("served block {} instead", block.hash)));
+        }
+        Ok(block)
+    }
+
+    /// Fermer une connexion, et bannir le pair si `ban`
+    pub fn disconnect(&self, id: u64, ban: bool) -> Result<PeerInfo, PeerError> {
+        let peer = {
+            let mut state = self.state.lock().unwrap();
+            let peer = state
+                .connections
+                .remove(&id)
+                .ok_or(PeerError::NotFound(id))?;
+            state.record_count();
+            peer
+        };
+        tracing::info!("Disconnected peer {} (#{})", peer.url, peer.id);
+        if ban {
+            self.ban(&peer.url, "banned by the operator");
+        }
+        Ok(peer)
+    }
+
+    /// Signaler une mauvaise conduite ; l'hôte du pair est banni quand son
+    /// score, tous ports confondus, atteint `BAN_THRESHOLD`
+    ///
+    /// # Returns
+    /// true si le pair vient d'être banni
+    pub fn misbehaving(&self, url: &str, penalty: u32, reason: &str) -> bool {
+        let Ok(url) = normalize_url(url) else {
+            return false;
+        };
+        let score = {
+            let mut state = self.state.lock().unwrap();
+            state.learn(url.clone(), AddressSource::Gossip);
+            let Some(entry) = state.book.get_mut(&url) else {
+                return false;
+            };
+            entry.score = entry.score.saturating_add(penalty);
+            state.score(&url)
+        };
+        tracing::warn!("Peer {} misbehaved ({}), score {}", url, reason, score);
+        if score < BAN_THRESHOLD {
+            return false;
+        }
+        self.ban(&url, reason);
+        true
+    }
+
+    /// Bannir l'hôte d'un pair pour `ban_duration` et fermer ses connexions,
+    /// sur tous les ports
+    pub fn ban(&self, url: &str, reason: &str) {
+        let until = Utc::now()
+            + chrono::Duration::from_std(self.config.ban_duration).unwrap_or(chrono::Duration::MAX);
+        {
+            let mut state = self.state.lock().unwrap();
+            state.learn(url.to_string(), AddressSource::Gossip);
+            // Le score de l'hôte est soldé par le bannissement
+            let key = host_key(url);
+            for entry in state.book.values_mut() {
+                if entry.url != url && host_key(&entry.url) == key {
+                    entry.score = 0;
+                }
+            }
+            if let Some(entry) = state.book.get_mut(url) {
+                entry.banned_until = Some(until);
+                entry.ban_reason = Some(reason.to_string());
+            }
+            state
+                .connections
+                .retain(|_, peer| host_key(&peer.url) != key);
+            state.record_count();
+        }
+        tracing::warn!("Banned peer {} until {}: {}", url, until, reason);
+        self.save();
+    }
+
+    /// Un tour de maintenance
+    ///
+    /// 1. Renouveler l'échange `addr` avec chaque pair sortant (un pair muet
+    ///    est déconnecté) et oublier les pairs entrants silencieux
+    /// 2. Compléter les connexions sortantes avec le carnet d'adresses, en
+    ///    commençant par les seeds et les pairs vus le plus récemment
+    /// 3. Lever les bannissements expirés et sauvegarder le carnet
+    pub async fn maintain(&self) {
+        for url in self.outbound_urls() {
+            match self.exchange_addr(&url).await {
+                Ok(reply) => {
+                    self.learn(&reply.addresses);
+                    self.state.lock().unwrap().touch(&url);
+                }
+                Err(e) => {
+                    tracing::info!("Dropping peer: {}", e);
+                    let mut state = self.state.lock().unwrap();
+                    state.connections.retain(|_, peer| peer.url != url);
+                    state.record_count();
+                }
+            }
+        }
+
+        let stale = Utc::now()
+            - chrono::Duration::from_std(3 * MAINTENANCE_INTERVAL).expect("short interval");
+        // Les adresses apprises pendant le tour deviennent aussitôt candidates
+        let mut tried = HashSet::new();
+        while self.state.lock().unwrap().count(Direction::Outbound) < self.config.max_outbound {
+            let Some(candidate) = self
+                .candidates()
+                .into_iter()
+                .find(|candidate| !tried.contains(candidate))
+            else {
+                break;
+            };
+            tried.insert(candidate.clone());
+            if !self.allows_local() && !self.is_configured(&candidate) {
+                // Un nom d'hôte gossipé peut désigner la machine elle-même
+                if resolves_locally(&candidate).await {
+                    tracing::debug!("Not contacting local address {}", candidate);
+                    continue;
+                }
+            }
+            if let Err(e) = self.connect(&candidate).await {
+                tracing::debug!("Cannot connect to {}: {}", candidate, e);
+            }
+        }
+
+        {
+            let now = Utc::now();
+            let mut state = self.state.lock().unwrap();
+            state
+                .connections
+                .retain(|_, peer| peer.direction == Direction::Outbound || peer.last_seen >= stale);
+            for entry in state.book.values_mut() {
+                if entry.banned_until.is_some_and(|until| until <= now) {
+                    entry.banned_until = None;
+                    entry.ban_reason = None;
+                    entry.score = 0;
+                }
+            }
+            state.record_count();
+        }
+        self.save();
+    }
+
+    /// Adresse ajoutée par l'opérateur (seed ou ajout manuel) ?
+    fn is_configured(&self, url: &str) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .book
+            .get(url)
+            .is_some_and(|entry| {
+                matches!(entry.source, AddressSource::Seed | AddressSource::Manual)
+            })
+    }
+
+    /// Adresses non bannies et non connectées, seeds d'abord puis par date de
+    /// dernier échange décroissante
+    ///
+    /// Les adresses locales ne sont retenues que si l'opérateur les a
+    /// configurées ou sur un réseau local de test.
+    fn candidates(&self) -> Vec<String> {
+        let allows_local = self.allows_local();
+        let state = self.state.lock().unwrap();
+        let banned = state.banned_hosts(Utc::now());
+        let mut candidates: Vec<&KnownAddress> = state
+            .book
+            .values()
+            .filter(|entry| !banned.contains(&host_key(&entry.url)))
+            .filter(|entry| state.connection(&entry.url).is_none())
+            .filter(|entry| self.config.public_url.as_deref() != Some(entry.url.as_str()))
+            .filter(|entry| {
+                allows_local
+                    || matches!(entry.source, AddressSource::Seed | AddressSource::Manual)
+                    || !is_local(&entry.url)
+            })
+            .collect();
+        candidates.sort_by_key(|entry| {
+            (
+                entry.source != AddressSource::Seed,
+                std::cmp::Reverse(entry.last_seen),
+            )
+        });
+        candidates.iter().map(|entry| entry.url.clone()).collect()
+    }
+
+    /// Maintenir les connexions jusqu'à l'arrêt du nœud
+    pub async fn run(self, shutdown: crate::shutdown::Shutdown) {
+        loop {
+            self.maintain().await;
+            tokio::select! {
+                _ = tokio::time::sleep(MAINTENANCE_INTERVAL) => {}
+                _ = shutdown.wait() => return,
+            }
+        }
+    }
+
+    /// Écrire le carnet d'adresses, s'il est persisté
+    pub fn save(&self) {
+        let Some(path) = &self.config.book_path else {
+            return;
+        };
+        let entries: Vec<KnownAddress> =
+            self.state.lock().unwrap().book.values().cloned().collect();
+        if let Err(e) = storage::write_json(path, &entries) {
+            tracing::error!("Failed to save address book to {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(config: PeerConfig) -> PeerManager {
+        PeerManager::new(config)
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url(" http://10.0.0.2:8090/ ").unwrap(),
+            "http://10.0.0.2:8090"
+        );
+        assert!(normalize_url("https://node.example").is_ok());
+        assert!(normalize_url("10.0.0.2:8090").is_err());
+        assert!(normalize_url("http://").is_err());
+        assert!(normalize_url("http://host/path").is_err());
+    }
+
+    #[test]
+    fn test_inbound_limit_and_gossip() {
+        let peers = manager(PeerConfig {
+            max_inbound: 1,
+            public_url: Some("http://me:8090".to_string()),
+            ..PeerConfig::default()
+        });
+
+        let message = AddrMessage {
+            from: Some("http://a:8090".to_string()),
+            addresses: vec![
+                "http://b:8090".to_string(),
+                "http://me:8090".to_string(),
+                "not a url".to_string(),
+            ],
+        };
+        let reply = peers.receive_addr(&message, Some("http://a:8090")).unwrap();
+        assert_eq!(reply.from.as_deref(), Some("http://me:8090"));
+        assert_eq!(reply.addresses, vec!["http://a:8090", "http://b:8090"]);
+        assert_eq!(peers.connections().len(), 1);
+        assert_eq!(peers.connections()[0].direction, Direction::Inbound);
+
+        // Le même pair ne prend pas un second emplacement, un autre est refusé
+        let empty = AddrMessage::default();
+        assert!(peers.receive_addr(&empty, Some("http://a:8090")).is_ok());
+        assert_eq!(
+            peers.receive_addr(&empty, Some("http://c:8090")),
+            Err(PeerError::InboundFull)
+        );
+
+        // Un message sans émetteur vérifié ne fait que partager des adresses
+        assert!(peers.receive_addr(&empty, None).is_ok());
+        assert_eq!(peers.connections().len(), 1);
+
+        // Un même hôte n'occupe pas toutes les places entrantes
+        let peers = manager(PeerConfig::default());
+        for port in 0..MAX_INBOUND_PER_HOST {
+            let url = format!("http://10.0.0.9:{}", 8000 + port);
+            assert!(peers.receive_addr(&empty, Some(&url)).is_ok());
+        }
+        assert_eq!(
+            peers.receive_addr(&empty, Some("http://10.0.0.9:9000")),
+            Err(PeerError::InboundFull)
+        );
+        assert!(peers
+            .receive_addr(&empty, Some("http://10.0.0.10:9000"))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_sender_must_match_connection() {
+        let peers = manager(PeerConfig::default());
+        let local = Some(SocketAddr::from(([127, 0, 0, 1], 50_000)));
+
+        let verify = |from: &'static str, remote| peers.verify_sender(Some(from), remote);
+        assert_eq!(
+            verify("http://127.0.0.1:8090/", local).await,
+            Ok(Some("http://127.0.0.1:8090".to_string()))
+        );
+        assert_eq!(
+            verify("http://localhost:8090", local).await,
+            Ok(Some("http://localhost:8090".to_string()))
+        );
+        let ipv6 = Some(SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, 50_000)));
+        assert!(verify("http://[::1]:8090", ipv6).await.unwrap().is_some());
+
+        // Une URL qui désigne un autre hôte n'est pas crue
+        assert_eq!(verify("http://10.0.0.9:8090", local).await, Ok(None));
+        assert_eq!(verify("http://127.0.0.1:8090", None).await, Ok(None));
+        assert_eq!(peers.verify_sender(None, local).await, Ok(None));
+        assert!(verify("not a url", local).await.is_err());
+    }
+
+    #[test]
+    fn test_full_address_book_evicts_unseen_gossip() {
+        let mut state = PeerState::default();
+        state.learn("http://seed:8090".to_string(), AddressSource::Seed);
+        for i in 1..MAX_KNOWN_ADDRESSES {
+            state.learn(format!("http://gossip-{}:8090", i), AddressSource::Gossip);
+        }
+        state
+            .book
+            .get_mut("http://gossip-1:8090")
+            .unwrap()
+            .last_seen = Some(Utc::now());
+        assert_eq!(state.book.len(), MAX_KNOWN_ADDRESSES);
+
+        state.learn("http://new:8090".to_string(), AddressSource::Gossip);
+        assert_eq!(state.book.len(), MAX_KNOWN_ADDRESSES);
+        assert!(state.book.contains_key("http://new:8090"));
+        assert!(state.book.contains_key("http://seed:8090"));
+        assert!(state.book.contains_key("http://gossip-1:8090"));
+
+        // Seeds, adresses manuelles et bannies ne sont jamais oubliées
+        let mut state = PeerState::default();
+        for i in 0..MAX_KNOWN_ADDRESSES {
+            state.learn(format!("http://seed-{}:8090", i), AddressSource::Seed);
+        }
+        state.learn("http://new:8090".to_string(), AddressSource::Gossip);
+        assert!(!state.book.contains_key("http://new:8090"));
+    }
+
+    #[tokio::test]
+    async fn test_misbehavior_bans_and_disconnects() {
+        let peers = manager(PeerConfig::default());
+        peers
+            .receive_addr(&AddrMessage::default(), Some("http://a:8090"))
+            .unwrap();
+
+        assert!(!peers.misbehaving("http://a:8090", MISMATCHED_BLOCK_PENALTY, "bad body"));
+        assert_eq!(peers.connections()[0].score, MISMATCHED_BLOCK_PENALTY);
+        assert!(peers.misbehaving("http://a:8090", INVALID_BLOCK_PENALTY, "invalid block"));
+
+        assert!(peers.connections().is_empty());
+        assert!(peers.is_banned("http://a:8090"));
+        assert_eq!(peers.banned()[0].reason, "invalid block");
+        assert_eq!(
+            peers.verify_sender(Some("http://a:8090"), None).await,
+            Err(PeerError::Banned("http://a:8090".to_string()))
+        );
+        assert!(!peers
+            .addr_message()
+            .addresses
+            .contains(&"http://a:8090".to_string()));
+
+        assert_eq!(peers.disconnect(42, false), Err(PeerError::NotFound(42)));
+    }
+
+    #[tokio::test]
+    async fn test_bans_apply_to_the_host() {
+        let peers = manager(PeerConfig::default());
+        let empty = AddrMessage::default();
+        peers
+            .receive_addr(&empty, Some("http://10.0.0.9:8000"))
+            .unwrap();
+        peers
+            .receive_addr(&empty, Some("http://10.0.0.10:8000"))
+            .unwrap();
+
+        // Les pénalités de l'hôte s'additionnent, quel que soit le port
+        assert!(!peers.misbehaving("http://10.0.0.9:8090", MISMATCHED_BLOCK_PENALTY, "bad body"));
+        assert_eq!(peers.connections()[0].score, MISMATCHED_BLOCK_PENALTY);
+        assert!(peers.misbehaving("http://10.0.0.9:9000", MISMATCHED_BLOCK_PENALTY, "bad body"));
+        assert!(peers.is_banned("http://10.0.0.9:7000"));
+        assert!(peers.is_banned("http://[::ffff:10.0.0.9]:7000"));
+        assert!(!peers.is_banned("http://10.0.0.10:8000"));
+        assert_eq!(peers.connections().len(), 1);
+        assert_eq!(peers.connections()[0].url, "http://10.0.0.10:8000");
+        assert_eq!(
+            peers.addr_message().addresses,
+            vec!["http://10.0.0.10:8000"]
+        );
+
+        // Un hôte banni ne revient pas sous un autre nom
+        peers.ban("http://127.0.0.1:8091", "invalid block");
+        let local = Some(SocketAddr::from(([127, 0, 0, 1], 50_000)));
+        assert_eq!(
+            peers
+                .verify_sender(Some("http://localhost:8092"), local)
+                .await,
+            Err(PeerError::Banned("http://localhost:8092".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gossiped_local_addresses_are_dropped() {
+        let peers = manager(PeerConfig {
+            seeds: vec!["http://127.0.0.1:8090".to_string()],
+            public_url: Some("http://me:8090".to_string()),
+            ..PeerConfig::default()
+        });
+        peers.learn(&[
+            "http://127.0.0.2:8090".to_string(),
+            "http://localhost:6379".to_string(),
+            "http://[::1]:8090".to_string(),
+            "http://169.254.169.254".to_string(),
+            "http://[fe80::1]:8090".to_string(),
+            "http://0.0.0.0:8090".to_string(),
+            "http://10.0.0.2:8090".to_string(),
+        ]);
+        assert_eq!(peers.known_addresses(), 2);
+
+        // Adresse locale déjà au carnet (par exemple relu d'un ancien
+        // fichier) : jamais contactée non plus
+        peers.state.lock().unwrap().learn(
+            "http://[::ffff:127.0.0.1]:8091".to_string(),
+            AddressSource::Gossip,
+        );
+        assert_eq!(
+            peers.candidates(),
+            vec!["http://127.0.0.1:8090", "http://10.0.0.2:8090"]
+        );
+        assert!(resolves_locally("http://localhost:8090").await);
+        assert!(!resolves_locally("http://10.0.0.2:8090").await);
+
+        // Sur un réseau local de test, les adresses locales circulent
+        let peers = manager(PeerConfig {
+            public_url: Some("http://127.0.0.1:8090".to_string()),
+            ..PeerConfig::default()
+        });
+        peers.learn(&["http://127.0.0.1:8091".to_string()]);
+        assert_eq!(peers.candidates(), vec!["http://127.0.0.1:8091"]);
+    }
+
+    #[test]
+    fn test_address_book_is_persisted() {
+        let path =
+            std::env::temp_dir().join(format!("mini-blockchain-peers-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let config = PeerConfig {
+            seeds: vec!["http://seed:8090".to_string()],
+            book_path: Some(path.clone()),
+            ..PeerConfig::default()
+        };
+
+        let peers = manager(config.clone());
+        peers.learn(&["http://gossiped:8090".to_string()]);
+        peers.ban("http://bad:8090", "invalid block");
+
+        let reloaded = manager(config);
+        assert_eq!(reloaded.known_addresses(), 3);
+        assert!(reloaded.is_banned("http://bad:8090"));
+        assert_eq!(reloaded.candidates()[0], "http://seed:8090");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
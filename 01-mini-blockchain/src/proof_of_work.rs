@@ -1,4 +1,120 @@
 use crate::block::Block;
+use crate::metrics::metrics;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chrono::SubsecRound;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use utoipa::ToSchema;
+
+/// Nombre de hashes entre deux vérifications du drapeau d'annulation
+const CANCEL_CHECK_INTERVAL: u64 = 1024;
+
+/// Mémoire utilisée par un hash Argon2id, en Kio
+pub const ARGON2_MEMORY_KIB: u32 = 4096;
+
+/// Nombre de passes Argon2id sur la mémoire
+const ARGON2_ITERATIONS: u32 = 1;
+
+/// Sel fixe : le mot de passe (hash de l'en-tête) est déjà unique par essai
+const ARGON2_SALT: &[u8] = b"mini-blockchain-pow";
+
+/// Algorithme de Proof of Work, inscrit dans l'en-tête de chaque bloc
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PowAlgorithm {
+    /// Le hash SHA-256 de l'en-tête doit commencer par `difficulty` zéros
+    #[default]
+    Sha256,
+    /// L'Argon2id du hash de l'en-tête doit commencer par `difficulty` zéros
+    ///
+    /// Chaque essai remplit `ARGON2_MEMORY_KIB` Kio de mémoire : le coût est
+    /// borné par la bande passante mémoire plutôt que par le calcul.
+    Argon2id,
+    /// Comme Bitcoin : double SHA-256 de l'en-tête de 80 octets
+    /// (`Block::bitcoin_header`), hash affiché octets inversés
+    Sha256d,
+}
+
+impl PowAlgorithm {
+    /// Identifiant haché dans l'en-tête texte du bloc (tous sauf `Sha256d`)
+    ///
+    /// Vide pour SHA-256 : le hash des blocs antérieurs au choix
+    /// d'algorithme ne change pas.
+    pub fn header_tag(&self) -> &'static str {
+        match self {
+            PowAlgorithm::Sha256 => "",
+            PowAlgorithm::Argon2id => "argon2id",
+            PowAlgorithm::Sha256d => "sha256d",
+        }
+    }
+}
+
+impl fmt::Display for PowAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowAlgorithm::Sha256 => f.write_str("sha256"),
+            PowAlgorithm::Argon2id => f.write_str("argon2id"),
+            PowAlgorithm::Sha256d => f.write_str("sha256d"),
+        }
+    }
+}
+
+impl FromStr for PowAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(PowAlgorithm::Sha256),
+            "argon2id" => Ok(PowAlgorithm::Argon2id),
+            "sha256d" => Ok(PowAlgorithm::Sha256d),
+            other => Err(format!(
+                "Unknown proof-of-work algorithm '{}', expected sha256, argon2id or sha256d",
+                other
+            )),
+        }
+    }
+}
+
+/// Hasheur Argon2id qui réutilise sa mémoire d'un essai à l'autre
+pub struct Argon2Hasher {
+    argon2: Argon2<'static>,
+    memory: Vec<argon2::Block>,
+}
+
+impl Argon2Hasher {
+    pub fn new() -> Self {
+        let params = Params::new(ARGON2_MEMORY_KIB, ARGON2_ITERATIONS, 1, Some(32))
+            .expect("valid Argon2 parameters");
+        Self {
+            memory: vec![argon2::Block::default(); params.block_count()],
+            argon2: Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+        }
+    }
+
+    /// Argon2id (32 octets) du hash brut d'un en-tête
+    pub fn digest(&mut self, header_hash: &[u8; 32]) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        self.argon2
+            .hash_password_into_with_memory(header_hash, ARGON2_SALT, &mut out, &mut self.memory)
+            .expect("valid Argon2 inputs");
+        out
+    }
+}
+
+impl Default for Argon2Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    /// Mémoire Argon2id de chaque thread de validation
+    static ARGON2: RefCell<Argon2Hasher> = RefCell::new(Argon2Hasher::new());
+}
 
 /// Miner un bloc avec l'algorithme Proof of Work
 ///
@@ -11,17 +127,71 @@ use crate::block::Block;
 /// un hash commençant par N zéros (N = difficulty). Elle incrémente
 /// le nonce jusqu'à trouver un hash valide.
 pub fn mine_block(block: &mut Block, difficulty: usize) {
-    let target = "0".repeat(difficulty);
+    mine_block_until(block, difficulty, &AtomicBool::new(false));
+}
+
+/// Miner un bloc en s'arrêtant si `cancel` passe à true
+///
+/// L'algorithme est celui inscrit dans l'en-tête du bloc (`Block::algorithm`).
+///
+/// # Returns
+/// true si un hash valide a été trouvé, false si le mining a été annulé
+/// (le hash du bloc reste alors vide)
+pub fn mine_block_until(block: &mut Block, difficulty: usize, cancel: &AtomicBool) -> bool {
+    // En-tête Bitcoin : horodatage à la seconde, nonce sur 32 bits
+    let mut header = (block.algorithm == PowAlgorithm::Sha256d).then(|| {
+        block.timestamp = block.timestamp.trunc_subsecs(0);
+        block.bitcoin_header()
+    });
+    // Seul le nonce change d'un essai à l'autre : hacher le reste une fois
+    let midstate = block.midstate();
+    let mut argon2 = (block.algorithm == PowAlgorithm::Argon2id).then(Argon2Hasher::new);
+    // Un essai Argon2id coûte des millisecondes : vérifier à chaque essai
+    let check_interval = if argon2.is_some() {
+        1
+    } else {
+        CANCEL_CHECK_INTERVAL
+    };
+    let started = Instant::now();
+    let mut hashes: u64 = 0;
 
     loop {
-        let hash = block.calculate_hash();
-        if hash.starts_with(&target) {
-            block.hash = hash;
+        // Consulter le drapeau d'annulation régulièrement sans ralentir la boucle
+        if hashes.is_multiple_of(check_interval) && cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let digest = match &mut header {
+            Some(header) => {
+                // Nonce épuisé : avancer l'horodatage d'une seconde, comme un mineur Bitcoin
+                if block.nonce > u64::from(u32::MAX) {
+                    block.timestamp += chrono::Duration::seconds(1);
+                    block.nonce = 0;
+                    *header = block.bitcoin_header();
+                }
+                header.nonce = block.nonce as u32;
+                let mut digest = header.hash();
+                digest.reverse();
+                digest
+            }
+            None => midstate.digest(block.nonce),
+        };
+        let work = match &mut argon2 {
+            Some(hasher) => hasher.digest(&digest),
+            None => digest,
+        };
+        hashes += 1;
+        if meets_difficulty(&work, difficulty) {
+            block.hash = hex::encode(digest);
             break;
         } else {
             block.nonce += 1;
         }
     }
+
+    // Exporter le hashrate et la durée du mining
+    metrics().record_mining(hashes, started.elapsed());
+    true
 }
 
 /// Vérifier qu'un hash respecte la difficulté du Proof of Work
@@ -35,6 +205,8 @@ pub fn mine_block(block: &mut Block, difficulty: usize) {
 ///
 /// # Exemple
 /// ```
+/// use mini_blockchain::proof_of_work::verify_proof_of_work;
+///
 /// let hash = "0000abc123...";
 /// assert!(verify_proof_of_work(hash, 4)); // true car commence par "0000"
 /// ```
@@ -44,6 +216,45 @@ pub fn verify_proof_of_work(hash: &str, difficulty: usize) -> bool {
     hash.starts_with(&target)
 }
 
+/// Vérifier le Proof of Work d'un bloc selon l'algorithme de son en-tête
+///
+/// # Arguments
+/// * `block` - Bloc dont le hash est correct
+/// * `difficulty` - Le nombre de zéros requis
+pub fn verify_block_work(block: &Block, difficulty: usize) -> bool {
+    match block.algorithm {
+        PowAlgorithm::Sha256 => verify_proof_of_work(&block.hash, difficulty),
+        PowAlgorithm::Sha256d => {
+            block.header_fits_bitcoin() && verify_proof_of_work(&block.hash, difficulty)
+        }
+        PowAlgorithm::Argon2id => {
+            let Some(hash) = hex::decode(&block.hash)
+                .ok()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            else {
+                return false;
+            };
+            difficulty == 0
+                || ARGON2
+                    .with(|hasher| meets_difficulty(&hasher.borrow_mut().digest(&hash), difficulty))
+        }
+    }
+}
+
+/// Vérifier la difficulté sur le hash brut, sans l'encoder en hexadécimal
+///
+/// # Returns
+/// Le même résultat que `verify_proof_of_work` sur `hex::encode(digest)`
+pub fn meets_difficulty(digest: &[u8; 32], difficulty: usize) -> bool {
+    // Deux chiffres hexadécimaux par octet
+    if difficulty > 2 * digest.len() {
+        return false;
+    }
+    let zero_bytes = difficulty / 2;
+    digest[..zero_bytes].iter().all(|byte| *byte == 0)
+        && (difficulty.is_multiple_of(2) || digest[zero_bytes] >> 4 == 0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +298,72 @@ mod tests {
         // (un hash commençant par "00" satisfait aussi difficulté 1)
         assert!(verify_proof_of_work(&block.hash, 1));
     }
+
+    #[test]
+    fn test_meets_difficulty_matches_hex_check() {
+        let mut digest = [0xffu8; 32];
+        digest[0] = 0x00;
+        digest[1] = 0x0a;
+        let hash = hex::encode(digest);
+
+        for difficulty in 0..=66 {
+            assert_eq!(
+                meets_difficulty(&digest, difficulty),
+                verify_proof_of_work(&hash, difficulty),
+                "difficulty {}",
+                difficulty
+            );
+        }
+        assert!(meets_difficulty(&[0u8; 32], 64));
+    }
+
+    #[test]
+    fn test_argon2id_mining() {
+        let mut block = Block::new(1, "Memory hard".to_string(), "previous".to_string());
+        block.algorithm = PowAlgorithm::Argon2id;
+        mine_block(&mut block, 1);
+
+        // Le hash du bloc reste le SHA-256 de l'en-tête, qui inclut l'algorithme
+        assert_eq!(block.hash, block.calculate_hash());
+        assert!(verify_block_work(&block, 1));
+        let mut digest = [0u8; 32];
+        hex::decode_to_slice(&block.hash, &mut digest).unwrap();
+        assert!(meets_difficulty(&Argon2Hasher::new().digest(&digest), 1));
+
+        // Changer d'algorithme change le hash : l'en-tête ne correspond plus
+        let mut relabeled = block.clone();
+        relabeled.algorithm = PowAlgorithm::Sha256;
+        assert_ne!(relabeled.calculate_hash(), block.hash);
+
+        assert_eq!("argon2id".parse(), Ok(PowAlgorithm::Argon2id));
+        assert_eq!(PowAlgorithm::Sha256.to_string(), "sha256");
+        assert!("scrypt".parse::<PowAlgorithm>().is_err());
+    }
+
+    #[test]
+    fn test_sha256d_mining() {
+        let mut block = Block::new(1, "Bitcoin style".to_string(), "ab".repeat(32));
+        block.algorithm = PowAlgorithm::Sha256d;
+        mine_block(&mut block, 2);
+
+        assert!(block.hash.starts_with("00"));
+        assert_eq!(block.hash, block.calculate_hash());
+        assert_eq!(block.hash, block.bitcoin_header().block_hash());
+        assert!(verify_block_work(&block, 2));
+
+        // Un horodatage qui n'est plus à la seconde sort du format Bitcoin
+        let mut tampered = block.clone();
+        tampered.timestamp += chrono::Duration::nanoseconds(1);
+        assert!(!verify_block_work(&tampered, 0));
+    }
+
+    #[test]
+    fn test_mining_cancelled() {
+        let mut block = Block::new(1, "Cancelled".to_string(), "previous".to_string());
+
+        // Difficulté impossible à atteindre : seule l'annulation arrête la boucle
+        let cancel = AtomicBool::new(true);
+        assert!(!mine_block_until(&mut block, 64, &cancel));
+        assert!(block.hash.is_empty());
+    }
 }
@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Signal d'arrêt partagé par le serveur, le mining et les connexions longues
+///
+/// Le drapeau atomique est consulté par la boucle de mining (thread bloquant),
+/// le canal `watch` réveille les tâches async qui attendent l'arrêt.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    flag: Arc<AtomicBool>,
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            flag: Arc::new(AtomicBool::new(false)),
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Déclencher l'arrêt (idempotent)
+    pub fn trigger(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+        self.sender.send_replace(true);
+    }
+
+    /// L'arrêt a-t-il été demandé ?
+    pub fn is_triggered(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+
+    /// Drapeau d'annulation passé à la boucle de mining
+    pub fn flag(&self) -> &AtomicBool {
+        &self.flag
+    }
+
+    /// S'abonner aux changements d'état (false puis true à l'arrêt)
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.sender.subscribe()
+    }
+
+    /// Attendre que l'arrêt soit déclenché
+    pub async fn wait(&self) {
+        let mut receiver = self.subscribe();
+        // L'erreur est impossible : `self` garde l'émetteur en vie
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Attendre SIGINT (Ctrl-C) ou SIGTERM
+///
+/// # Returns
+/// Le nom du signal reçu
+pub async fn wait_for_signal() -> &'static str {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install the Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install the SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_wait_returns_after_trigger() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait().await }
+        });
+        shutdown.trigger();
+
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter woke up")
+            .unwrap();
+        assert!(shutdown.flag().load(Ordering::SeqCst));
+
+        // Un arrêt déjà déclenché ne bloque pas
+        shutdown.wait().await;
+    }
+}
@@ -0,0 +1,418 @@
+use crate::block::Block;
+use crate::block_store::BlockStore;
+use crate::blockchain::Blockchain;
+use crate::chain_service::ChainHandle;
+use crate::checkpoints::Checkpoints;
+use crate::consensus;
+use crate::events::NodeEvent;
+use crate::shutdown::Shutdown;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Fichier contenant la chaîne dans un répertoire de données
+pub const CHAIN_FILE: &str = "chain.json";
+
+/// Index dérivé hash → hauteur, reconstructible à partir de la chaîne
+pub const HASH_INDEX_FILE: &str = "hash_index.json";
+
+/// Carnet d'adresses des pairs
+pub const PEERS_FILE: &str = "peers.json";
+
+/// Version du format de `chain.json` (et de la réponse de `GET /chain`)
+///
+/// Version 2 : le hash des blocs engage leurs données par la racine de Merkle
+/// et le nombre de leurs transactions. Les fichiers plus anciens, sans champ
+/// `version`, sont en version 1 : leurs hashes ne se vérifient plus et ils
+/// sont refusés au chargement.
+pub const CHAIN_FILE_VERSION: u32 = 2;
+
+/// Délai pendant lequel les nouveaux blocs sont regroupés en une seule
+/// sauvegarde (une synchronisation en ajoute des centaines d'affilée)
+pub const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Erreurs possibles lors de la lecture ou de l'écriture de la chaîne
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("No chain found at {0}")]
+    NotFound(PathBuf),
+
+    #[error("Invalid chain file: {0}")]
+    Format(#[from] serde_json::Error),
+
+    #[error(
+        "{path} uses chain format version {found}, this node only reads version {}: \
+         block hashes changed, sync or import the chain again",
+        CHAIN_FILE_VERSION
+    )]
+    UnsupportedVersion { path: PathBuf, found: u32 },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Format accepté en lecture : une `Blockchain` sérialisée, ou la réponse
+/// de `GET /chain` (qui ne contient pas la difficulté)
+#[derive(Deserialize)]
+struct ChainFile {
+    /// Absent des fichiers écrits avant la version 2
+    #[serde(default)]
+    version: Option<u32>,
+    chain: Vec<Block>,
+    #[serde(default)]
+    difficulty: Option<usize>,
+}
+
+/// Format écrit : la `Blockchain` précédée de la version du format
+#[derive(Serialize)]
+struct VersionedChain<'a> {
+    version: u32,
+    #[serde(flatten)]
+    blockchain: &'a Blockchain,
+}
+
+/// Répertoire de données du nœud (`chain.json` + index dérivés)
+#[derive(Debug, Clone)]
+pub struct DataDir {
+    root: PathBuf,
+}
+
+impl DataDir {
+    /// Ouvrir (sans le créer) un répertoire de données
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn chain_path(&self) -> PathBuf {
+        self.root.join(CHAIN_FILE)
+    }
+
+    pub fn hash_index_path(&self) -> PathBuf {
+        self.root.join(HASH_INDEX_FILE)
+    }
+
+    pub fn peers_path(&self) -> PathBuf {
+        self.root.join(PEERS_FILE)
+    }
+
+    /// Le répertoire contient-il déjà une chaîne ?
+    pub fn exists(&self) -> bool {
+        self.chain_path().is_file()
+    }
+
+    /// Charger la chaîne du répertoire
+    pub fn load(&self) -> Result<Blockchain, StorageError> {
+        read_chain_file(&self.chain_path(), 0)
+    }
+
+    /// Charger la chaîne, ou en créer une nouvelle si le répertoire est vide
+    pub fn load_or_create(&self, difficulty: usize) -> Result<Blockchain, StorageError> {
+        if self.exists() {
+            self.load()
+        } else {
+            let blockchain = Blockchain::new(difficulty);
+            self.save(&blockchain)?;
+            Ok(blockchain)
+        }
+    }
+
+    /// Écrire la chaîne puis reconstruire les index dérivés
+    pub fn save(&self, blockchain: &Blockchain) -> Result<(), StorageError> {
+        fs::create_dir_all(&self.root)?;
+        write_chain_file(&self.chain_path(), blockchain)?;
+        self.reindex(blockchain)?;
+        Ok(())
+    }
+
+    /// Reconstruire l'index hash → hauteur
+    ///
+    /// # Returns
+    /// Le nombre d'entrées de l'index
+    pub fn reindex(&self, blockchain: &Blockchain) -> Result<usize, StorageError> {
+        let index: BTreeMap<&str, u64> = blockchain
+            .chain
+            .iter()
+            .map(|block| (block.hash.as_str(), block.index))
+            .collect();
+        write_atomic(&self.hash_index_path(), &serde_json::to_vec_pretty(&index)?)?;
+        Ok(index.len())
+    }
+}
+
+/// Lire une chaîne depuis un fichier JSON
+///
+/// # Arguments
+/// * `path` - Fichier `chain.json` ou export de `GET /chain`
+/// * `default_difficulty` - Difficulté utilisée si le fichier n'en contient pas
+///
+/// # Returns
+/// `UnsupportedVersion` si le fichier n'est pas en version `CHAIN_FILE_VERSION`
+pub fn read_chain_file(path: &Path, default_difficulty: usize) -> Result<Blockchain, StorageError> {
+    let content = fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => StorageError::NotFound(path.to_path_buf()),
+        _ => StorageError::Io(e),
+    })?;
+    let file: ChainFile = serde_json::from_slice(&content)?;
+    let version = file.version.unwrap_or(1);
+    if version != CHAIN_FILE_VERSION {
+        return Err(StorageError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            found: version,
+        });
+    }
+    if file.chain.is_empty() {
+        return Err(StorageError::NotFound(path.to_path_buf()));
+    }
+
+    Ok(Blockchain {
+        chain: BlockStore::from(file.chain),
+        difficulty: file.difficulty.unwrap_or(default_difficulty),
+        checkpoints: Checkpoints::default(),
+        consensus: consensus::default_engine(),
+    })
+}
+
+/// Écrire une chaîne dans un fichier JSON (remplacement atomique)
+pub fn write_chain_file(path: &Path, blockchain: &Blockchain) -> Result<(), StorageError> {
+    let file = VersionedChain {
+        version: CHAIN_FILE_VERSION,
+        blockchain,
+    };
+    write_atomic(path, &serde_json::to_vec_pretty(&file)?)
+}
+
+/// Lire un fichier JSON
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, StorageError> {
+    let content = fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => StorageError::NotFound(path.to_path_buf()),
+        _ => StorageError::Io(e),
+    })?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+/// Écrire un fichier JSON (remplacement atomique), en créant son répertoire
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, &serde_json::to_vec_pretty(value)?)
+}
+
+/// Écrire dans un fichier temporaire puis le renommer, pour ne jamais laisser
+/// un fichier à moitié écrit
+///
+/// Le fichier temporaire est synchronisé sur le disque avant le renommage, et
+/// son répertoire après : une coupure de courant laisse l'ancien fichier ou
+/// le nouveau, jamais un fichier vide.
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), StorageError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    sync_parent(path)?;
+    Ok(())
+}
+
+/// Synchroniser le répertoire de `path`, qui porte l'entrée renommée
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::File::open(parent)?.sync_all()
+}
+
+/// Un répertoire ne s'ouvre pas comme un fichier hors Unix : le renommage y
+/// est journalisé par le système de fichiers
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Sauvegarder la chaîne après chaque nouveau bloc publié sur le bus
+/// d'événements
+///
+/// Les blocs publiés pendant `SAVE_DEBOUNCE` après le premier sont regroupés
+/// en une seule sauvegarde, écrite sur un thread bloquant. Si des événements
+/// ont été perdus, la chaîne est sauvegardée quand même : c'est toujours
+/// l'état complet qui est écrit. La tâche se termine à l'arrêt du nœud, qui
+/// fait alors lui-même la dernière sauvegarde.
+pub async fn persist_on_events(
+    data_dir: DataDir,
+    chain: ChainHandle,
+    mut events: broadcast::Receiver<NodeEvent>,
+    shutdown: Shutdown,
+) {
+    loop {
+        let event = tokio::select! {
+            event = events.recv() => event,
+            _ = shutdown.wait() => return,
+        };
+        match event {
+            Ok(NodeEvent::BlockAdded { .. }) | Ok(NodeEvent::Reorg { .. }) => {}
+            Ok(_) => continue,
+            Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return,
+        }
+
+        // Laisser arriver les blocs suivants avant d'écrire
+        let debounce = tokio::time::sleep(SAVE_DEBOUNCE);
+        tokio::pin!(debounce);
+        loop {
+            tokio::select! {
+                _ = &mut debounce => break,
+                event = events.recv() => {
+                    if let Err(RecvError::Closed) = event {
+                        break;
+                    }
+                }
+                _ = shutdown.wait() => return,
+            }
+        }
+
+        let snapshot = chain.snapshot();
+        let target = data_dir.clone();
+        let result = tokio::task::spawn_blocking(move || target.save(&snapshot))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e).into()));
+        if let Err(e) = result {
+            tracing::error!(
+                "Failed to save chain to {}: {}",
+                data_dir.root().display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mini-blockchain-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_save_and_load_data_dir() {
+        let dir = temp_dir("storage");
+        let data_dir = DataDir::new(&dir);
+        assert!(!data_dir.exists());
+
+        let mut blockchain = data_dir.load_or_create(1).unwrap();
+        blockchain.add_block("Block 1".to_string()).unwrap();
+        data_dir.save(&blockchain).unwrap();
+
+        let loaded = data_dir.load().unwrap();
+        assert_eq!(loaded.chain, blockchain.chain);
+        assert_eq!(loaded.difficulty, 1);
+
+        let index: BTreeMap<String, u64> =
+            serde_json::from_slice(&fs::read(data_dir.hash_index_path()).unwrap()).unwrap();
+        assert_eq!(index[&blockchain.chain[1].hash], 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bursts_of_blocks_are_saved_once_settled() {
+        let dir = temp_dir("persist");
+        let data_dir = DataDir::new(&dir);
+        let events = crate::events::EventBus::default();
+        let shutdown = Shutdown::new();
+        let chain = ChainHandle::spawn(Blockchain::new(1), events.clone(), shutdown.clone());
+        let persistence = tokio::spawn(persist_on_events(
+            data_dir.clone(),
+            chain.clone(),
+            events.subscribe(),
+            shutdown.clone(),
+        ));
+
+        for i in 1..=3 {
+            chain.append(format!("Block {}", i)).await.unwrap();
+        }
+        // Rien n'est écrit avant la fin de la rafale
+        assert!(!data_dir.exists());
+
+        tokio::time::sleep(SAVE_DEBOUNCE * 3).await;
+        assert_eq!(data_dir.load().unwrap().chain, chain.snapshot().chain);
+        assert!(!dir.join(format!("{}.tmp", CHAIN_FILE)).exists());
+
+        shutdown.trigger();
+        persistence.await.unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_chain_response_export() {
+        let dir = temp_dir("export");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("export.json");
+
+        let blockchain = Blockchain::new(1);
+        let export = serde_json::json!({
+            "version": CHAIN_FILE_VERSION,
+            "chain": blockchain.chain,
+            "length": 1,
+            "is_valid": true,
+        });
+        fs::write(&path, export.to_string()).unwrap();
+
+        let loaded = read_chain_file(&path, 3).unwrap();
+        assert_eq!(loaded.difficulty, 3);
+        assert_eq!(loaded.chain, blockchain.chain);
+
+        assert!(matches!(
+            read_chain_file(&dir.join("missing.json"), 1),
+            Err(StorageError::NotFound(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_chain_files_of_another_version_are_refused() {
+        let dir = temp_dir("version");
+        let data_dir = DataDir::new(&dir);
+        data_dir.save(&Blockchain::new(1)).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_slice(&fs::read(data_dir.chain_path()).unwrap()).unwrap();
+        assert_eq!(saved["version"], CHAIN_FILE_VERSION);
+
+        // Fichier écrit avant l'ajout du champ : version 1
+        let mut legacy = saved.clone();
+        legacy.as_object_mut().unwrap().remove("version");
+        fs::write(data_dir.chain_path(), legacy.to_string()).unwrap();
+        assert!(matches!(
+            data_dir.load(),
+            Err(StorageError::UnsupportedVersion { found: 1, .. })
+        ));
+        // Le fichier n'est pas remplacé par une chaîne neuve
+        assert!(data_dir.load_or_create(1).is_err());
+
+        let mut newer = saved;
+        newer["version"] = (CHAIN_FILE_VERSION + 1).into();
+        fs::write(data_dir.chain_path(), newer.to_string()).unwrap();
+        assert!(matches!(
+            data_dir.load(),
+            Err(StorageError::UnsupportedVersion { found, .. }) if found == CHAIN_FILE_VERSION + 1
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,879 @@
+//! Téléchargement initial de la chaîne, en-têtes d'abord (headers-first)
+//!
+//! 1. Les en-têtes du pair le plus haut sont téléchargés à partir du dernier
+//!    bloc commun et vérifiés : index, liens, points de contrôle, hash et
+//!    sceau du moteur de consensus. L'en-tête porte le nombre et la racine de
+//!    Merkle des données, qui suffisent à recalculer le hash : le Proof of
+//!    Work annoncé est donc réellement fait, sans télécharger les données.
+//! 2. Les corps des blocs sont demandés en parallèle à tous les pairs ; chacun
+//!    doit avoir l'en-tête validé à sa hauteur.
+//! 3. Les blocs sont validés entièrement (`Blockchain::append_block`) sur une
+//!    copie de la chaîne, qui ne remplace la chaîne du nœud qu'une fois plus
+//!    lourde que la branche locale.
+//!
+//! Si les corps d'une branche manquent ou sont refusés, le pair suivant est
+//! essayé. Avec un gestionnaire de pairs (`with_peer_manager`), les pairs
+//! bannis sont ignorés et ceux qui envoient des en-têtes ou des blocs
+//! invalides, ou des en-têtes dont personne ne sert les corps, sont pénalisés,
+//! jusqu'au bannissement.
+
+use crate::api::models::{SyncHeadersResponse, MAX_HEADERS};
+use crate::block::Block;
+use crate::blockchain::{Blockchain, BlockchainError};
+use crate::chain_service::{ChainHandle, ServiceError};
+use crate::events::NodeEvent;
+use crate::peers::{
+    PeerManager, INVALID_BLOCK_PENALTY, MISMATCHED_BLOCK_PENALTY, UNRESOLVED_HEADERS_PENALTY,
+};
+use crate::proof_of_work::PowAlgorithm;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::task::JoinSet;
+use utoipa::ToSchema;
+
+/// Corps de blocs demandés en même temps, tous pairs confondus
+const BODY_WINDOW: u64 = 32;
+
+/// Délai maximal d'une requête à un pair
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Erreurs de la synchronisation
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("No peer is reachable")]
+    NoPeers,
+
+    #[error("Invalid header {height}: {reason}")]
+    InvalidHeader { height: u64, reason: String },
+
+    /// Sceau ou point de contrôle faux : le pair a servi un en-tête forgé
+    #[error("Forged header {height}: {reason}")]
+    ForgedHeader { height: u64, reason: String },
+
+    #[error("Peer {peer} failed: {reason}")]
+    PeerFailed { peer: String, reason: String },
+
+    #[error("No peer served block {0}")]
+    MissingBlock(u64),
+
+    #[error("Block {height} rejected: {error}")]
+    Rejected { height: u64, error: BlockchainError },
+
+    /// Des blocs ont été ajoutés localement pendant le téléchargement
+    #[error("The local chain changed during sync")]
+    ChainMoved,
+
+    #[error(transparent)]
+    Service(#[from] ServiceError),
+}
+
+/// En-tête d'un bloc : tout le bloc sauf ses données, remplacées par leur
+/// nombre et leur racine de Merkle
+///
+/// Suffisant pour vérifier le chaînage, le hash et le sceau ; les données
+/// arrivent avec les corps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SyncHeader {
+    pub index: u64,
+    pub timestamp: DateTime<Utc>,
+    /// Racine de Merkle des transactions (`Block::merkle_root`), en hexadécimal
+    pub merkle_root: String,
+    /// Nombre de transactions (`Block::transaction_count`)
+    pub transactions: usize,
+    pub previous_hash: String,
+    pub hash: String,
+    pub nonce: u64,
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub algorithm: PowAlgorithm,
+}
+
+impl From<&Block> for SyncHeader {
+    fn from(block: &Block) -> Self {
+        Self {
+            index: block.index,
+            timestamp: block.timestamp,
+            merkle_root: hex::encode(block.merkle_root()),
+            transactions: block.transaction_count(),
+            previous_hash: block.previous_hash.clone(),
+            hash: block.hash.clone(),
+            nonce: block.nonce,
+            signature: block.signature.clone(),
+            algorithm: block.algorithm,
+        }
+    }
+}
+
+impl SyncHeader {
+    /// Le hash annoncé est-il celui que donnent les champs de l'en-tête ?
+    pub fn has_valid_hash(&self) -> bool {
+        hex::decode(&self.merkle_root)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .is_some_and(|root| {
+                self.to_block().hash_with_root(&root, self.transactions) == self.hash
+            })
+    }
+
+    /// Bloc sans données portant cet en-tête, pour `ConsensusEngine::verify_seal`
+    pub fn to_block(&self) -> Block {
+        Block {
+            index: self.index,
+            timestamp: self.timestamp,
+            data: String::new(),
+            previous_hash: self.previous_hash.clone(),
+            hash: self.hash.clone(),
+            nonce: self.nonce,
+            signature: self.signature.clone(),
+            algorithm: self.algorithm,
+        }
+    }
+}
+
+/// Vérifier que `headers` prolonge le sommet `(index, hash)`
+///
+/// Une hauteur inattendue ou un lien rompu donnent `InvalidHeader` (le pair
+/// peut suivre une autre branche), un point de contrôle, un hash ou un sceau
+/// faux `ForgedHeader`.
+///
+/// # Arguments
+/// * `bc` - Chaîne locale (points de contrôle, consensus, difficulté)
+/// * `tip` - Index et hash du dernier en-tête déjà accepté
+/// * `headers` - En-têtes suivants, dans l'ordre
+pub fn validate_headers(
+    bc: &Blockchain,
+    tip: (u64, &str),
+    headers: &[SyncHeader],
+) -> Result<(), SyncError> {
+    let (mut index, mut hash) = tip;
+    for header in headers {
+        let invalid = |reason: &str| SyncError::InvalidHeader {
+            height: header.index,
+            reason: reason.to_string(),
+        };
+        let forged = |reason: &str| SyncError::ForgedHeader {
+            height: header.index,
+            reason: reason.to_string(),
+        };
+        if header.index != index + 1 {
+            return Err(invalid("unexpected height"));
+        }
+        if header.previous_hash != hash {
+            return Err(invalid("does not link to the previous header"));
+        }
+        if bc
+            .checkpoints
+            .get(header.index)
+            .is_some_and(|checkpoint| checkpoint != header.hash)
+        {
+            return Err(forged("checkpoint mismatch"));
+        }
+        if !header.has_valid_hash() {
+            return Err(forged("hash does not match the header"));
+        }
+        if !bc.consensus.verify_seal(&header.to_block(), bc.difficulty) {
+            return Err(forged("invalid seal"));
+        }
+        index = header.index;
+        hash = &header.hash;
+    }
+    Ok(())
+}
+
+/// Étape de la synchronisation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    /// Aucune synchronisation lancée (pas de pair configuré)
+    #[default]
+    Idle,
+    /// Téléchargement et vérification des en-têtes
+    Headers,
+    /// Téléchargement des corps et ajout à la chaîne
+    Blocks,
+    /// À jour avec le meilleur pair
+    Synced,
+    /// Abandonnée, voir `error`
+    Failed,
+}
+
+/// Avancement de la synchronisation (GET /sync/status)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SyncStatus {
+    pub phase: SyncPhase,
+    /// Pairs configurés
+    pub peers: Vec<String>,
+    /// Pair dont les en-têtes sont suivis
+    pub best_peer: Option<String>,
+    /// Hauteur de la chaîne locale
+    pub local_height: u64,
+    /// Dernier en-tête validé
+    pub header_height: u64,
+    /// Hauteur annoncée par le meilleur pair
+    pub target_height: u64,
+    pub error: Option<String>,
+}
+
+/// Avancement partagé entre la tâche de synchronisation et l'API
+#[derive(Debug, Clone, Default)]
+pub struct SyncTracker(Arc<Mutex<SyncStatus>>);
+
+impl SyncTracker {
+    /// Copie de l'avancement courant
+    pub fn status(&self) -> SyncStatus {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut SyncStatus)) {
+        f(&mut self.0.lock().unwrap());
+    }
+}
+
+/// Pair joignable, avec la hauteur et le genesis qu'il annonce
+struct RankedPeer {
+    position: usize,
+    height: u64,
+    genesis_hash: String,
+}
+
+/// En-têtes validés auprès d'un pair
+struct HeaderChain {
+    peer: usize,
+    genesis_hash: String,
+    /// Dernier bloc commun avec la chaîne locale
+    fork: u64,
+    headers: Vec<SyncHeader>,
+}
+
+/// API REST d'un pair
+#[derive(Debug, Clone)]
+struct Peer {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl Peer {
+    async fn headers(&self, from: u64, limit: usize) -> Result<SyncHeadersResponse, String> {
+        self.get(&format!("/sync/headers?from={}&limit={}", from, limit))
+            .await
+    }
+
+    async fn block(&self, index: u64) -> Result<Block, String> {
+        self.get(&format!("/blocks/{}", index)).await
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        let response = self
+            .http
+            .get(format!("{}{}", self.url, path))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| e.to_string())?;
+        response.json().await.map_err(|e| e.to_string())
+    }
+}
+
+/// Synchronisation headers-first d'une chaîne auprès de pairs
+pub struct HeadersFirstSync {
+    chain: ChainHandle,
+    peers: Vec<Peer>,
+    tracker: SyncTracker,
+    manager: Option<PeerManager>,
+}
+
+impl HeadersFirstSync {
+    /// Préparer la synchronisation
+    ///
+    /// # Arguments
+    /// * `chain` - Service de la chaîne locale
+    /// * `peers` - URL des API REST des pairs (`http://hôte:port`)
+    /// * `tracker` - Avancement publié sur GET /sync/status
+    pub fn new(chain: ChainHandle, peers: &[String], tracker: SyncTracker) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(PEER_TIMEOUT)
+            .build()
+            .expect("HTTP client");
+        let peers: Vec<Peer> = peers
+            .iter()
+            .map(|url| Peer {
+                url: url.trim_end_matches('/').to_string(),
+                http: http.clone(),
+            })
+            .collect();
+        tracker.update(|status| {
+            status.peers = peers.iter().map(|peer| peer.url.clone()).collect();
+        });
+        Self {
+            chain,
+            peers,
+            tracker,
+            manager: None,
+        }
+    }
+
+    /// Ignorer les pairs bannis et signaler les pairs qui se conduisent mal
+    pub fn with_peer_manager(mut self, manager: PeerManager) -> Self {
+        self.manager = Some(manager);
+        self
+    }
+
+    /// Pénaliser un pair, si un gestionnaire de pairs est configuré
+    fn penalize(&self, url: &str, penalty: u32, reason: &str) {
+        if let Some(manager) = &self.manager {
+            manager.misbehaving(url, penalty, reason);
+        }
+    }
+
+    /// Rattraper le meilleur pair puis s'arrêter
+    ///
+    /// # Returns
+    /// La hauteur locale atteinte
+    pub async fn run(&self) -> Result<u64, SyncError> {
+        let result = self.sync().await;
+        let height = self.chain.snapshot().latest_block().index;
+        self.tracker.update(|status| {
+            status.local_height = height;
+            match &result {
+                Ok(_) => status.phase = SyncPhase::Synced,
+                Err(e) => {
+                    status.phase = SyncPhase::Failed;
+                    status.error = Some(e.to_string());
+                }
+            }
+        });
+        match &result {
+            Ok(_) => tracing::info!("Sync complete at height {}", height),
+            Err(e) => tracing::warn!("Sync stopped at height {}: {}", height, e),
+        }
+        result.map(|_| height)
+    }
+
+    /// Essayer les pairs du plus haut au plus bas jusqu'à en rattraper un
+    ///
+    /// Si les corps d'une branche ne peuvent pas être téléchargés ou sont
+    /// refusés, le pair suivant est essayé ; le pair dont aucun pair n'a servi
+    /// les corps annoncés par ses en-têtes est pénalisé.
+    async fn sync(&self) -> Result<(), SyncError> {
+        self.tracker
+            .update(|status| status.phase = SyncPhase::Headers);
+        let ranked = self.rank_peers().await;
+        if ranked.is_empty() {
+            return Err(SyncError::NoPeers);
+        }
+
+        let mut last_error = None;
+        for candidate in ranked {
+            let bc = self.chain.snapshot();
+            if candidate.height <= bc.latest_block().index {
+                break;
+            }
+            let url = self.peers[candidate.position].url.clone();
+            self.tracker
+                .update(|status| status.phase = SyncPhase::Headers);
+            let best = match self.download_headers(candidate, &bc).await {
+                Ok(Some(best)) => best,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("Dropping headers from {}: {}", url, e);
+                    if matches!(e, SyncError::ForgedHeader { .. }) {
+                        self.penalize(&url, INVALID_BLOCK_PENALTY, &e.to_string());
+                    }
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            self.tracker
+                .update(|status| status.phase = SyncPhase::Blocks);
+            match self.download_branch(best, bc).await {
+                Err(e @ (SyncError::MissingBlock(_) | SyncError::Rejected { .. })) => {
+                    tracing::warn!("Headers from {} did not resolve: {}", url, e);
+                    if let SyncError::MissingBlock(height) = e {
+                        let reason = format!("no peer served block {} of its headers", height);
+                        self.penalize(&url, UNRESOLVED_HEADERS_PENALTY, &reason);
+                    }
+                    last_error = Some(e);
+                }
+                result => return result,
+            }
+        }
+        last_error.map_or(Ok(()), Err)
+    }
+
+    /// Hauteur et genesis annoncés par chaque pair, du plus haut au plus bas
+    ///
+    /// Un nœud neuf (genesis seul) accepte tous les pairs et adoptera le
+    /// genesis du meilleur, comme pour un import ; sinon seuls les pairs de
+    /// même genesis sont retenus.
+    async fn rank_peers(&self) -> Vec<RankedPeer> {
+        let bc = self.chain.snapshot();
+        let mut ranked = Vec::new();
+        for (position, peer) in self.peers.iter().enumerate() {
+            if self.is_banned(&peer.url) {
+                tracing::info!("Peer {} is banned, ignored", peer.url);
+                continue;
+            }
+            match peer.headers(1, 0).await {
+                Ok(page) if bc.len() == 1 || page.genesis_hash == bc.chain[0].hash => {
+                    ranked.push(RankedPeer {
+                        position,
+                        height: page.height,
+                        genesis_hash: page.genesis_hash,
+                    })
+                }
+                Ok(_) => tracing::warn!("Peer {} follows another chain, ignored", peer.url),
+                Err(e) => tracing::warn!("Peer {} is unreachable: {}", peer.url, e),
+            }
+        }
+        ranked.sort_by_key(|peer| std::cmp::Reverse(peer.height));
+        ranked
+    }
+
+    fn is_banned(&self, url: &str) -> bool {
+        self.manager
+            .as_ref()
+            .is_some_and(|manager| manager.is_banned(url))
+    }
+
+    /// Télécharger les en-têtes de `candidate` qui prolongent la chaîne locale
+    ///
+    /// Les en-têtes partent du dernier bloc commun avec le pair (`find_fork`) :
+    /// une branche concurrente plus lourde est retenue, une plus légère
+    /// ignorée.
+    ///
+    /// # Returns
+    /// Les en-têtes validés, None si la branche du pair est plus légère
+    async fn download_headers(
+        &self,
+        candidate: RankedPeer,
+        bc: &Blockchain,
+    ) -> Result<Option<HeaderChain>, SyncError> {
+        let peer = &self.peers[candidate.position];
+        let local = bc.latest_block();
+        // Un nœud neuf part du genesis du pair
+        let tip = if bc.len() == 1 && candidate.genesis_hash != local.hash {
+            (0, candidate.genesis_hash.clone())
+        } else {
+            let fork = self
+                .find_fork(peer, bc, local.index.min(candidate.height))
+                .await?;
+            (fork, bc.chain[fork as usize].hash.clone())
+        };
+        self.tracker.update(|status| {
+            status.best_peer = Some(peer.url.clone());
+            status.target_height = candidate.height;
+            status.header_height = tip.0;
+        });
+        tracing::info!(
+            "Syncing headers {}..={} from {}",
+            tip.0 + 1,
+            candidate.height,
+            peer.url
+        );
+
+        let fork = tip.0;
+        let headers = self.headers_from(peer, bc, tip, candidate.height).await?;
+        // Hauteur annoncée mais aucun en-tête servi
+        if headers.is_empty() {
+            return Err(SyncError::PeerFailed {
+                peer: peer.url.clone(),
+                reason: format!(
+                    "advertised height {} but served no headers",
+                    candidate.height
+                ),
+            });
+        }
+        if !outweighs_local(bc, fork, &headers) {
+            tracing::info!(
+                "Peer {} follows a lighter branch forked at #{}, ignored",
+                peer.url,
+                fork
+            );
+            return Ok(None);
+        }
+        Ok(Some(HeaderChain {
+            peer: candidate.position,
+            genesis_hash: candidate.genesis_hash,
+            fork,
+            headers,
+        }))
+    }
+
+    /// Dernier bloc commun avec le pair, en remontant depuis la hauteur `top`
+    ///
+    /// Les en-têtes du pair sont comparés à la chaîne locale par pages de
+    /// `MAX_HEADERS`, du haut vers le bas.
+    ///
+    /// # Returns
+    /// La hauteur du dernier bloc commun, `PeerFailed` si le pair ne partage
+    /// même pas le genesis
+    async fn find_fork(&self, peer: &Peer, bc: &Blockchain, top: u64) -> Result<u64, SyncError> {
+        let failed = |reason: String| SyncError::PeerFailed {
+            peer: peer.url.clone(),
+            reason,
+        };
+        let mut end = top;
+        loop {
+            let start = end.saturating_sub(MAX_HEADERS as u64 - 1);
+            let page = peer
+                .headers(start, (end - start + 1) as usize)
+                .await
+                .map_err(failed)?;
+            let common = page.headers.iter().rev().find(|header| {
+                header.index >= start
+                    && header.index <= end
+                    && bc
+                        .get_block(header.index)
+                        .is_some_and(|block| block.hash == header.hash)
+            });
+            if let Some(header) = common {
+                return Ok(header.index);
+            }
+            if start == 0 {
+                return Err(failed("shares no block with the local chain".to_string()));
+            }
+            end = start - 1;
+        }
+    }
+
+    /// Télécharger et valider les en-têtes qui suivent `tip`, jusqu'à la
+    /// hauteur `target` annoncée par le pair (les en-têtes au-delà sont ignorés)
+    async fn headers_from(
+        &self,
+        peer: &Peer,
+        bc: &Blockchain,
+        tip: (u64, String),
+        target: u64,
+    ) -> Result<Vec<SyncHeader>, SyncError> {
+        let mut headers: Vec<SyncHeader> = Vec::new();
+        loop {
+            let (index, hash) = headers
+                .last()
+                .map_or((tip.0, tip.1.as_str()), |h| (h.index, h.hash.as_str()));
+            if index >= target {
+                return Ok(headers);
+            }
+            let limit = MAX_HEADERS.min((target - index) as usize);
+            let mut page =
+                peer.headers(index + 1, limit)
+                    .await
+                    .map_err(|reason| SyncError::PeerFailed {
+                        peer: peer.url.clone(),
+                        reason,
+                    })?;
+            if page.headers.is_empty() {
+                return Ok(headers);
+            }
+            page.headers.truncate(limit);
+            validate_headers(bc, (index, hash), &page.headers)?;
+            headers.extend(page.headers);
+
+            let height = headers.last().map_or(index, |h| h.index);
+            self.tracker.update(|status| status.header_height = height);
+            tracing::info!("Validated headers up to #{}", height);
+        }
+    }
+
+    /// Télécharger les corps de la branche `best` et l'installer dès qu'elle
+    /// l'emporte sur la branche locale
+    ///
+    /// Les blocs sont ajoutés à une copie de `base` coupée au fork (avec le
+    /// genesis du pair pour un nœud neuf) ; la chaîne du service n'est
+    /// remplacée (`ChainHandle::commit`) qu'une fois la copie plus lourde que
+    /// la branche locale, puis après chaque lot. Un lot manquant ou refusé
+    /// laisse donc la chaîne locale intacte, ou sur une branche déjà plus
+    /// lourde.
+    ///
+    /// La hauteur `h` est demandée au pair `h mod n`, puis aux suivants en cas
+    /// d'échec ou de corps qui ne correspond pas à l'en-tête. Le pair qui a
+    /// servi un bloc refusé par la validation est pénalisé.
+    ///
+    /// # Arguments
+    /// * `best` - En-têtes validés, à partir du bloc qui suit le fork
+    /// * `base` - Chaîne locale sur laquelle les en-têtes ont été validés
+    async fn download_branch(
+        &self,
+        best: HeaderChain,
+        mut base: Arc<Blockchain>,
+    ) -> Result<(), SyncError> {
+        let headers = best.headers;
+        let Some(first) = headers.first().map(|header| header.index) else {
+            return Ok(());
+        };
+        let last = first + headers.len() as u64 - 1;
+
+        let mut staged = Blockchain::clone(&base);
+        if best.genesis_hash != base.chain[0].hash {
+            let peer = &self.peers[best.peer];
+            let genesis = peer
+                .block(0)
+                .await
+                .ok()
+                .filter(|block| block.hash == best.genesis_hash)
+                .ok_or(SyncError::MissingBlock(0))?;
+            staged
+                .adopt_genesis(genesis)
+                .map_err(|error| SyncError::Rejected { height: 0, error })?;
+            tracing::info!("Adopting genesis {} from {}", best.genesis_hash, peer.url);
+        }
+        staged.truncate(best.fork);
+        let weight = |bc: &Blockchain, from: u64| {
+            bc.chain
+                .iter()
+                .skip(from as usize + 1)
+                .map(|block| bc.consensus.block_weight(block, bc.difficulty))
+                .fold(0u128, u128::saturating_add)
+        };
+        let local_weight = weight(&base, best.fork);
+        // Branche concurrente : publier `Reorg` à la première installation
+        let mut reorg = (best.fork < base.latest_block().index).then_some(best.fork);
+        let mut installed = best.fork;
+
+        let peers: Vec<Peer> = self
+            .peers
+            .iter()
+            .filter(|peer| !self.is_banned(&peer.url))
+            .cloned()
+            .collect();
+        let mut tasks = JoinSet::new();
+        let mut received: BTreeMap<u64, (Block, String)> = BTreeMap::new();
+        let mut next_request = first;
+        let mut next_append = first;
+
+        while next_append <= last {
+            // Garder la fenêtre pleine sans trop d'avance sur les ajouts
+            while next_request <= last && next_request < next_append + BODY_WINDOW {
+                let header = headers[(next_request - first) as usize].clone();
+                tasks.spawn(fetch_block(peers.clone(), header, self.manager.clone()));
+                next_request += 1;
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (block, source) = joined.expect("block download task")?;
+            received.insert(block.index, (block, source));
+
+            let mut batch = Vec::new();
+            let mut sources = BTreeMap::new();
+            while let Some((block, source)) = received.remove(&next_append) {
+                sources.insert(block.index, source);
+                batch.push(block);
+                next_append += 1;
+            }
+            if batch.is_empty() {
+                continue;
+            }
+
+            // Validation complète hors du runtime, sur la copie
+            let (back, result) = tokio::task::spawn_blocking(move || {
+                let result = batch.into_iter().try_for_each(|block| {
+                    let height = block.index;
+                    staged
+                        .append_block(block)
+                        .map(|_| ())
+                        .map_err(|error| SyncError::Rejected { height, error })
+                });
+                (staged, result)
+            })
+            .await
+            .expect("block validation task");
+            staged = back;
+            if let Err(e) = result {
+                if let SyncError::Rejected { height, error } = &e {
+                    if *error != BlockchainError::FutureBlock {
+                        self.penalize(&sources[height], INVALID_BLOCK_PENALTY, &e.to_string());
+                    }
+                    self.chain
+                        .events()
+                        .publish(NodeEvent::block_rejected(*height, error));
+                }
+                return Err(e);
+            }
+            tracing::info!("Validated blocks up to #{} of {}", next_append - 1, last);
+
+            if weight(&staged, best.fork) > local_weight {
+                self.install(&mut base, &staged, installed, reorg.take())
+                    .await?;
+                installed = next_append - 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remplacer la chaîne du service par `staged` si elle n'a pas bougé
+    /// depuis `base`, puis publier `Reorg` et `BlockAdded` pour chaque bloc
+    /// au-dessus de `installed`
+    async fn install(
+        &self,
+        base: &mut Arc<Blockchain>,
+        staged: &Blockchain,
+        installed: u64,
+        reorg: Option<u64>,
+    ) -> Result<(), SyncError> {
+        if !self.chain.commit(base, staged.clone()).await? {
+            return Err(SyncError::ChainMoved);
+        }
+        let tip = staged.latest_block();
+        if let Some(fork) = reorg {
+            let event = NodeEvent::Reorg {
+                fork_height: fork,
+                old_tip: base.latest_block().hash.clone(),
+                new_tip: tip.hash.clone(),
+            };
+            tracing::warn!("Reorganized the chain onto a heavier branch: {:?}", event);
+            self.chain.events().publish(event);
+        }
+        for block in staged.chain.iter().skip(installed as usize + 1) {
+            self.chain.events().publish(NodeEvent::BlockAdded {
+                block: block.clone(),
+            });
+        }
+        let height = tip.index;
+        self.tracker.update(|status| status.local_height = height);
+        *base = Arc::new(staged.clone());
+        Ok(())
+    }
+}
+
+/// La branche de `headers`, greffée sur le bloc `fork`, est-elle plus lourde
+/// que la branche locale au-dessus de `fork` ?
+fn outweighs_local(bc: &Blockchain, fork: u64, headers: &[SyncHeader]) -> bool {
+    let weight = |block: &Block| bc.consensus.block_weight(block, bc.difficulty);
+    let local = bc
+        .chain
+        .iter()
+        .skip(fork as usize + 1)
+        .map(weight)
+        .fold(0u128, u128::saturating_add);
+    let branch = headers
+        .iter()
+        .map(|header| weight(&header.to_block()))
+        .fold(0u128, u128::saturating_add);
+    branch > local
+}
+
+/// Demander le corps d'un bloc à chaque pair tour à tour
+///
+/// # Returns
+/// Le bloc et l'URL du pair qui l'a servi
+async fn fetch_block(
+    peers: Vec<Peer>,
+    header: SyncHeader,
+    manager: Option<PeerManager>,
+) -> Result<(Block, String), SyncError> {
+    if peers.is_empty() {
+        return Err(SyncError::MissingBlock(header.index));
+    }
+    let start = header.index as usize % peers.len();
+    for peer in peers.iter().cycle().skip(start).take(peers.len()) {
+        match peer.block(header.index).await {
+            Ok(block) if SyncHeader::from(&block) == header => {
+                return Ok((block, peer.url.clone()))
+            }
+            Ok(_) => {
+                tracing::warn!(
+                    "Peer {} sent block {} that does not match its header",
+                    peer.url,
+                    header.index
+                );
+                if let Some(manager) = &manager {
+                    let reason = format!("block {} does not match its header", header.index);
+                    manager.misbehaving(&peer.url, MISMATCHED_BLOCK_PENALTY, &reason);
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Peer {} did not serve block {}: {}",
+                peer.url,
+                header.index,
+                e
+            ),
+        }
+    }
+    Err(SyncError::MissingBlock(header.index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(bc: &Blockchain) -> Vec<SyncHeader> {
+        bc.chain.iter().skip(1).map(SyncHeader::from).collect()
+    }
+
+    #[test]
+    fn test_validate_headers() {
+        let mut source = Blockchain::new(2);
+        for i in 1..=3 {
+            source.add_block(format!("Block {}", i)).unwrap();
+        }
+        let local = Blockchain::new(2);
+        let genesis = source.chain[0].hash.clone();
+        let headers = headers(&source);
+
+        assert!(validate_headers(&local, (0, &genesis), &headers).is_ok());
+        assert!(validate_headers(&local, (1, &headers[0].hash), &headers[1..]).is_ok());
+
+        // Trou, mauvais lien, puis hash sans Proof of Work
+        let error = validate_headers(&local, (0, &genesis), &headers[1..]).unwrap_err();
+        assert!(matches!(error, SyncError::InvalidHeader { height: 2, .. }));
+
+        let mut forged = headers.clone();
+        forged[1].previous_hash = genesis.clone();
+
+        assert!(matches!(
+            validate_headers(&local, (0, &genesis), &forged),
+            Err(SyncError::InvalidHeader { height: 2, .. })
+        ));
+
+        // Un hash qui a l'air miné mais que l'en-tête ne donne pas
+        let mut forged = headers.clone();
+        forged[2].hash = format!("00{}", "f".repeat(62));
+        let error = validate_headers(&local, (0, &genesis), &forged).unwrap_err();
+        assert!(matches!(error, SyncError::ForgedHeader { height: 3, .. }));
+        assert!(
+            error.to_string().contains("hash does not match"),
+            "{}",
+            error
+        );
+
+        // Le bon hash d'un bloc qui n'a pas été miné
+        let mut unmined = source.chain[3].clone();
+        while unmined.hash.starts_with("00") {
+            unmined.nonce += 1;
+            unmined.hash = unmined.calculate_hash();
+        }
+        let mut forged = headers.clone();
+        forged[2] = SyncHeader::from(&unmined);
+        let error = validate_headers(&local, (0, &genesis), &forged).unwrap_err();
+        assert!(error.to_string().contains("invalid seal"), "{}", error);
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        let mut bc = Blockchain::new(1);
+        bc.add_block("Payload".to_string()).unwrap();
+        let block = bc.latest_block();
+
+        let header = SyncHeader::from(block);
+        let stripped = header.to_block();
+        assert!(stripped.data.is_empty());
+        assert_eq!(SyncHeader::from(&stripped).hash, header.hash);
+        assert!(bc.consensus.verify_seal(&stripped, bc.difficulty));
+
+        // Le hash se recalcule sans les données, qu'il engage par la racine
+        assert!(header.has_valid_hash());
+        let mut other = header.clone();
+        other.merkle_root = hex::encode(Block::genesis().merkle_root());
+        assert!(!other.has_valid_hash());
+        let mut other = header.clone();
+        other.transactions += 1;
+        assert!(!other.has_valid_hash());
+    }
+}
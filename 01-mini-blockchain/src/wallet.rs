@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Erreurs possibles lors de la manipulation d'un wallet
+#[derive(Error, Debug)]
+pub enum WalletError {
+    #[error("Invalid secret key")]
+    InvalidSecretKey,
+
+    #[error("Invalid wallet file: {0}")]
+    InvalidFile(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Portefeuille : une paire de clés ECDSA secp256k1
+#[derive(Debug, Clone)]
+pub struct Wallet {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+}
+
+/// Format du fichier de wallet (JSON)
+#[derive(Debug, Serialize, Deserialize)]
+struct WalletFile {
+    secret_key: String,
+    public_key: String,
+    address: String,
+}
+
+impl Wallet {
+    /// Générer un nouveau wallet avec une clé aléatoire
+    pub fn generate() -> Self {
+        let secp = Secp256k1::new();
+        let (secret_key, public_key) = secp.generate_keypair(&mut secp256k1::rand::thread_rng());
+        Self {
+            secret_key,
+            public_key,
+        }
+    }
+
+    /// Restaurer un wallet depuis sa clé secrète hexadécimale
+    pub fn from_secret_hex(secret: &str) -> Result<Self, WalletError> {
+        let secret_key = SecretKey::from_str(secret).map_err(|_| WalletError::InvalidSecretKey)?;
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key);
+        Ok(Self {
+            secret_key,
+            public_key,
+        })
+    }
+
+    /// Clé secrète en hexadécimal (à ne jamais partager)
+    pub fn secret_hex(&self) -> String {
+        hex::encode(self.secret_key.secret_bytes())
+    }
+
+    /// Clé publique compressée en hexadécimal (66 caractères)
+    pub fn public_key_hex(&self) -> String {
+        self.public_key.to_string()
+    }
+
+    /// Adresse du wallet : 20 premiers octets du SHA-256 de la clé publique
+    pub fn address(&self) -> String {
+        address_of(&self.public_key_hex()).expect("valid public key")
+    }
+
+    /// Signer un message (le SHA-256 du message est signé)
+    ///
+    /// # Returns
+    /// Signature compacte en hexadécimal (128 caractères)
+    pub fn sign(&self, message: &[u8]) -> String {
+        let digest = Message::from_digest(sha256(message));
+        let signature = Secp256k1::new().sign_ecdsa(&digest, &self.secret_key);
+        hex::encode(signature.serialize_compact())
+    }
+
+    /// Sauvegarder le wallet dans un fichier JSON, en écrasant le fichier
+    /// existant
+    ///
+    /// Sous Unix, le fichier n'est lisible que par son propriétaire (0600),
+    /// y compris s'il existait déjà avec des droits plus larges.
+    pub fn save(&self, path: &Path) -> Result<(), WalletError> {
+        let file = WalletFile {
+            secret_key: self.secret_hex(),
+            public_key: self.public_key_hex(),
+            address: self.address(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| WalletError::InvalidFile(e.to_string()))?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            let file = options.open(path)?;
+            // `mode` ne s'applique qu'à la création : restreindre aussi un
+            // fichier existant, avant d'y écrire la clé
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            write_secret(file, json.as_bytes())
+        }
+        #[cfg(not(unix))]
+        write_secret(options.open(path)?, json.as_bytes())
+    }
+
+    /// Charger un wallet depuis un fichier JSON
+    pub fn load(path: &Path) -> Result<Self, WalletError> {
+        let json = std::fs::read_to_string(path)?;
+        let file: WalletFile =
+            serde_json::from_str(&json).map_err(|e| WalletError::InvalidFile(e.to_string()))?;
+        Self::from_secret_hex(&file.secret_key)
+    }
+}
+
+/// Vérifier une signature produite par `Wallet::sign`
+///
+/// # Arguments
+/// * `public_key` - Clé publique compressée en hexadécimal
+/// * `message` - Message signé
+/// * `signature` - Signature compacte en hexadécimal
+pub fn verify_signature(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let Ok(public_key) = PublicKey::from_str(public_key) else {
+        return false;
+    };
+    let Some(signature) = hex::decode(signature)
+        .ok()
+        .and_then(|bytes| Signature::from_compact(&bytes).ok())
+    else {
+        return false;
+    };
+    let digest = Message::from_digest(sha256(message));
+    Secp256k1::verification_only()
+        .verify_ecdsa(&digest, &signature, &public_key)
+        .is_ok()
+}
+
+/// Calculer l'adresse associée à une clé publique hexadécimale
+pub fn address_of(public_key: &str) -> Option<String> {
+    let public_key = PublicKey::from_str(public_key).ok()?;
+    Some(hex::encode(&sha256(&public_key.serialize())[..20]))
+}
+
+fn write_secret(mut file: std::fs::File, content: &[u8]) -> Result<(), WalletError> {
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Transfert signé, stocké comme données d'un bloc
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignedTransfer {
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub timestamp: DateTime<Utc>,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl SignedTransfer {
+    /// Créer et signer un transfert depuis `wallet` vers l'adresse `to`
+    pub fn new(wallet: &Wallet, to: String, amount: u64) -> Self {
+        let mut transfer = Self {
+            from: wallet.address(),
+            to,
+            amount,
+            timestamp: Utc::now(),
+            public_key: wallet.public_key_hex(),
+            signature: String::new(),
+        };
+        transfer.signature = wallet.sign(transfer.signing_payload().as_bytes());
+        transfer
+    }
+
+    /// Données couvertes par la signature
+    fn signing_payload(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            self.from,
+            self.to,
+            self.amount,
+            self.timestamp.to_rfc3339()
+        )
+    }
+
+    /// Vérifier la signature et que `from` correspond à la clé publique
+    pub fn verify(&self) -> bool {
+        address_of(&self.public_key).as_deref() == Some(self.from.as_str())
+            && verify_signature(
+                &self.public_key,
+                self.signing_payload().as_bytes(),
+                &self.signature,
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let wallet = Wallet::generate();
+        let signature = wallet.sign(b"hello");
+
+        assert!(verify_signature(
+            &wallet.public_key_hex(),
+            b"hello",
+            &signature
+        ));
+        assert!(!verify_signature(
+            &wallet.public_key_hex(),
+            b"other",
+            &signature
+        ));
+        assert!(!verify_signature(
+            &Wallet::generate().public_key_hex(),
+            b"hello",
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_restore_from_secret() {
+        let wallet = Wallet::generate();
+        let restored = Wallet::from_secret_hex(&wallet.secret_hex()).unwrap();
+
+        assert_eq!(restored.address(), wallet.address());
+        assert_eq!(wallet.address().len(), 40);
+        assert!(Wallet::from_secret_hex("not hex").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "mini-blockchain-wallet-{}.json",
+            std::process::id()
+        ));
+        // Un fichier existant lisible par tous est écrasé et restreint
+        std::fs::write(&path, "{}").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        }
+
+        let wallet = Wallet::generate();
+        wallet.save(&path).unwrap();
+        assert_eq!(Wallet::load(&path).unwrap().address(), wallet.address());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_signed_transfer() {
+        let wallet = Wallet::generate();
+        let mut transfer = SignedTransfer::new(&wallet, "recipient".to_string(), 42);
+        assert!(transfer.verify());
+
+        transfer.amount = 1_000;
+        assert!(!transfer.verify());
+    }
+}
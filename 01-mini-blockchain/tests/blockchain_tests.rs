@@ -1,3 +1,4 @@
+#[allow(unused_imports)] // Pour les tests encore à écrire
 use mini_blockchain::{Block, Blockchain};
 
 #[test]
@@ -0,0 +1,111 @@
+// Tests du client en ligne de commande `bc-cli`, lancé comme un processus
+// contre un wallet local et contre un nœud démarré sur un port libre.
+
+use mini_blockchain::api::{self, AppState};
+use mini_blockchain::config::NodeConfig;
+use mini_blockchain::Blockchain;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use tokio::net::TcpListener;
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "mini-blockchain-cli-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn bc_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bc-cli"))
+        .args(args)
+        .env_remove("BC_NODE")
+        .env_remove("BC_API_KEY")
+        .env_remove("BC_WALLET")
+        .output()
+        .expect("bc-cli runs")
+}
+
+fn json(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("JSON output")
+}
+
+#[test]
+fn test_wallet_round_trip() {
+    let path = temp_path("wallet.json");
+    let wallet = path.to_str().unwrap();
+
+    let created = json(&bc_cli(&[
+        "--wallet", wallet, "-o", "json", "wallet", "new",
+    ]));
+    let shown = json(&bc_cli(&[
+        "--wallet", wallet, "-o", "json", "wallet", "show",
+    ]));
+    assert_eq!(shown["address"], created["address"]);
+    assert_eq!(shown["public_key"], created["public_key"]);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // Le wallet existant n'est remplacé qu'avec --force
+    assert!(!bc_cli(&["--wallet", wallet, "wallet", "new"])
+        .status
+        .success());
+    let replaced = json(&bc_cli(&[
+        "--wallet", wallet, "-o", "json", "wallet", "new", "--force",
+    ]));
+    assert_ne!(replaced["address"], created["address"]);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_add_and_read_blocks_from_a_node() {
+    let state = AppState::new(Blockchain::new(1));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let node = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn({
+        let state = state.clone();
+        async move { api::serve(listener, state, &NodeConfig::default()).await }
+    });
+
+    let run = |args: Vec<String>| {
+        tokio::task::spawn_blocking(move || {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            bc_cli(&args)
+        })
+    };
+    let args = |command: &[&str]| {
+        ["--node", node.as_str(), "-o", "json"]
+            .iter()
+            .chain(command)
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let added = json(&run(args(&["add", "Block from the CLI"])).await.unwrap());
+    assert_eq!(added["block"]["index"], 1);
+    assert_eq!(added["block"]["data"], "Block from the CLI");
+
+    let block = json(&run(args(&["block", "1"])).await.unwrap());
+    assert_eq!(block["hash"], added["block"]["hash"]);
+    assert_eq!(state.chain.snapshot().len(), 2);
+
+    // Une erreur du nœud donne un code de sortie non nul
+    let missing = run(args(&["block", "42"])).await.unwrap();
+    assert!(!missing.status.success());
+
+    state.shutdown.trigger();
+}
@@ -0,0 +1,53 @@
+// Vérifie que la spécification OpenAPI commitée (openapi.json) correspond
+// à celle générée depuis les handlers. Pour la régénérer :
+//   UPDATE_OPENAPI=1 cargo test --test openapi_tests
+
+use mini_blockchain::api::openapi::ApiDoc;
+use utoipa::OpenApi;
+
+const SPEC_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+#[test]
+fn test_openapi_spec_has_not_drifted() {
+    let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+
+    if std::env::var_os("UPDATE_OPENAPI").is_some() {
+        std::fs::write(SPEC_PATH, &generated).unwrap();
+        return;
+    }
+
+    let committed = std::fs::read_to_string(SPEC_PATH)
+        .expect("openapi.json is missing, run: UPDATE_OPENAPI=1 cargo test --test openapi_tests");
+    assert!(
+        committed == generated,
+        "openapi.json is out of date, run: UPDATE_OPENAPI=1 cargo test --test openapi_tests"
+    );
+}
+
+#[test]
+fn test_openapi_spec_documents_all_routes() {
+    let spec = ApiDoc::openapi();
+    for path in [
+        "/chain",
+        "/blocks",
+        "/blocks/{index}",
+        "/blocks/hash/{hash}",
+        "/blocks/{index}/proof/{position}",
+        "/headers",
+        "/sync/headers",
+        "/sync/status",
+        "/peers",
+        "/peers/{id}",
+        "/peers/addr",
+        "/peers/blocks",
+        "/orphans",
+        "/validate",
+        "/export",
+        "/import",
+        "/rpc",
+        "/events",
+        "/ws",
+    ] {
+        assert!(spec.paths.paths.contains_key(path), "missing path {}", path);
+    }
+}
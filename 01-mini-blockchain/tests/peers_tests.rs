@@ -0,0 +1,237 @@
+// Tests de la découverte de pairs entre nœuds réels (API REST servie sur des
+// ports locaux)
+
+use axum::body::{to_bytes, Body};
+use axum::extract::connect_info::MockConnectInfo;
+use axum::http::{Request, StatusCode};
+use mini_blockchain::api::{self, AppState, PeersResponse};
+use mini_blockchain::config::NodeConfig;
+use mini_blockchain::peers::{Direction, PeerConfig, PeerManager};
+use mini_blockchain::{Block, Blockchain, NodeEvent};
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+
+/// Servir un nœud qui annonce son URL et part de `seeds`
+async fn spawn_node(seeds: Vec<String>) -> (String, PeerManager) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let peers = PeerManager::new(PeerConfig {
+        seeds,
+        public_url: Some(url.clone()),
+        ..PeerConfig::default()
+    });
+    let state = AppState::new(Blockchain::new(1)).with_peers(peers.clone());
+    tokio::spawn(async move { api::serve(listener, state, &NodeConfig::default()).await });
+    (url, peers)
+}
+
+/// Envoyer une requête au routeur, comme depuis 127.0.0.1
+async fn send(state: &AppState, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let app = api::router(state.clone(), &NodeConfig::default())
+        .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 50_000))));
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_addresses_spread_by_gossip() {
+    // a <- b <- c : c ne connaît que b, et découvre a par l'échange `addr`
+    let (a, a_peers) = spawn_node(Vec::new()).await;
+    let (b, b_peers) = spawn_node(vec![a.clone()]).await;
+    let (_, c_peers) = spawn_node(vec![b.clone()]).await;
+
+    b_peers.maintain().await;
+    assert_eq!(b_peers.outbound_urls(), vec![a.clone()]);
+    assert_eq!(a_peers.connections()[0].url, b);
+    assert_eq!(a_peers.connections()[0].direction, Direction::Inbound);
+
+    c_peers.maintain().await;
+    let mut outbound = c_peers.outbound_urls();
+    outbound.sort();
+    let mut expected = vec![a.clone(), b.clone()];
+    expected.sort();
+    assert_eq!(outbound, expected);
+    assert_eq!(a_peers.connections().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_manual_peer_control() {
+    let (remote, _) = spawn_node(Vec::new()).await;
+    let state = AppState::new(Blockchain::new(1));
+
+    let request = Request::post("/peers")
+        .header("content-type", "application/json")
+        .body(Body::from(format!(r#"{{"url": "{}"}}"#, remote)))
+        .unwrap();
+    let (status, peer) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(peer["url"], remote.as_str());
+    assert_eq!(peer["direction"], "outbound");
+    let id = peer["id"].as_u64().unwrap();
+
+    let request = Request::post("/peers")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"url": "http://127.0.0.1:9"}"#))
+        .unwrap();
+    let (status, error) = send(&state, request).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(error["code"], "peer_unreachable");
+
+    let request = Request::delete(format!("/peers/{}?ban=true", id))
+        .body(Body::empty())
+        .unwrap();
+    let (status, _) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let request = Request::get("/peers").body(Body::empty()).unwrap();
+    let (status, body) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK);
+    let peers: PeersResponse = serde_json::from_value(body).unwrap();
+    assert!(peers.connections.is_empty());
+    assert_eq!(peers.banned[0].url, remote);
+    assert_eq!(peers.known_addresses, 2);
+
+    let request = Request::delete(format!("/peers/{}", id))
+        .body(Body::empty())
+        .unwrap();
+    let (status, error) = send(&state, request).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["code"], "peer_not_found");
+
+    // Un pair banni ne peut plus se présenter
+    let request = Request::post("/peers/addr")
+        .header("content-type", "application/json")
+        .body(Body::from(format!(
+            r#"{{"from": "{}", "addresses": []}}"#,
+            remote
+        )))
+        .unwrap();
+    let (status, error) = send(&state, request).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(error["code"], "peer_banned");
+
+    // L'ajout manuel lève le bannissement
+    let request = Request::post("/peers")
+        .header("content-type", "application/json")
+        .body(Body::from(format!(r#"{{"url": "{}"}}"#, remote)))
+        .unwrap();
+    let (status, _) = send(&state, request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(state.peers.banned().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_orphans_wait_for_parents_requested_from_outbound_peers() {
+    let mut remote = Blockchain::new(1);
+    for i in 1..=3 {
+        remote.add_block(format!("Block {}", i)).unwrap();
+    }
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let remote_url = format!("http://{}", listener.local_addr().unwrap());
+    let remote_state = AppState::new(remote.clone());
+    tokio::spawn(async move { api::serve(listener, remote_state, &NodeConfig::default()).await });
+
+    let mut local = remote.clone();
+    local.truncate(0);
+    let state = AppState::new(local);
+    let relay = |block: &Block, from: Option<&str>| {
+        let body = serde_json::json!({ "from": from, "block": block });
+        Request::post("/peers/blocks")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    // Bloc 3 sans émetteur : il attend, personne à qui demander son parent
+    let (status, body) = send(&state, relay(&remote.chain[3], None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "orphaned");
+    assert_eq!(body["missing_parent"], remote.chain[2].hash.as_str());
+
+    let request = Request::get("/orphans").body(Body::empty()).unwrap();
+    let (_, body) = send(&state, request).await;
+    assert_eq!(body["count"], 1);
+    assert_eq!(body["orphans"][0]["hash"], remote.chain[3].hash.as_str());
+
+    // Bloc 2 relayé par un nœud auquel on n'est pas connecté : son URL n'est
+    // pas contactée
+    let (_, body) = send(&state, relay(&remote.chain[2], Some(&remote_url))).await;
+    assert_eq!(body["status"], "orphaned");
+    assert_eq!(body["missing_parent"], remote.chain[1].hash.as_str());
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert_eq!(state.chain.snapshot().len(), 1);
+    assert_eq!(state.orphans.len(), 2);
+
+    // Une fois le pair sortant, le bloc 1 lui est demandé, puis 2 et 3 suivent
+    state.peers.add_manual(&remote_url).await.unwrap();
+    state
+        .orphans
+        .request_parents(
+            &state.chain,
+            &state.peers,
+            &remote.chain[3].hash,
+            Some(remote_url.clone()),
+        )
+        .await;
+    for _ in 0..100 {
+        if state.chain.snapshot().len() == 4 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(state.chain.snapshot().chain, remote.chain);
+    assert!(state.orphans.is_empty());
+
+    let (_, body) = send(&state, relay(&remote.chain[3], None)).await;
+    assert_eq!(body["status"], "duplicate");
+
+    // Un bloc au hash faux est refusé ; l'émetteur n'est banni que si son
+    // URL désigne l'adresse de la connexion
+    let mut tampered = remote.chain[3].clone();
+    tampered.index = 4;
+    tampered.previous_hash = remote.chain[3].hash.clone();
+    tampered.hash = "0".repeat(64);
+    let mut events = state.events.subscribe();
+    let (status, error) = send(&state, relay(&tampered, Some("http://10.0.0.9:8090"))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error["code"], "invalid_hash");
+    assert!(state.peers.banned().is_empty());
+    assert!(matches!(
+        events.try_recv(),
+        Ok(NodeEvent::ValidationFailed {
+            block_index: Some(4),
+            ..
+        })
+    ));
+
+    let (status, _) = send(&state, relay(&tampered, Some("http://127.0.0.1:8091"))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(state.peers.is_banned("http://127.0.0.1:8091"));
+}
+
+#[tokio::test]
+async fn test_forged_sender_is_not_registered() {
+    let state = AppState::new(Blockchain::new(1));
+    let addr = |from: &str| {
+        Request::post("/peers/addr")
+            .header("content-type", "application/json")
+            .body(Body::from(format!(
+                r#"{{"from": "{}", "addresses": ["http://10.0.0.7:8090"]}}"#,
+                from
+            )))
+            .unwrap()
+    };
+
+    // L'URL d'un autre hôte ne prend pas de place entrante
+    let (status, _) = send(&state, addr("http://10.0.0.9:8090")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(state.peers.connections().is_empty());
+    assert_eq!(state.peers.known_addresses(), 1);
+
+    let (status, _) = send(&state, addr("http://127.0.0.1:8091")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(state.peers.connections()[0].url, "http://127.0.0.1:8091");
+}
@@ -0,0 +1,66 @@
+// Tests de l'arrêt gracieux : un arrêt pendant le mining doit laisser
+// une chaîne cohérente et le serveur doit se terminer rapidement.
+
+use mini_blockchain::api::{self, AppState};
+use mini_blockchain::config::NodeConfig;
+use mini_blockchain::Blockchain;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_shutdown_during_add_block_keeps_chain_consistent() {
+    let mut blockchain = Blockchain::new(1);
+    blockchain.add_block("Block 1".to_string()).unwrap();
+    let before = blockchain.chain.clone();
+
+    // Difficulté inatteignable : le mining ne se termine que par l'arrêt
+    blockchain.difficulty = 64;
+    let state = AppState::new(blockchain);
+    let shutdown = state.shutdown.clone();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn({
+        let state = state.clone();
+        async move { api::serve(listener, state, &NodeConfig::default()).await }
+    });
+
+    let client = reqwest::Client::new();
+
+    // Un abonné SSE ne doit pas empêcher l'arrêt
+    let events = client.get(format!("{}/events", base)).send().await.unwrap();
+    assert!(events.status().is_success());
+
+    let mining = tokio::spawn({
+        let client = client.clone();
+        let base = base.clone();
+        async move {
+            client
+                .post(format!("{}/blocks", base))
+                .json(&serde_json::json!({ "data": "never mined" }))
+                .send()
+                .await
+        }
+    });
+
+    // Laisser le mining démarrer puis arrêter le nœud
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    shutdown.trigger();
+
+    let response = mining.await.unwrap().unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "mining_cancelled");
+
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server stopped after shutdown")
+        .unwrap()
+        .unwrap();
+
+    let mut chain = (*state.chain.snapshot()).clone();
+    assert_eq!(chain.chain, before);
+    // Les blocs conservés ont été minés en difficulté 1
+    chain.difficulty = 1;
+    assert!(chain.validate_detailed().is_empty());
+}
@@ -0,0 +1,355 @@
+// Tests de la synchronisation headers-first entre nœuds réels (API REST
+// servie sur des ports locaux)
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Path, Query};
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{Json, Router};
+use mini_blockchain::api::{self, AppState, SyncHeadersResponse};
+use mini_blockchain::config::NodeConfig;
+use mini_blockchain::consensus::ProofOfWork;
+use mini_blockchain::peers::{PeerConfig, PeerManager};
+use mini_blockchain::proof_of_work::PowAlgorithm;
+use mini_blockchain::sync::{HeadersFirstSync, SyncError, SyncHeader, SyncPhase, SyncStatus};
+use mini_blockchain::{Block, Blockchain, NodeEvent};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+
+/// Servir `blockchain` sur un port libre et retourner l'URL du nœud
+async fn spawn_node(blockchain: Blockchain) -> String {
+    spawn_node_on("127.0.0.1", blockchain).await
+}
+
+/// Comme `spawn_node`, sur l'adresse de boucle locale `host` : les
+/// bannissements valent pour tout l'hôte
+async fn spawn_node_on(host: &str, blockchain: Blockchain) -> String {
+    serve_state(host, AppState::new(blockchain)).await
+}
+
+/// Servir l'API d'un nœud déjà créé sur l'adresse `host`
+async fn serve_state(host: &str, state: AppState) -> String {
+    let listener = TcpListener::bind((host, 0)).await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { api::serve(listener, state, &NodeConfig::default()).await });
+    url
+}
+
+/// Servir un pair qui annonce la hauteur `advertised` mais renvoie tous les
+/// en-têtes de `blockchain` à partir de `from`, sans respecter `limit`
+async fn spawn_lying_node(blockchain: Blockchain, advertised: u64) -> String {
+    let headers_chain = blockchain.clone();
+    let app = Router::new()
+        .route(
+            "/sync/headers",
+            get(
+                move |Query(query): Query<HashMap<String, u64>>| async move {
+                    let from = query["from"];
+                    Json(SyncHeadersResponse {
+                        genesis_hash: headers_chain.chain[0].hash.clone(),
+                        height: advertised,
+                        from,
+                        headers: headers_chain
+                            .chain
+                            .iter()
+                            .skip(from as usize)
+                            .map(SyncHeader::from)
+                            .collect(),
+                    })
+                },
+            ),
+        )
+        .route(
+            "/blocks/:index",
+            get(move |Path(index): Path<u64>| async move {
+                Json::<Block>(blockchain.get_block(index).unwrap().clone())
+            }),
+        );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    url
+}
+
+/// Servir les en-têtes de `blockchain` mais seulement les corps jusqu'à la
+/// hauteur `served`
+async fn spawn_partial_node(blockchain: Blockchain, served: u64) -> String {
+    let headers_chain = blockchain.clone();
+    let app = Router::new()
+        .route(
+            "/sync/headers",
+            get(
+                move |Query(query): Query<HashMap<String, u64>>| async move {
+                    let from = query["from"];
+                    Json(SyncHeadersResponse {
+                        genesis_hash: headers_chain.chain[0].hash.clone(),
+                        height: headers_chain.latest_block().index,
+                        from,
+                        headers: headers_chain
+                            .chain
+                            .iter()
+                            .skip(from as usize)
+                            .take(query["limit"] as usize)
+                            .map(SyncHeader::from)
+                            .collect(),
+                    })
+                },
+            ),
+        )
+        .route(
+            "/blocks/:index",
+            get(move |Path(index): Path<u64>| async move {
+                match blockchain.get_block(index) {
+                    Some(block) if index <= served => Ok(Json(block.clone())),
+                    _ => Err(StatusCode::NOT_FOUND),
+                }
+            }),
+        );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    url
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_new_node_catches_up_from_several_peers() {
+    let mut source = Blockchain::new(1);
+    for i in 1..=40 {
+        source.add_block(format!("Block {}", i)).unwrap();
+    }
+    let peers = vec![
+        spawn_node(source.clone()).await,
+        spawn_node(source.clone()).await,
+        // Pair injoignable : ignoré
+        "http://127.0.0.1:9".to_string(),
+    ];
+
+    let state = AppState::new(Blockchain::new(1));
+    let mut events = state.events.subscribe();
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone());
+
+    assert_eq!(sync.run().await.unwrap(), 40);
+    assert_eq!(state.chain.snapshot().chain, source.chain);
+    assert!(matches!(
+        events.try_recv(),
+        Ok(NodeEvent::BlockAdded { block }) if block.index == 1
+    ));
+
+    let app = api::router(state.clone(), &NodeConfig::default());
+    let request = Request::get("/sync/status").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let status: SyncStatus = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status.phase, SyncPhase::Synced);
+    assert_eq!(status.local_height, 40);
+    assert_eq!(status.header_height, 40);
+    assert_eq!(status.target_height, 40);
+    assert_eq!(status.peers.len(), 3);
+
+    // Déjà à jour : rien à télécharger
+    assert_eq!(sync.run().await.unwrap(), 40);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_sync_skips_peer_with_invalid_headers() {
+    let mut honest = Blockchain::new(1);
+    for i in 1..=3 {
+        honest.add_block(format!("Block {}", i)).unwrap();
+    }
+
+    // Chaîne plus longue mais sans Proof of Work valide (un hash sur seize
+    // remplit la difficulté 1 par hasard : on en tire un autre)
+    let mut forged = honest.clone();
+    forged.add_block("Block 4".to_string()).unwrap();
+    forged.difficulty = 0;
+    for attempt in 0.. {
+        let cheap = forged
+            .add_block(format!("Cheap block {}", attempt))
+            .unwrap();
+        if !cheap.hash.starts_with('0') {
+            break;
+        }
+        forged.truncate(4);
+    }
+
+    let peers = vec![spawn_node(forged).await, spawn_node(honest.clone()).await];
+    let state = AppState::new(Blockchain::new(1));
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone());
+
+    assert_eq!(sync.run().await.unwrap(), 3);
+    assert_eq!(state.chain.snapshot().chain, honest.chain);
+    assert_eq!(state.sync.status().best_peer.as_ref(), Some(&peers[1]));
+
+    // Sans pair joignable, la synchronisation échoue
+    let sync = HeadersFirstSync::new(
+        state.chain.clone(),
+        &["http://127.0.0.1:9".to_string()],
+        state.sync.clone(),
+    );
+    assert!(sync.run().await.is_err());
+    assert_eq!(state.sync.status().phase, SyncPhase::Failed);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_peer_serving_invalid_blocks_is_banned() {
+    // Chaîne à la Bitcoin : l'en-tête n'engage que la racine de Merkle
+    let bitcoin_chain =
+        || Blockchain::new(1).with_consensus(Arc::new(ProofOfWork::new(PowAlgorithm::Sha256d)));
+    let mut honest = bitcoin_chain();
+    for i in 1..=4 {
+        honest.add_block(format!("Block {}\nFee {}", i, i)).unwrap();
+    }
+
+    // En-têtes identiques, mais le corps du bloc 2 duplique sa dernière
+    // transaction : même racine de Merkle et même hash, données refusées.
+    // Le bloc est altéré une fois le service démarré, qui tronquerait sinon
+    // la chaîne au dernier bloc valide
+    let tampered = AppState::new(honest.clone());
+    let forged = tampered
+        .chain
+        .write(|bc| {
+            let block = bc.chain.get_mut(2).unwrap();
+            block.data = "Block 2\nFee 2\nFee 2".to_string();
+            block.calculate_hash()
+        })
+        .await
+        .unwrap();
+    assert_eq!(forged, honest.chain[2].hash);
+
+    let peers = vec![
+        serve_state("127.0.0.1", tampered).await,
+        spawn_node_on("127.0.0.2", honest.clone()).await,
+    ];
+    let state = AppState::new(bitcoin_chain());
+    let mut events = state.events.subscribe();
+    let manager = PeerManager::new(PeerConfig::default());
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone())
+        .with_peer_manager(manager.clone());
+
+    // Le bloc 2 est demandé en premier au pair 2 mod 2 = 0, qui est banni ;
+    // la synchronisation reprend avec le pair suivant
+    assert_eq!(sync.run().await.unwrap(), 4);
+    assert_eq!(state.chain.snapshot().chain, honest.chain);
+    assert!(manager.is_banned(&peers[0]));
+    assert!(!manager.is_banned(&peers[1]));
+    let rejected = std::iter::from_fn(|| events.try_recv().ok()).find(|event| {
+        matches!(
+            event,
+            NodeEvent::ValidationFailed {
+                block_index: Some(2),
+                ..
+            }
+        )
+    });
+    assert!(rejected.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_unserved_branch_keeps_the_local_chain() {
+    let mut common = Blockchain::new(1);
+    for i in 1..=2 {
+        common.add_block(format!("Block {}", i)).unwrap();
+    }
+    let mut local = common.clone();
+    for i in 3..=40 {
+        local.add_block(format!("Local {}", i)).unwrap();
+    }
+    let mut remote = common.clone();
+    for i in 3..=45 {
+        remote.add_block(format!("Remote {}", i)).unwrap();
+    }
+
+    // Le pair annonce sa branche plus lourde mais ne sert que le premier lot
+    // de corps : le second n'arrive jamais
+    let peers = vec![spawn_partial_node(remote, 2 + 32).await];
+    let state = AppState::new(local.clone());
+    let mut events = state.events.subscribe();
+    let manager = PeerManager::new(PeerConfig::default());
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone())
+        .with_peer_manager(manager.clone());
+
+    assert!(matches!(
+        sync.run().await,
+        Err(SyncError::MissingBlock(height)) if height > 34
+    ));
+    assert_eq!(state.chain.snapshot().chain, local.chain);
+    assert!(events.try_recv().is_err());
+
+    // Le pair dont les en-têtes ne se résolvent jamais est pénalisé, puis banni
+    assert!(!manager.is_banned(&peers[0]));
+    assert!(sync.run().await.is_err());
+    assert!(manager.is_banned(&peers[0]));
+    assert_eq!(state.chain.snapshot().chain, local.chain);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_sync_stops_at_advertised_height() {
+    let mut source = Blockchain::new(1);
+    for i in 1..=4 {
+        source.add_block(format!("Block {}", i)).unwrap();
+    }
+
+    // Les en-têtes au-delà de la hauteur annoncée sont ignorés
+    let peers = vec![spawn_lying_node(source.clone(), 2).await];
+    let state = AppState::new(Blockchain::new(1));
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone());
+    assert_eq!(sync.run().await.unwrap(), 2);
+    let mut expected = source.clone();
+    expected.truncate(2);
+    assert_eq!(state.chain.snapshot().chain, expected.chain);
+
+    // Une hauteur annoncée sans en-tête servi n'est pas une chaîne vide à ajouter
+    let peers = vec![spawn_lying_node(Blockchain::new(1), 5).await];
+    let state = AppState::new(Blockchain::new(1));
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone());
+    assert!(matches!(
+        sync.run().await,
+        Err(SyncError::PeerFailed { .. })
+    ));
+    assert_eq!(state.chain.snapshot().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_competing_branch_is_adopted_without_ban() {
+    let mut common = Blockchain::new(1);
+    for i in 1..=2 {
+        common.add_block(format!("Block {}", i)).unwrap();
+    }
+    let mut local = common.clone();
+    local.add_block("Local 3".to_string()).unwrap();
+    let mut remote = common.clone();
+    for i in 3..=5 {
+        remote.add_block(format!("Remote {}", i)).unwrap();
+    }
+
+    let peers = vec![spawn_node(remote.clone()).await];
+    let state = AppState::new(local.clone());
+    let mut events = state.events.subscribe();
+    let manager = PeerManager::new(PeerConfig::default());
+    let sync = HeadersFirstSync::new(state.chain.clone(), &peers, state.sync.clone())
+        .with_peer_manager(manager.clone());
+
+    // Le bloc 3 local ne se rattache pas aux en-têtes du pair : la branche
+    // plus lourde du pair remplace la branche locale à partir du bloc 2
+    assert_eq!(sync.run().await.unwrap(), 5);
+    assert_eq!(state.chain.snapshot().chain, remote.chain);
+    assert!(manager.banned().is_empty());
+    let Ok(NodeEvent::Reorg {
+        fork_height,
+        old_tip,
+        new_tip,
+    }) = events.try_recv()
+    else {
+        panic!("expected a reorg event");
+    };
+    assert_eq!(fork_height, 2);
+    assert_eq!(old_tip, local.latest_block().hash);
+    assert!(remote
+        .chain
+        .iter()
+        .skip(3)
+        .any(|block| block.hash == new_tip));
+}
@@ -0,0 +1,74 @@
+// Tests de l'outil hors ligne `bc-tool`, lancé comme un processus sur des
+// fichiers de chaîne écrits dans le répertoire temporaire.
+
+use mini_blockchain::storage;
+use mini_blockchain::Blockchain;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "mini-blockchain-tool-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn bc_tool(data: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bc-tool"))
+        .arg("--data")
+        .arg(data)
+        .args(["--difficulty", "1"])
+        .args(args)
+        .env_remove("BLOCKCHAIN_DATA_DIR")
+        .env_remove("BLOCKCHAIN_CONSENSUS")
+        .env_remove("BLOCKCHAIN_POW_ALGORITHM")
+        .output()
+        .expect("bc-tool runs")
+}
+
+#[test]
+fn test_export_import_and_verify() {
+    let source = temp_path("source.json");
+    let export = temp_path("export.jsonl");
+    let copy = temp_path("copy.json");
+
+    let mut blockchain = Blockchain::new(1);
+    for i in 1..=3 {
+        blockchain.add_block(format!("Block {}", i)).unwrap();
+    }
+    storage::write_chain_file(&source, &blockchain).unwrap();
+
+    let verify = bc_tool(&source, &["verify"]);
+    assert_eq!(verify.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&verify.stdout).contains("OK: 4 blocks"));
+
+    // Export puis import dans une chaîne vierge
+    let exported = bc_tool(&source, &["export", "--out", export.to_str().unwrap()]);
+    assert!(exported.status.success());
+    let imported = bc_tool(&copy, &["import", export.to_str().unwrap()]);
+    assert!(
+        imported.status.success(),
+        "{}",
+        String::from_utf8_lossy(&imported.stderr)
+    );
+    let copied = storage::read_chain_file(&copy, 1).unwrap();
+    assert_eq!(copied.chain, blockchain.chain);
+    assert_eq!(bc_tool(&copy, &["verify"]).status.code(), Some(0));
+
+    // Un bloc altéré est signalé avec le code de sortie 2
+    let mut tampered = blockchain.clone();
+    let mut blocks: Vec<_> = tampered.chain.iter().cloned().collect();
+    blocks[2].data = "Tampered".to_string();
+    tampered.chain = blocks.into();
+    storage::write_chain_file(&source, &tampered).unwrap();
+    let verify = bc_tool(&source, &["verify"]);
+    assert_eq!(verify.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&verify.stdout).contains("position      2"));
+
+    for path in [source, export, copy] {
+        std::fs::remove_file(path).unwrap();
+    }
+}